@@ -1,42 +1,109 @@
 pub mod ai;
+mod assets;
+mod assist;
+mod audio;
 mod boxes;
 mod character;
+mod damage_number;
 mod deserialize;
+mod display;
+mod frame_advantage;
+mod gamepad;
 mod input;
+mod locale;
 mod net;
+mod perf;
 mod physics;
 mod projectile;
 mod render;
+mod replay;
 mod scene;
+mod settings;
 mod stage;
+mod transition;
+mod tuning;
+mod watcher;
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
+use candle_core::Device;
+use candle_nn::Sequential;
 use sdl3::{
-    EventPump,
+    EventPump, GamepadSubsystem,
     event::{Event, WindowEvent},
     keyboard::Keycode,
     pixels::Color,
-    render::{Canvas, Texture, TextureCreator},
+    render::{Canvas, FPoint, FRect, TextureCreator},
     video::{Window, WindowContext},
 };
 
 use crate::game::{
-    input::{
-        InputHistory, Inputs, PLAYER1_BUTTONS, PLAYER1_DIRECTIONS, PLAYER2_BUTTONS,
-        PLAYER2_DIRECTIONS,
+    audio::{Audio, SoundCue},
+    damage_number::DamageNumber,
+    display::{DisplayMode, DisplaySettings},
+    gamepad::Gamepads,
+    input::{InputHistory, Inputs},
+    locale::Strings,
+    perf::{FrameMetrics, PerfOverlay},
+    projectile::ClashSpark,
+    render::{
+        Camera, HeadlessTextureLoader, SdlTextureLoader, TextureLoader, TextureRegion,
+        animation::Animation,
+        text::{draw_text_centered, text_height},
+    },
+    scene::{
+        EscapeAction, Scene, Scenes, UiSkin,
+        gameplay::{HudLayout, MOVE_LIST_SHADE, WinIcons},
     },
-    render::{Camera, animation::Animation},
-    scene::{Scene, Scenes},
+    settings::{Controls, LastPlayed, Settings},
     stage::Stage,
+    transition::Transition,
+    tuning::Tuning,
+    watcher::{AssetWatcher, Changes},
 };
 
-const GAME_VERSION: &[u8] = "0.1.0".as_bytes();
+const GAME_VERSION: &[u8] = "0.2.0".as_bytes();
+/// Written whenever the settings menu saves
+const SETTINGS_PATH: &str = "./settings.json";
 
 const FRAME_RATE: usize = 60;
 const FRAME_DURATION: f64 = 1.0 / FRAME_RATE as f64;
+const FRAME_DURATION_NANOS: i128 = Duration::from_secs(1).as_nanos() as i128 / FRAME_RATE as i128;
 const SCORE_TO_WIN: u32 = 2;
 const MAX_ROLLBACK_FRAMES: usize = 64;
+/// How early a vsynced frame can be and still run a tick
+const VSYNC_SLACK_NANOS: i128 = 1_000_000;
+/// Flips drawing the collision, hurt, hit and throw boxes
+const SHOW_BOXES_KEY: Keycode = Keycode::F11;
+/// Flips the move list over local play
+const MOVE_LIST_KEY: Keycode = Keycode::F12;
+/// Start button, pauses local play like Escape without the way back to the menu, answers yes
+/// to the quit confirmation and plays on without an unplugged pad
+const PAUSE_KEY: Keycode = Keycode::Return;
+/// Stops training from ticking, or starts it again
+const FREEZE_KEY: Keycode = Keycode::Insert;
+/// Runs a single tick of frozen training
+const FRAME_STEP_KEY: Keycode = Keycode::PageDown;
+/// Cycles training between full, half and quarter speed
+const SLOW_MOTION_KEY: Keycode = Keycode::Home;
+/// Slow motion divisors `SLOW_MOTION_KEY` cycles through
+const SLOW_MOTION_STEPS: [i128; 3] = [1, 2, 4];
+/// Handled by the game itself, so they can't be bound to a player
+const RESERVED_KEYS: [Keycode; 7] = [
+    Keycode::Escape,
+    SHOW_BOXES_KEY,
+    MOVE_LIST_KEY,
+    PAUSE_KEY,
+    FREEZE_KEY,
+    FRAME_STEP_KEY,
+    SLOW_MOTION_KEY,
+];
+/// Game units a player can cover in one tick, anything further is a reset and isn't blended
+const INTERPOLATION_MAX_STEP: f32 = 60.0;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Side {
@@ -55,13 +122,56 @@ impl Side {
 
 pub struct GameContext {
     should_quit: bool,
+    /// Address of the matchmaking server online play goes through
     matchmaking_server: String,
+    /// Environment name -> address of every matchmaking server in the game config
+    matchmaking_servers: HashMap<String, String>,
     left_agent_filepath: String,
     right_agent_filepath: String,
-    main_menu_texture: usize,
+    character_agent_filepaths: HashMap<String, String>,
+    /// The left and right agents were picked in model select, so they play whatever the character
+    agents_picked: [bool; 2],
+    /// Small runtime net, preferred over the left agent when playing against the AI
+    distilled_agent_filepath: Option<String>,
+    /// Models model select already loaded by path, handed to the first AI scene playing them
+    picked_agents: HashMap<String, Sequential>,
+    /// Frames each AI action is held for, must match what the models were trained with
+    agent_action_repeat: usize,
+    /// Directory of training checkpoints fought in order by the arcade scene
+    ladder_dirpath: String,
+    /// Character config paths players can pick from
+    roster: Vec<String>,
+    /// (side, position) the left and right players start at
+    start_slots: [(Side, FPoint); 2],
+    /// Picks made in character select, loaded by the game before the next scene starts
+    pending_characters: Option<CharacterPicks>,
+    /// Config paths player1 and player2 were loaded from
+    character_configs: [String; 2],
+    /// Palettes player1 and player2 were loaded with, kept for hot reloads
+    character_palettes: [usize; 2],
+    settings: Settings,
+    /// Saved in the settings menu, applied by the game before the next scene starts
+    pending_settings: Option<Settings>,
+    /// UI text in the language picked in the settings
+    strings: Strings,
+    /// Languages with a string table, listed once on load for the settings menu
+    languages: Vec<String>,
+    /// Boxes are drawn over the fighters, starts on in debug builds
+    show_boxes: bool,
+    /// Both players' commands are listed over local play
+    show_move_list: bool,
+    main_menu_texture: TextureRegion,
+    /// Played in the menus, gameplay plays the stage's track
+    menu_music: Option<String>,
+    skin: UiSkin,
     round_start_animation: Animation,
-    timer_animation: Animation,
-    stage: Stage,
+    /// Counts down with the texture instead of the text digits
+    timer_animation: Option<Animation>,
+    hud: HudLayout,
+    win_icons: WinIcons,
+    stages: Vec<Stage>,
+    /// Index into `stages` of the one being fought on
+    stage: usize,
     player1: character::Context,
     player2: character::Context,
 
@@ -73,6 +183,88 @@ impl GameContext {
     pub fn should_quit(&self) -> bool {
         self.should_quit
     }
+
+    /// The left or right agent's model, the character's registered model unless one was picked
+    pub fn agent_filepath(&self, character: &character::Context, side: Side) -> &str {
+        let (filepath, picked) = match side {
+            Side::Left => (&self.left_agent_filepath, self.agents_picked[0]),
+            Side::Right => (&self.right_agent_filepath, self.agents_picked[1]),
+        };
+        if picked {
+            return filepath;
+        }
+        self.character_agent_filepaths
+            .get(character.name())
+            .unwrap_or(filepath)
+    }
+
+    /// The model at `filepath`, loaded once, either by model select or here
+    pub fn take_agent(&mut self, filepath: &str) -> Result<Sequential, String> {
+        match self.picked_agents.remove(filepath) {
+            Some(agent) => Ok(agent),
+            None => ai::load_inference_model(filepath, &Device::Cpu)
+                .map_err(|err| format!("Failed to load model '{filepath}': {err}")),
+        }
+    }
+
+    pub fn roster_len(&self) -> usize {
+        self.roster.len()
+    }
+
+    pub fn pick_characters(&mut self, picks: CharacterPicks) -> Result<(), String> {
+        if picks.player1 >= self.roster.len() || picks.player2 >= self.roster.len() {
+            return Err(format!("Character pick out of the roster: {picks:?}"));
+        }
+        self.pending_characters = Some(picks);
+        Ok(())
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    pub fn stage(&self) -> &Stage {
+        &self.stages[self.stage]
+    }
+
+    /// Stages are all loaded up front, so the pick applies right away
+    pub fn pick_stage(&mut self, stage: usize) -> Result<(), String> {
+        if stage >= self.stages.len() {
+            return Err(format!("Stage pick out of the stages: {stage}"));
+        }
+        self.stage = stage;
+        Ok(())
+    }
+
+    pub fn change_settings(&mut self, settings: Settings) {
+        self.pending_settings = Some(settings);
+    }
+
+    /// Model for the human vs AI scene, uses the distilled net when one has been made
+    pub fn verses_agent_filepath(&self, character: &character::Context) -> &str {
+        if self.agents_picked[0] {
+            return &self.left_agent_filepath;
+        }
+        let fallback = self
+            .distilled_agent_filepath
+            .as_deref()
+            .filter(|path| std::path::Path::new(path).is_file())
+            .unwrap_or(&self.left_agent_filepath);
+        self.character_agent_filepaths
+            .get(character.name())
+            .map(String::as_str)
+            .unwrap_or(fallback)
+    }
+}
+
+/// Roster indices chosen for each player, and the side player1 starts on
+#[derive(Clone, Copy, Debug)]
+pub struct CharacterPicks {
+    pub player1: usize,
+    pub player2: usize,
+    pub player1_palette: usize,
+    pub player2_palette: usize,
+    pub player1_side: Side,
 }
 
 #[derive(Clone, PartialEq)]
@@ -81,20 +273,51 @@ pub struct GameState {
     player2_inputs: Inputs,
     player1: character::State,
     player2: character::State,
+    clash_sparks: Vec<ClashSpark>,
+    damage_numbers: Vec<DamageNumber>,
+    /// Made by the latest game frame, the game plays them after the tick
+    sounds: Vec<SoundCue>,
 }
 
 impl GameState {
     pub fn reset(&mut self, context: &GameContext) {
         self.player1.reset(&context.player1);
         self.player2.reset(&context.player2);
+        self.clash_sparks.clear();
+        self.damage_numbers.clear();
+        self.sounds.clear();
         self.player1_inputs.reset();
         self.player2_inputs.reset();
     }
+
+    /// Copy with the players `alpha` of the way from where they were in `previous`
+    fn interpolated(&self, previous: &GameState, alpha: f32) -> GameState {
+        let lerp = |from: FPoint, to: FPoint| {
+            if (to.x - from.x).abs() > INTERPOLATION_MAX_STEP
+                || (to.y - from.y).abs() > INTERPOLATION_MAX_STEP
+            {
+                return to;
+            }
+            FPoint::new(
+                from.x + (to.x - from.x) * alpha,
+                from.y + (to.y - from.y) * alpha,
+            )
+        };
+        let mut shown = self.clone();
+        shown
+            .player1
+            .set_pos(lerp(previous.player1.pos(), self.player1.pos()));
+        shown
+            .player2
+            .set_pos(lerp(previous.player2.pos(), self.player2.pos()));
+        shown
+    }
 }
 
 pub struct PlayerInputs {
     player1: InputHistory,
     player2: InputHistory,
+    controls: Controls,
 }
 
 impl PlayerInputs {
@@ -123,13 +346,18 @@ impl PlayerInputs {
     }
 
     pub fn online_key_mapping(&mut self) {
-        self.player2
-            .set_mappings(PLAYER1_BUTTONS, PLAYER1_DIRECTIONS);
+        self.player2.set_mappings(self.controls.0[0]);
     }
 
     pub fn local_key_mapping(&mut self) {
-        self.player2
-            .set_mappings(PLAYER2_BUTTONS, PLAYER2_DIRECTIONS);
+        self.player2.set_mappings(self.controls.0[1]);
+    }
+
+    /// Rebinds both players, the local mapping is picked back up for player2
+    pub fn set_controls(&mut self, controls: Controls) {
+        self.controls = controls;
+        self.player1.set_mappings(controls.0[0]);
+        self.local_key_mapping();
     }
 
     pub fn set_delay(&mut self, delay: usize) {
@@ -138,65 +366,225 @@ impl PlayerInputs {
     }
 }
 
+/// Runs the AI trainer without creating a window or renderer
+pub fn train_headless(
+    config: &str,
+    screen_dim: (u32, u32),
+    mode: ai::TrainingMode,
+    dqn_variant: ai::DQNVariant,
+) -> Result<(), String> {
+    let (context, mut state, mut inputs) = headless_simulation(config, screen_dim)?;
+    ai::train(&context, &mut inputs, &mut state, mode, dqn_variant).map_err(|err| err.to_string())
+}
+
+/// Loads a character config without a window, Ok with its warnings if it loads
+pub fn check_character(config: &str) -> Result<Vec<String>, String> {
+    deserialize::check_character(config)
+}
+
+/// Writes a starter character config and sprite for modders to build on, returns the config's path
+pub fn new_character(name: &str) -> Result<String, String> {
+    deserialize::create_character(name)
+}
+
+/// Plays two models against each other without a window and prints the results
+pub fn benchmark_headless(
+    config: &str,
+    screen_dim: (u32, u32),
+    model1_path: &str,
+    model2_path: &str,
+    rounds: usize,
+) -> Result<(), String> {
+    let (context, mut state, mut inputs) = headless_simulation(config, screen_dim)?;
+    ai::benchmark(
+        &context,
+        &mut inputs,
+        &mut state,
+        model1_path,
+        model2_path,
+        rounds,
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn headless_simulation(
+    config: &str,
+    screen_dim: (u32, u32),
+) -> Result<(GameContext, GameState, PlayerInputs), String> {
+    let mut loader = HeadlessTextureLoader::default();
+    deserialize::deserialize_simulation(&mut loader, screen_dim, config)
+        .map_err(|err| err.to_string())
+}
+
+/// Overrides picked on the command line, none of them are saved to the settings
+pub struct LaunchOptions {
+    /// Game config to load everything from
+    pub config: String,
+    pub fullscreen: bool,
+    /// Window size, the settings' resolution otherwise
+    pub resolution: Option<(u32, u32)>,
+    /// Replay file to start playing back
+    pub replay: Option<PathBuf>,
+    /// Matchmaking server to start online play on
+    pub matchmaking_server: Option<String>,
+    /// Matchmaking environment of the game config to play online through
+    pub matchmaking: Option<String>,
+    /// Zip archive to read assets out of, needs the `archive` feature
+    pub archive: Option<String>,
+}
+
 pub struct Game<'a> {
     context: GameContext,
     state: GameState,
+    /// State before the latest tick, rendering blends from it towards `state`
+    previous_state: GameState,
     scene: Scenes,
     inputs: PlayerInputs,
 
     // Window management / render
-    global_textures: Vec<Texture<'a>>,
+    texture_loader: SdlTextureLoader<'a>,
     canvas: Canvas<Window>,
     events: EventPump,
-    _texture_creator: &'a TextureCreator<WindowContext>,
+
+    /// Game config everything was loaded from, reread when it is edited
+    config: String,
+    /// Edited character configs reload in local play, other configs and images in debug builds
+    watcher: Option<AssetWatcher>,
+    /// Move balancing overlay, only in debug builds
+    tuning: Tuning,
+    /// Frame timings overlay, only in debug builds
+    perf: PerfOverlay,
+    audio: Audio,
+    /// Index into `SLOW_MOTION_STEPS` of training's speed
+    slow_motion: usize,
+    /// Training only ticks on the frame step key
+    frozen: bool,
+    /// Ticks of frozen training queued by the frame step key
+    frame_steps: usize,
+    /// Prompt of the quit confirmation while it's up
+    confirming: Option<&'static str>,
+    /// Picked by Escape, swapped in on the next tick like a scene's own change
+    escaped_to: Option<Scenes>,
+    gamepads: Gamepads,
+    /// Fade between the scene and the next, if one is playing
+    transition: Option<Transition>,
 }
 
 impl<'a> Game<'a> {
-    /// Maybe this could also be from a config file? ///
     pub fn init(
         texture_creator: &'a TextureCreator<WindowContext>,
         canvas: Canvas<Window>,
         events: EventPump,
+        gamepads: GamepadSubsystem,
         screen_dim: (u32, u32),
+        options: LaunchOptions,
     ) -> Self {
-        deserialize::deserialize(
+        let assets =
+            assets::open(options.archive.as_deref()).expect("Failed to open asset archive");
+        let mut game = deserialize::deserialize(
             texture_creator,
             canvas,
             events,
+            gamepads,
             screen_dim,
-            "./resources/config.json",
+            &options.config,
+            assets,
         )
-        .expect("Failed to deserialize game config")
+        .expect("Failed to deserialize game config");
+        game.launch(options)
+            .expect("Failed to apply launch options");
+        game
     }
 
-    pub fn run(mut self) {
-        if cfg!(feature = "train_agents") {
-            ai::train(&self.context, &mut self.inputs, &mut self.state)
-                .expect("Failed to train AI");
-            panic!("Done training");
+    /// Applies the command line overrides on top of the saved settings
+    fn launch(&mut self, options: LaunchOptions) -> Result<(), String> {
+        if options.fullscreen || options.resolution.is_some() {
+            let saved = self.context.settings.display;
+            let mut display = saved;
+            if options.fullscreen {
+                display.mode = DisplayMode::Fullscreen;
+            }
+            if let Some(resolution) = options.resolution {
+                display.resolution = resolution;
+            }
+            self.apply_display(display)?;
+            // Kept out of the settings file, the settings menu still shows what was saved
+            self.context.settings.display = saved;
+        }
+        if let Some(environment) = options.matchmaking {
+            self.context.matchmaking_server = self
+                .context
+                .matchmaking_servers
+                .get(&environment)
+                .cloned()
+                .ok_or_else(|| {
+                    let mut environments: Vec<_> =
+                        self.context.matchmaking_servers.keys().collect();
+                    environments.sort();
+                    format!(
+                        "No matchmaking environment '{environment}' in '{}', it has {environments:?}",
+                        self.config
+                    )
+                })?;
+        }
+        if let Some(server) = options.matchmaking_server {
+            self.context.matchmaking_server = server;
+            self.scene = Scenes::online_select();
         }
+        if let Some(path) = options.replay {
+            self.scene = Scenes::replay(&mut self.context, path)?;
+            self.load_picked_characters()?;
+        }
+        Ok(())
+    }
 
+    pub fn run(mut self) {
         // Enter starting scene
         self.scene
             .enter(&self.context, &mut self.inputs, &mut self.state);
+        self.play_scene_music();
 
         let mut last_frame = Instant::now();
-        let mut lag = 0;
+        // Signed since vsynced frames can run a tick slightly early
+        let mut lag: i128 = 0;
         while !self.context.should_quit {
             let frame_start = Instant::now();
-            lag += frame_start
+            let elapsed = frame_start
                 .checked_duration_since(last_frame)
                 .unwrap_or(Duration::ZERO)
-                .as_nanos();
+                .as_nanos() as i128;
+            // Training is slowed by feeding the accumulator less time, frozen it gets none at all
+            lag += if self.held_up() {
+                0
+            } else if !self.scene.training() {
+                elapsed
+            } else if self.frozen {
+                0
+            } else {
+                elapsed / SLOW_MOTION_STEPS[self.slow_motion]
+            };
 
             self.input();
+            if self.scene.training() {
+                lag += std::mem::take(&mut self.frame_steps) as i128 * FRAME_DURATION_NANOS;
+            }
 
-            const FRAME_DURATION_NANOS: u128 =
-                std::time::Duration::from_secs(1).as_nanos() / FRAME_RATE as u128;
-            while lag >= FRAME_DURATION_NANOS {
+            // Presents land a little either side of the tick, the lag keeps counting real time so
+            // a display slightly off 60Hz only drops or doubles a tick once the drift adds up to one
+            let tick_threshold = if self.context.settings.display.vsync {
+                FRAME_DURATION_NANOS - VSYNC_SLACK_NANOS
+            } else {
+                FRAME_DURATION_NANOS
+            };
+            let update_start = Instant::now();
+            while lag >= tick_threshold {
+                self.previous_state.clone_from(&self.state);
                 if let Err(err) = self.update() {
-                    self.scene.exit(&self.context, &mut self.inputs, &mut self.state);
+                    self.scene
+                        .exit(&self.context, &mut self.inputs, &mut self.state);
                     self.scene = Scenes::reset(&self.context, &mut self.inputs, &mut self.state);
+                    self.play_scene_music();
+                    self.transition = None;
 
                     if cfg!(feature = "debug") {
                         println!("[WARNING] Error on scene update: {err}");
@@ -205,12 +593,29 @@ impl<'a> Game<'a> {
                 lag -= FRAME_DURATION_NANOS;
             }
 
-            self.render();
+            let update_time = update_start.elapsed();
+
+            let render_start = Instant::now();
+            // Drawn a tick behind, as far towards the latest one as the leftover time reaches
+            let alpha = (lag as f32 / FRAME_DURATION_NANOS as f32).clamp(0.0, 1.0);
+            self.render(alpha);
+            if cfg!(feature = "debug") {
+                self.perf.record_frame(FrameMetrics {
+                    lag,
+                    update_time,
+                    render_time: render_start.elapsed(),
+                    rolled_back: self.scene.rolled_back_frames(),
+                    texture_count: self.texture_loader.textures().len(),
+                });
+            }
 
             last_frame = frame_start;
-            spin_sleep::sleep(
-                Duration::from_secs_f64(FRAME_DURATION).saturating_sub(frame_start.elapsed()),
-            );
+            // With vsync presenting already waits for the display
+            if !self.context.settings.display.vsync {
+                spin_sleep::sleep(
+                    Duration::from_secs_f64(FRAME_DURATION).saturating_sub(frame_start.elapsed()),
+                );
+            }
         }
 
         self.scene
@@ -226,9 +631,17 @@ impl<'a> Game<'a> {
                     repeat: false,
                     ..
                 } => {
-                    self.scene
-                        .exit(&self.context, &mut self.inputs, &mut self.state);
-                    self.scene = Scenes::reset(&self.context, &mut self.inputs, &mut self.state);
+                    // Mid transition the scene Escape would act on is on its way out
+                    if self.confirming.take().is_some() || self.transition.is_some() {
+                        continue;
+                    }
+                    match self.scene.escape() {
+                        EscapeAction::Pause => {
+                            self.scene.toggle_pause();
+                        }
+                        EscapeAction::Back(scene) => self.escaped_to = Some(scene),
+                        EscapeAction::Confirm(prompt) => self.confirming = Some(prompt),
+                    }
                 }
                 Event::Window {
                     win_event: WindowEvent::Resized(x, y),
@@ -241,8 +654,46 @@ impl<'a> Game<'a> {
                     repeat: false,
                     ..
                 } => {
+                    if !RESERVED_KEYS.contains(&keycode) && self.scene.capture_key(keycode) {
+                        continue;
+                    }
                     self.inputs.player1.handle_keypress(keycode);
                     self.inputs.player2.handle_keypress(keycode);
+                    if keycode == SHOW_BOXES_KEY {
+                        self.context.show_boxes = !self.context.show_boxes;
+                    }
+                    if keycode == MOVE_LIST_KEY {
+                        self.context.show_move_list = !self.context.show_move_list;
+                    }
+                    if keycode == PAUSE_KEY && self.confirming.take().is_some() {
+                        // The main menu quits the game, anywhere else goes back to it
+                        match self.scene {
+                            Scenes::MainMenu(_) => self.context.should_quit = true,
+                            _ => self.escaped_to = Some(Scenes::new()),
+                        }
+                    } else if keycode == PAUSE_KEY
+                        && self.scene.in_match()
+                        && self.gamepads.disconnected().is_some()
+                    {
+                        self.gamepads.forget_disconnected();
+                    } else if keycode == PAUSE_KEY {
+                        self.scene.toggle_pause();
+                    }
+                    if keycode == FREEZE_KEY {
+                        self.frozen = !self.frozen;
+                    }
+                    if keycode == FRAME_STEP_KEY && self.frozen {
+                        self.frame_steps += 1;
+                    }
+                    if keycode == SLOW_MOTION_KEY {
+                        self.slow_motion = (self.slow_motion + 1) % SLOW_MOTION_STEPS.len();
+                    }
+                    if cfg!(feature = "debug") && self.scene.hot_reloads_characters() {
+                        self.tuning.handle_keypress(keycode, &mut self.context);
+                    }
+                    if cfg!(feature = "debug") {
+                        self.perf.handle_keypress(keycode);
+                    }
                 }
                 Event::KeyUp {
                     keycode: Some(keycode),
@@ -252,15 +703,83 @@ impl<'a> Game<'a> {
                     self.inputs.player1.handle_keyrelease(keycode);
                     self.inputs.player2.handle_keyrelease(keycode);
                 }
+                Event::ControllerDeviceAdded { which, .. } => {
+                    if let Some(player) = self.gamepads.connect(which)
+                        && cfg!(feature = "debug")
+                    {
+                        println!("Gamepad {which} plays for player {}", player + 1);
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    // Whatever the pad was holding would stay held until it came back
+                    if let Some(player) = self.gamepads.disconnect(which) {
+                        for key in self.context.settings.controls.0[player].keys() {
+                            self.inputs.player1.handle_keyrelease(key);
+                            self.inputs.player2.handle_keyrelease(key);
+                        }
+                    }
+                }
+                Event::ControllerButtonDown { which, button, .. } => {
+                    if let Some(player) = self.gamepads.player(which)
+                        && let Some(key) = self
+                            .gamepads
+                            .button_key(button, &self.context.settings.controls.0[player])
+                    {
+                        self.inputs.player1.handle_keypress(key);
+                        self.inputs.player2.handle_keypress(key);
+                    }
+                }
+                Event::ControllerButtonUp { which, button, .. } => {
+                    if let Some(player) = self.gamepads.player(which)
+                        && let Some(key) = self
+                            .gamepads
+                            .button_key(button, &self.context.settings.controls.0[player])
+                    {
+                        self.inputs.player1.handle_keyrelease(key);
+                        self.inputs.player2.handle_keyrelease(key);
+                    }
+                }
+                Event::ControllerAxisMotion {
+                    which, axis, value, ..
+                } => {
+                    let Some(player) = self.gamepads.player(which) else {
+                        continue;
+                    };
+                    let bindings = &self.context.settings.controls.0[player];
+                    for (key, pressed) in self.gamepads.stick_keys(player, axis, value, bindings) {
+                        if pressed {
+                            self.inputs.player1.handle_keypress(key);
+                            self.inputs.player2.handle_keypress(key);
+                        } else {
+                            self.inputs.player1.handle_keyrelease(key);
+                            self.inputs.player2.handle_keyrelease(key);
+                        }
+                    }
+                }
                 _ => {}
             }
         }
     }
 
     fn update(&mut self) -> Result<(), String> {
+        if let Some(transition) = &mut self.transition {
+            let fading_out = transition.fading_out();
+            let next_scene = transition.update();
+            if transition.finished() {
+                self.transition = None;
+            }
+            // The old scene holds still while it fades out
+            if fading_out {
+                if let Some(next_scene) = next_scene {
+                    self.change_scene(next_scene)?;
+                }
+                self.audio.update();
+                return Ok(());
+            }
+        }
+
         // Handle inputs
-        self
-            .scene
+        self.scene
             .handle_input(&self.context, &mut self.inputs, &mut self.state)?;
 
         self.state.player1_inputs.update(
@@ -272,29 +791,400 @@ impl<'a> Game<'a> {
             self.inputs.player2.parse_history(),
         );
 
-        if let Some(mut new_scene) = self.scene.update(&self.context, &mut self.state)? {
-            self.scene
-                .exit(&self.context, &mut self.inputs, &mut self.state);
-            new_scene.enter(&self.context, &mut self.inputs, &mut self.state);
-            self.scene = new_scene;
+        let new_scene = match self.escaped_to.take() {
+            Some(escaped_to) => Some(escaped_to),
+            None => self.scene.update(&mut self.context, &mut self.state)?,
+        };
+        // Before a scene change can load other characters, the cues index into these ones
+        self.play_sounds();
+        if let Some(new_scene) = new_scene {
+            // Online play starts on the same tick as the peer's, so it cuts in and only fades in
+            if new_scene.online() {
+                self.change_scene(new_scene)?;
+                self.transition = Some(Transition::fade_in());
+            } else {
+                self.transition = Some(Transition::fade_to(new_scene));
+            }
+        }
+        self.audio.set_ducked(self.scene.ducks_music(&self.state));
+        self.audio.update();
+
+        if let Some(watcher) = &mut self.watcher {
+            let changes = watcher.changes(&self.context.character_configs);
+            self.hot_reload(changes);
         }
 
         Ok(())
     }
 
-    fn render(&mut self) {
+    fn change_scene(&mut self, mut new_scene: Scenes) -> Result<(), String> {
+        self.scene
+            .exit(&self.context, &mut self.inputs, &mut self.state);
+        self.load_picked_characters()?;
+        self.apply_pending_settings()?;
+        new_scene.enter(&self.context, &mut self.inputs, &mut self.state);
+        self.scene = new_scene;
+        self.play_scene_music();
+        if self.scene.in_match()
+            && let Err(err) = self.remember_last_played()
+            && cfg!(feature = "debug")
+        {
+            println!("[WARNING] Failed to save last played: {err}");
+        }
+        // Every session of training starts back at full speed
+        self.slow_motion = 0;
+        self.frozen = false;
+        self.frame_steps = 0;
+        self.confirming = None;
+        Ok(())
+    }
+
+    /// `alpha` is how far the players are drawn from the previous tick to the latest one
+    fn render(&mut self, alpha: f32) {
         self.canvas.set_draw_color(Color::BLACK);
         self.canvas.clear();
 
+        let shown = self.state.interpolated(&self.previous_state, alpha);
         self.scene
             .render(
                 &mut self.canvas,
-                &self.global_textures,
+                self.texture_loader.textures(),
                 &self.context,
-                &self.state,
+                &shown,
             )
             .expect("Failed to render scene");
+        if let Some(transition) = &self.transition {
+            transition
+                .render(&mut self.canvas)
+                .expect("Failed to render scene transition");
+        }
+        if self.scene.training() {
+            self.render_training_speed()
+                .expect("Failed to render training speed");
+        }
+        if let Some(prompt) = self.confirming {
+            self.render_prompt(prompt, "Enter: Yes   Escape: No")
+                .expect("Failed to render quit confirmation");
+        } else if let Some(player) = self.gamepads.disconnected()
+            && self.scene.in_match()
+        {
+            self.render_prompt(
+                &self
+                    .context
+                    .strings
+                    .fill("Player {} controller disconnected", &[&(player + 1)]),
+                "Reconnect it, or Enter: Play on without it",
+            )
+            .expect("Failed to render controller prompt");
+        }
+        if cfg!(feature = "debug") && self.scene.hot_reloads_characters() {
+            self.tuning
+                .render(&mut self.canvas, &self.context)
+                .expect("Failed to render tuning overlay");
+        }
+        if cfg!(feature = "debug") {
+            self.perf
+                .render(&mut self.canvas)
+                .expect("Failed to render perf overlay");
+        }
 
         self.canvas.present();
     }
+
+    /// Labels training when it's frozen or slowed, full speed shows nothing
+    fn render_training_speed(&mut self) -> Result<(), sdl3::Error> {
+        let label = if self.frozen {
+            "FROZEN   PageDown: Step"
+        } else {
+            match SLOW_MOTION_STEPS[self.slow_motion] {
+                1 => return Ok(()),
+                2 => "SPEED 50%",
+                _ => "SPEED 25%",
+            }
+        };
+        let label = self.context.strings.get(label).to_string();
+        let (w, h) = self.canvas.window().size();
+        let (w, h) = (w as f32, h as f32);
+        let scale = h / 250.0;
+        draw_text_centered(
+            &mut self.canvas,
+            &label,
+            w / 2.0,
+            h - text_height(scale) * 3.0,
+            scale,
+            Color::YELLOW,
+        )
+    }
+
+    /// Offline matches stop ticking while a prompt waits on an answer, online ones can't
+    fn held_up(&self) -> bool {
+        let waiting = self.confirming.is_some()
+            || (self.scene.in_match() && self.gamepads.disconnected().is_some());
+        // A picked way out still needs ticks to be taken and faded through
+        waiting && !self.scene.online() && self.escaped_to.is_none() && self.transition.is_none()
+    }
+
+    /// Shades the scene under the prompt and its answers, both are translated here
+    fn render_prompt(&mut self, prompt: &str, answers: &str) -> Result<(), sdl3::Error> {
+        let strings = &self.context.strings;
+        let (prompt, answers) = (
+            strings.get(prompt).to_string(),
+            strings.get(answers).to_string(),
+        );
+        let (w, h) = self.canvas.window().size();
+        let (w, h) = (w as f32, h as f32);
+        self.canvas.set_blend_mode(sdl3::render::BlendMode::Blend);
+        self.canvas.set_draw_color(MOVE_LIST_SHADE);
+        self.canvas.fill_rect(FRect::new(0.0, 0.0, w, h))?;
+        self.canvas.set_blend_mode(sdl3::render::BlendMode::None);
+
+        let scale = h / 200.0;
+        draw_text_centered(
+            &mut self.canvas,
+            &prompt,
+            w / 2.0,
+            h * 0.45,
+            scale,
+            Color::WHITE,
+        )?;
+        draw_text_centered(
+            &mut self.canvas,
+            &answers,
+            w / 2.0,
+            h * 0.45 + text_height(scale) * 2.5,
+            scale,
+            Color::GRAY,
+        )
+    }
+
+    /// Crossfades to the new scene's track, scenes sharing a track keep it going
+    fn play_scene_music(&mut self) {
+        self.audio.play_music(self.scene.music(&self.context));
+    }
+
+    fn play_sounds(&mut self) {
+        for cue in std::mem::take(&mut self.state.sounds) {
+            let character = match cue.player {
+                0 => &self.context.player1,
+                _ => &self.context.player2,
+            };
+            self.audio.play_sound(character.sound(cue.sound), cue.pan);
+        }
+    }
+
+    fn apply_pending_settings(&mut self) -> Result<(), String> {
+        let Some(mut settings) = self.context.pending_settings.take() else {
+            return Ok(());
+        };
+        // Not something the settings menu changes
+        settings.last_played = self.context.settings.last_played;
+        // Re-applying an unchanged display still resizes the window
+        if settings.display != self.context.settings.display {
+            self.apply_display(settings.display)?;
+        }
+        let saved = settings.save(SETTINGS_PATH);
+        self.apply_settings(settings);
+        saved
+    }
+
+    /// Everything but the display, which goes through `apply_display`
+    fn apply_settings(&mut self, settings: Settings) {
+        self.audio
+            .set_volumes(settings.audio.music(), settings.audio.effects());
+        self.inputs.set_controls(settings.controls);
+        if settings.language != self.context.strings.language() {
+            self.context.strings = Strings::load(self.texture_loader.assets(), &settings.language);
+        }
+        self.context.settings = settings;
+    }
+
+    fn apply_display(&mut self, settings: DisplaySettings) -> Result<(), String> {
+        settings.apply(self.canvas.window_mut())?;
+        self.canvas
+            .set_vsync(settings.vsync as i32)
+            .map_err(|err| err.to_string())?;
+        self.texture_loader
+            .set_scale_mode(settings.filter.scale_mode());
+        self.context.settings.display = settings;
+        self.context
+            .camera
+            .set_pixel_perfect(settings.pixel_perfect);
+        self.context.camera.resize(self.canvas.window().size());
+        Ok(())
+    }
+
+    /// Starts on the characters and stage of the last run's latest match
+    fn pick_last_played(&mut self) -> Result<(), String> {
+        let last_played = self.context.settings.last_played;
+        let Some([player1, player2]) = last_played.characters else {
+            return Ok(());
+        };
+        self.context.pick_stage(last_played.stage)?;
+        self.context.pick_characters(CharacterPicks {
+            player1,
+            player2,
+            player1_palette: last_played.palettes[0],
+            player2_palette: last_played.palettes[1],
+            player1_side: Side::Left,
+        })?;
+        self.load_picked_characters()
+    }
+
+    fn remember_last_played(&mut self) -> Result<(), String> {
+        let roster_index =
+            |config: &String| self.context.roster.iter().position(|path| path == config);
+        let [player1, player2] = &self.context.character_configs;
+        let last_played = LastPlayed {
+            characters: roster_index(player1)
+                .zip(roster_index(player2))
+                .map(|(player1, player2)| [player1, player2]),
+            palettes: self.context.character_palettes,
+            stage: self.context.stage,
+        };
+        if last_played == self.context.settings.last_played {
+            return Ok(());
+        }
+        self.context.settings.last_played = last_played;
+        self.context.settings.save(SETTINGS_PATH)
+    }
+
+    /// Characters are only deserialized once they have been picked
+    fn load_picked_characters(&mut self) -> Result<(), String> {
+        let Some(picks) = self.context.pending_characters.take() else {
+            return Ok(());
+        };
+        let [left, right] = self.context.start_slots;
+        let (player1_start, player2_start) = match picks.player1_side {
+            Side::Left => (left, right),
+            Side::Right => (right, left),
+        };
+
+        let (player1_context, player1_state) = deserialize::deserialize_character(
+            &mut self.texture_loader,
+            &self.context.roster[picks.player1],
+            player1_start.0,
+            player1_start.1,
+            picks.player1_palette,
+            None,
+        )?;
+        let (player2_context, player2_state) = deserialize::deserialize_character(
+            &mut self.texture_loader,
+            &self.context.roster[picks.player2],
+            player2_start.0,
+            player2_start.1,
+            picks.player2_palette,
+            // Mirror matches never share colors
+            (picks.player1 == picks.player2).then_some(picks.player1_palette),
+        )?;
+
+        self.context.player1 = player1_context;
+        self.context.player2 = player2_context;
+        self.state.player1 = player1_state;
+        self.state.player2 = player2_state;
+        self.context.character_configs = [
+            self.context.roster[picks.player1].clone(),
+            self.context.roster[picks.player2].clone(),
+        ];
+        self.context.character_palettes = [picks.player1_palette, picks.player2_palette];
+        Ok(())
+    }
+
+    /// Player1's palette when player2 plays the same character
+    fn mirror_palette(&self, player: usize) -> Option<usize> {
+        let [player1_config, player2_config] = &self.context.character_configs;
+        (player == 1 && player1_config == player2_config)
+            .then_some(self.context.character_palettes[0])
+    }
+
+    /// Half saved files fail to load, what was there keeps going until the next save
+    ///
+    /// Characters reload in local play in every build, textures and the game config only in
+    /// debug builds
+    fn hot_reload(&mut self, changes: Changes) {
+        for player in 0..2 {
+            if changes.characters[player]
+                && self.scene.hot_reloads_characters()
+                && let Err(err) = self.hot_reload_character(player)
+                && cfg!(feature = "debug")
+            {
+                println!("[WARNING] Failed to hot reload character: {err}");
+            }
+        }
+        if !cfg!(feature = "debug") {
+            return;
+        }
+        for path in &changes.textures {
+            match self.texture_loader.reload(path) {
+                Ok(true) => println!("Hot reloaded '{}'", path.display()),
+                Ok(false) => {}
+                Err(err) => println!("[WARNING] Failed to hot reload texture: {err}"),
+            }
+        }
+        // Online the other side would play on the old data and desync
+        if changes.game_config && !self.scene.online() {
+            match self.reload_game_config() {
+                Ok(()) => println!("Hot reloaded '{}'", self.config),
+                Err(err) => println!("[WARNING] Failed to hot reload game config: {err}"),
+            }
+        }
+    }
+
+    /// Swaps in the menus, HUD, stages and AI paths of the edited game config, the fight goes on
+    fn reload_game_config(&mut self) -> Result<(), String> {
+        let (context, _, _) = deserialize::deserialize_simulation(
+            &mut self.texture_loader,
+            self.canvas.window().size(),
+            &self.config,
+        )
+        .map_err(|err| err.to_string())?;
+        let old = &mut self.context;
+        // Models picked in model select outlive the reload, along with the ones it already loaded
+        if !old.agents_picked[0] {
+            old.left_agent_filepath = context.left_agent_filepath;
+            old.distilled_agent_filepath = context.distilled_agent_filepath;
+        }
+        if !old.agents_picked[1] {
+            old.right_agent_filepath = context.right_agent_filepath;
+        }
+        old.character_agent_filepaths = context.character_agent_filepaths;
+        old.agent_action_repeat = context.agent_action_repeat;
+        old.ladder_dirpath = context.ladder_dirpath;
+        old.roster = context.roster;
+        old.start_slots = context.start_slots;
+        old.main_menu_texture = context.main_menu_texture;
+        old.menu_music = context.menu_music;
+        old.skin = context.skin;
+        old.round_start_animation = context.round_start_animation;
+        old.timer_animation = context.timer_animation;
+        old.hud = context.hud;
+        old.win_icons = context.win_icons;
+        old.stage = old.stage.min(context.stages.len() - 1);
+        old.stages = context.stages;
+        Ok(())
+    }
+
+    /// Re-deserializes the player's character, keeping where they stood
+    fn hot_reload_character(&mut self, player: usize) -> Result<(), String> {
+        let config = &self.context.character_configs[player];
+        let mirror_of = self.mirror_palette(player);
+        let (character_context, character_state) = match player {
+            0 => (&mut self.context.player1, &mut self.state.player1),
+            _ => (&mut self.context.player2, &mut self.state.player2),
+        };
+        let (new_context, new_state) = deserialize::deserialize_character(
+            &mut self.texture_loader,
+            config,
+            character_state.side(),
+            character_state.pos(),
+            self.context.character_palettes[player],
+            mirror_of,
+        )?;
+        *character_context = new_context;
+        *character_state = new_state;
+
+        if cfg!(feature = "debug") {
+            println!("Hot reloaded '{config}'");
+        }
+        Ok(())
+    }
 }
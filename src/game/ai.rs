@@ -1,7 +1,8 @@
-use std::time::Instant;
+use std::{collections::HashMap, time::Instant};
 
-use candle_core::{Device, Result, Tensor};
-use candle_nn::{Sequential, VarMap};
+use candle_core::{D, DType, Device, Result, Tensor};
+use candle_nn::{Sequential, VarBuilder, VarMap, seq};
+use clap::ValueEnum;
 use rand::rngs::ThreadRng;
 
 use crate::game::{
@@ -10,16 +11,40 @@ use crate::game::{
     input::{ButtonFlag, Direction, InputHistory, Inputs},
 };
 
+mod benchmark;
+mod distill;
 mod dqn;
 mod env;
 mod ppo;
 mod training;
 
+pub use dqn::DQNVariant;
+
+/// Which trainer `train` runs
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum TrainingMode {
+    #[default]
+    TrainerPool,
+    /// League of reward styles instead of a single challenger lineage
+    League,
+    /// Shrink the best trainer pool policy into the small runtime net
+    Distill,
+    /// Value based self play, with the improvements picked by `DQNVariant`
+    Dqn,
+}
+
 // Environment
 type PlayerSerial = [f32; PLAYER_STATE_LEN];
-const PLAYER_STATE_LEN: usize = 37;
-const STATE_VECTOR_LEN: usize = PLAYER_STATE_LEN * 2 + 3;
+const PLAYER_STATE_LEN: usize = 46;
+/// Per player features from before frame data was added
+const PLAYER_BASE_STATE_LEN: usize = 37;
+/// Per player features from before buffs were added
+const PLAYER_FRAME_DATA_STATE_LEN: usize = 43;
+const GLOBAL_STATE_LEN: usize = 3;
+const STATE_VECTOR_LEN: usize = PLAYER_STATE_LEN * 2 + GLOBAL_STATE_LEN;
 const ACTION_SPACE: usize = 9 * 8;
+/// Numpad 5, no buttons
+const NEUTRAL_ACTION: Action = 4;
 
 type Action = u32;
 #[derive(Clone, Copy)]
@@ -34,30 +59,206 @@ struct DuelFloat {
     agent2: f32,
 }
 
+/// Holds an agent's action for a fixed number of frames before it picks a new one
+pub struct ActionRepeat {
+    repeat: usize,
+    frames_left: usize,
+    action: Action,
+}
+
+impl ActionRepeat {
+    pub fn new(repeat: usize) -> Self {
+        Self {
+            repeat: repeat.max(1),
+            frames_left: 0,
+            action: NEUTRAL_ACTION,
+        }
+    }
+
+    /// True when the held action has run out
+    pub fn ready(&self) -> bool {
+        self.frames_left == 0
+    }
+
+    pub fn set(&mut self, action: Action) {
+        self.action = action;
+        self.frames_left = self.repeat;
+    }
+
+    /// Uses up one frame of the held action
+    pub fn tick(&mut self) -> Action {
+        self.frames_left = self.frames_left.saturating_sub(1);
+        self.action
+    }
+}
+
 /// Interface used for current AI implementation
 pub fn get_agent_action(agent: &Sequential, obs: &Tensor, rng: &mut ThreadRng) -> Result<u32> {
     ppo::get_agent_action(agent, obs, rng)
 }
 
+/// Samples one action per row of a (batch, STATE_VECTOR_LEN) observation tensor
+pub fn sample_agent_actions(
+    agent: &Sequential,
+    batch: &Tensor,
+    rng: &mut ThreadRng,
+    actions: &mut [Action],
+) -> Result<()> {
+    ppo::sample_agent_actions(agent, batch, rng, actions)
+}
+
+/// Both players' observations, reused every frame so AI scenes don't rebuild them from scratch
+pub struct ObservationBuffer {
+    data: [f32; STATE_VECTOR_LEN * 2],
+}
+
+impl ObservationBuffer {
+    pub fn new() -> Self {
+        Self {
+            data: [0.0; STATE_VECTOR_LEN * 2],
+        }
+    }
+
+    /// Row 0 is from player1's point of view, row 1 from player2's
+    pub fn fill(&mut self, context: &GameContext, state: &GameState, timer: f32) {
+        let player1_state: PlayerSerial =
+            state.player1.serialize(&context.player1, context.stage());
+        let player2_state: PlayerSerial =
+            state.player2.serialize(&context.player2, context.stage());
+
+        let (agent1, agent2) = self.data.split_at_mut(STATE_VECTOR_LEN);
+        write_observation(
+            agent1,
+            context,
+            state,
+            timer,
+            &player1_state,
+            &player2_state,
+        );
+        write_observation(
+            agent2,
+            context,
+            state,
+            timer,
+            &player2_state,
+            &player1_state,
+        );
+    }
+
+    /// Writes only `row`'s point of view, for when the other agent isn't run
+    pub fn fill_row(&mut self, context: &GameContext, state: &GameState, timer: f32, row: usize) {
+        let player1_state: PlayerSerial =
+            state.player1.serialize(&context.player1, context.stage());
+        let player2_state: PlayerSerial =
+            state.player2.serialize(&context.player2, context.stage());
+        let (own_state, other_state) = if row == 0 {
+            (&player1_state, &player2_state)
+        } else {
+            (&player2_state, &player1_state)
+        };
+
+        let start = row * STATE_VECTOR_LEN;
+        write_observation(
+            &mut self.data[start..start + STATE_VECTOR_LEN],
+            context,
+            state,
+            timer,
+            own_state,
+            other_state,
+        );
+    }
+
+    pub fn batch(&self, device: &Device) -> Result<Tensor> {
+        Tensor::from_slice(&self.data, (2, STATE_VECTOR_LEN), device)
+    }
+
+    /// Just `row` as a batch of one
+    pub fn row_batch(&self, row: usize, device: &Device) -> Result<Tensor> {
+        let start = row * STATE_VECTOR_LEN;
+        Tensor::from_slice(
+            &self.data[start..start + STATE_VECTOR_LEN],
+            (1, STATE_VECTOR_LEN),
+            device,
+        )
+    }
+}
+
 /// Interface used for training
 pub fn train(
     context: &GameContext,
     inputs: &mut PlayerInputs,
     state: &mut GameState,
+    mode: TrainingMode,
+    dqn_variant: DQNVariant,
 ) -> Result<()> {
     let env = Environment::new(context, inputs, state);
     let device = Device::cuda_if_available(0).unwrap_or(Device::Cpu);
     let start = Instant::now();
-    training::trainer_pool(env, device, start)
+    match mode {
+        TrainingMode::TrainerPool => training::trainer_pool(env, device, start),
+        TrainingMode::League => training::league(env, device, start),
+        TrainingMode::Distill => distill::distill(env, device, start),
+        TrainingMode::Dqn => dqn::train(env, device, start, dqn_variant),
+    }
 }
 
+/// Headless AI vs AI matches for comparing models
+pub fn benchmark(
+    context: &GameContext,
+    inputs: &mut PlayerInputs,
+    state: &mut GameState,
+    model1_path: &str,
+    model2_path: &str,
+    rounds: usize,
+) -> Result<()> {
+    let env = Environment::new(context, inputs, state);
+    benchmark::benchmark(env, model1_path, model2_path, rounds)
+}
+
+/// Loads either a full PPO policy or a distilled student, whichever the file holds
 pub fn load_model(filepath: &str, device: &Device) -> Result<(VarMap, Sequential)> {
-    let mut var_map = VarMap::new();
-    let agent = ppo::make_model(&var_map, device)?;
-    var_map.load(filepath)?;
+    let var_map = VarMap::new();
+    let tensors = candle_core::safetensors::load(filepath, device)?;
+    let input_len = model_input_len(filepath, &tensors)?;
+    let vb = VarBuilder::from_varmap(&var_map, DType::F32, device);
+    let agent = if tensors.contains_key(distill::STUDENT_MARKER) {
+        distill::build_student(vb, input_len)?
+    } else {
+        ppo::build_actor(vb, input_len)?
+    };
+    // Same as `VarMap::load`, but from the tensors already read
+    for (name, var) in var_map.data().lock().expect("Failed to lock varmap").iter() {
+        match tensors.get(name) {
+            Some(tensor) => var.set(tensor)?,
+            None => candle_core::bail!("'{filepath}' is missing '{name}'"),
+        }
+    }
     Ok((var_map, agent))
 }
 
+/// Loads a policy with plain tensors instead of trainable vars, so forward passes never track gradients
+pub fn load_inference_model(filepath: &str, device: &Device) -> Result<Sequential> {
+    let tensors = candle_core::safetensors::load(filepath, device)?;
+    let input_len = model_input_len(filepath, &tensors)?;
+    let is_student = tensors.contains_key(distill::STUDENT_MARKER);
+    let vb = VarBuilder::from_tensors(tensors, DType::F32, device);
+    if is_student {
+        distill::build_student(vb, input_len)
+    } else {
+        ppo::build_actor(vb, input_len)
+    }
+}
+
+fn model_input_len(filepath: &str, tensors: &HashMap<String, Tensor>) -> Result<usize> {
+    match tensors
+        .get(distill::STUDENT_MARKER)
+        .or_else(|| tensors.get(ppo::ACTOR_MARKER))
+    {
+        Some(input) => input.dim(1),
+        None => candle_core::bail!("'{filepath}' is not a policy model"),
+    }
+}
+
 pub fn take_agent_turn(inputs_history: &mut InputHistory, inputs: &mut Inputs, action: u32) {
     let (dir, buttons) = map_ai_action(action);
 
@@ -89,6 +290,32 @@ fn map_ai_action(ai_action: u32) -> (Direction, ButtonFlag) {
     (dir, buttons)
 }
 
+/// Inverse of `map_ai_action`
+fn encode_ai_action(dir: Direction, buttons: ButtonFlag) -> Action {
+    let dir_index = match dir {
+        Direction::DownLeft => 0,
+        Direction::Down => 1,
+        Direction::DownRight => 2,
+        Direction::Left => 3,
+        Direction::Neutral => 4,
+        Direction::Right => 5,
+        Direction::UpLeft => 6,
+        Direction::Up => 7,
+        Direction::UpRight => 8,
+    };
+    dir_index + 9 * buttons.bits() as Action
+}
+
+/// Start of every policy net, models trained on a shorter observation only read its leading features
+fn observation_input(input_len: usize) -> Sequential {
+    let input = seq();
+    if input_len < STATE_VECTOR_LEN {
+        input.add_fn(move |xs| xs.narrow(D::Minus1, 0, input_len))
+    } else {
+        input
+    }
+}
+
 fn save_model(var_map: &VarMap, filename: &str) -> Result<()> {
     if let Some(parent) = std::path::Path::new(filename).parent() {
         std::fs::create_dir_all(parent)?;
@@ -111,64 +338,37 @@ fn copy_var_map(source: &VarMap, destination: &mut VarMap) -> Result<()> {
     Ok(())
 }
 
-pub fn serialize_observation(
+fn write_observation(
+    out: &mut [f32],
     context: &GameContext,
     state: &GameState,
     timer: f32,
-    device: &Device,
-) -> Result<Tensor> {
-    let player1_state: PlayerSerial = state.player1.serialize(&context.player1, &context.stage);
-    let player2_state: PlayerSerial = state.player2.serialize(&context.player2, &context.stage);
-
-    _serialize_observation(context, state, timer, player1_state, player2_state, device)
-}
-
-pub fn serialize_observation_inv(
-    context: &GameContext,
-    state: &GameState,
-    timer: f32,
-    device: &Device,
-) -> Result<Tensor> {
-    let player1_state: PlayerSerial = state.player1.serialize(&context.player1, &context.stage);
-    let player2_state: PlayerSerial = state.player2.serialize(&context.player2, &context.stage);
-
-    _serialize_observation(context, state, timer, player2_state, player1_state, device)
-}
-
-pub fn observation_with_inv(
-    context: &GameContext,
-    state: &GameState,
-    timer: f32,
-    device: &Device,
-) -> Result<(Tensor, Tensor)> {
-    let player1_state: PlayerSerial = state.player1.serialize(&context.player1, &context.stage);
-    let player2_state: PlayerSerial = state.player2.serialize(&context.player2, &context.stage);
-
-    let agent1 =
-        _serialize_observation(context, state, timer, player1_state, player2_state, device)?;
-    let agent2 =
-        _serialize_observation(context, state, timer, player2_state, player1_state, device)?;
-
-    Ok((agent1, agent2))
-}
-
-fn _serialize_observation(
-    context: &GameContext,
-    state: &GameState,
-    timer: f32,
-    player1_state: PlayerSerial,
-    player2_state: PlayerSerial,
-    device: &Device,
-) -> Result<Tensor> {
+    player1_state: &PlayerSerial,
+    player2_state: &PlayerSerial,
+) {
     let global_inputs = [
         timer,
-        (state.player1.pos().x - state.player2.pos().x).abs() / context.stage.width(),
-        (state.player1.pos().y - state.player2.pos().y).abs() / context.stage.height(),
+        (state.player1.pos().x - state.player2.pos().x).abs() / context.stage().width(),
+        (state.player1.pos().y - state.player2.pos().y).abs() / context.stage().height(),
+    ];
+    // Each feature group is laid out for both players before the next so older layouts stay a prefix
+    let (base1, extra1) = player1_state.split_at(PLAYER_BASE_STATE_LEN);
+    let (base2, extra2) = player2_state.split_at(PLAYER_BASE_STATE_LEN);
+    let (frame_data1, buff1) = extra1.split_at(PLAYER_FRAME_DATA_STATE_LEN - PLAYER_BASE_STATE_LEN);
+    let (frame_data2, buff2) = extra2.split_at(PLAYER_FRAME_DATA_STATE_LEN - PLAYER_BASE_STATE_LEN);
+    let sections: [&[f32]; 7] = [
+        &global_inputs,
+        base1,
+        base2,
+        frame_data1,
+        frame_data2,
+        buff1,
+        buff2,
     ];
-    let state_iter = global_inputs
-        .into_iter()
-        .chain(player1_state)
-        .chain(player2_state);
 
-    Tensor::from_iter(state_iter, device)
+    let mut start = 0;
+    for section in sections {
+        out[start..start + section.len()].copy_from_slice(section);
+        start += section.len();
+    }
 }
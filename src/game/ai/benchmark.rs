@@ -0,0 +1,112 @@
+use candle_core::{Device, Result};
+
+use crate::game::{
+    FRAME_RATE, Side,
+    ai::{ACTION_SPACE, env::Environment, get_agent_action, load_inference_model, map_ai_action},
+};
+
+/// Most used actions printed per model
+const TOP_ACTIONS: usize = 5;
+
+#[derive(Default)]
+struct ModelStats {
+    wins: usize,
+    damage_dealt: f32,
+    actions: Vec<usize>,
+}
+
+impl ModelStats {
+    fn new() -> Self {
+        Self {
+            actions: vec![0; ACTION_SPACE],
+            ..Default::default()
+        }
+    }
+
+    fn print(&self, name: &str, rounds: usize) {
+        let total_actions = self.actions.iter().sum::<usize>().max(1);
+        println!("{name}");
+        println!(
+            "  Win rate: {:.1}%",
+            100.0 * self.wins as f32 / rounds as f32
+        );
+        println!(
+            "  Avg damage dealt: {:.1}% hp",
+            100.0 * self.damage_dealt / rounds as f32
+        );
+
+        let mut ranked = self.actions.iter().enumerate().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.cmp(a.1));
+        println!("  Top actions:");
+        for (action, count) in ranked.into_iter().take(TOP_ACTIONS) {
+            let (dir, buttons) = map_ai_action(action as u32);
+            println!(
+                "    {dir:?} + {buttons:?}: {:.1}%",
+                100.0 * *count as f32 / total_actions as f32
+            );
+        }
+    }
+}
+
+/// Plays `rounds` rounds between two models, swapping sides every round, and prints a summary
+pub fn benchmark(
+    mut env: Environment<'_>,
+    model1_path: &str,
+    model2_path: &str,
+    rounds: usize,
+) -> Result<()> {
+    let device = Device::Cpu;
+    let model1 = load_inference_model(model1_path, &device)?;
+    let model2 = load_inference_model(model2_path, &device)?;
+    let mut rng = rand::rng();
+
+    let mut stats1 = ModelStats::new();
+    let mut stats2 = ModelStats::new();
+    let mut draws = 0;
+    let mut total_frames = 0;
+
+    for round in 0..rounds {
+        let side = if round.is_multiple_of(2) {
+            Side::Left
+        } else {
+            Side::Right
+        };
+        env.reset_on_side(side);
+
+        loop {
+            let (obs, obs_inv) = env.obs_with_inv(&device)?;
+            let action1 = get_agent_action(&model1, &obs, &mut rng)?;
+            let action2 = get_agent_action(&model2, &obs_inv, &mut rng)?;
+            stats1.actions[action1 as usize] += 1;
+            stats2.actions[action2 as usize] += 1;
+
+            let (terminal, _) = env.step((action1, action2));
+            if terminal {
+                break;
+            }
+        }
+
+        let (score1, score2) = env.score();
+        match score1.cmp(&score2) {
+            std::cmp::Ordering::Greater => stats1.wins += 1,
+            std::cmp::Ordering::Less => stats2.wins += 1,
+            std::cmp::Ordering::Equal => draws += 1,
+        }
+        let (hp1, hp2) = env.hp_per();
+        stats1.damage_dealt += 1.0 - hp2;
+        stats2.damage_dealt += 1.0 - hp1;
+        total_frames += env.round_frames();
+    }
+
+    println!("___________________________");
+    println!("Rounds: {rounds}, Draws: {draws}");
+    println!(
+        "Avg round length: {:.1} secs",
+        total_frames as f32 / rounds as f32 / FRAME_RATE as f32
+    );
+    stats1.print(model1_path, rounds);
+    stats2.print(model2_path, rounds);
+    println!("___________________________");
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::time::Instant;
+
+use candle_core::{D, DType, Device, Result, Tensor};
+use candle_nn::{
+    Activation, AdamW, Module, Optimizer, Sequential, VarBuilder, VarMap, linear,
+    ops::{log_softmax, softmax},
+};
+use rand::seq::SliceRandom;
+
+use crate::game::ai::{
+    ACTION_SPACE, STATE_VECTOR_LEN, env::Environment, get_agent_action, load_model,
+    observation_input, save_model,
+};
+
+const TEACHER_PATH: &str = "./ai/ppo/best_NEW.safetensors";
+const STUDENT_OUTPUT_PATH: &str = "./ai/distilled_NEW.safetensors";
+
+const STUDENT_HIDDEN_COUNT: usize = 64;
+/// Tensor name only the student has, used to tell the architectures apart when loading
+pub const STUDENT_MARKER: &str = "student_in.weight";
+
+const LEARNING_RATE: f64 = 0.001;
+const ROUNDS: usize = 20;
+const STEPS_PER_ROUND: usize = 16_000;
+const EPOCHS_PER_ROUND: usize = 8;
+const BATCH_SIZE: usize = 512;
+/// Softens the teacher's distribution so the student also learns its second choices
+const TEMPERATURE: f64 = 2.0;
+
+pub fn make_student(var_map: &VarMap, device: &Device, input_len: usize) -> Result<Sequential> {
+    build_student(
+        VarBuilder::from_varmap(var_map, DType::F32, device),
+        input_len,
+    )
+}
+
+pub fn build_student(vb: VarBuilder, input_len: usize) -> Result<Sequential> {
+    let student = observation_input(input_len)
+        .add(linear(
+            input_len,
+            STUDENT_HIDDEN_COUNT,
+            vb.pp("student_in"),
+        )?)
+        .add(Activation::Relu)
+        .add(linear(
+            STUDENT_HIDDEN_COUNT,
+            STUDENT_HIDDEN_COUNT,
+            vb.pp("student_hidden"),
+        )?)
+        .add(Activation::Relu)
+        .add(linear(
+            STUDENT_HIDDEN_COUNT,
+            ACTION_SPACE,
+            vb.pp("student_out"),
+        )?);
+
+    Ok(student)
+}
+
+/// Trains a small policy to match the teacher's action distribution
+///
+/// After the first round the student plays agent1, so it also sees the states its own mistakes lead to
+pub fn distill(mut env: Environment<'_>, device: Device, start: Instant) -> Result<()> {
+    let mut rng = rand::rng();
+    let (_teacher_map, teacher) = load_model(TEACHER_PATH, &device)?;
+
+    let var_map = VarMap::new();
+    let student = make_student(&var_map, &device, STATE_VECTOR_LEN)?;
+    let mut optimizer = AdamW::new_lr(var_map.all_vars(), LEARNING_RATE)?;
+
+    for round in 0..ROUNDS {
+        let driver = if round == 0 { &teacher } else { &student };
+
+        let mut observations = Vec::with_capacity(STEPS_PER_ROUND * 2);
+        env.reset();
+        for _ in 0..STEPS_PER_ROUND {
+            let (obs, obs_inv) = env.obs_with_inv(&device)?;
+            let action1 = get_agent_action(driver, &obs, &mut rng)?;
+            let action2 = get_agent_action(&teacher, &obs_inv, &mut rng)?;
+            observations.push(obs);
+            observations.push(obs_inv);
+
+            let (terminal, _) = env.step((action1, action2));
+            if terminal {
+                env.reset();
+            }
+        }
+
+        let observations = Tensor::stack(&observations, 0)?;
+        let targets = softmax(
+            &(teacher.forward(&observations)?.detach() / TEMPERATURE)?,
+            D::Minus1,
+        )?;
+
+        let mut indices = (0..observations.dim(0)? as u32).collect::<Vec<_>>();
+        let mut loss_sum = 0.0;
+        let mut batches = 0;
+        for _ in 0..EPOCHS_PER_ROUND {
+            indices.shuffle(&mut rng);
+            for batch in indices.chunks(BATCH_SIZE) {
+                let batch = Tensor::from_slice(batch, batch.len(), &device)?;
+                let obs = observations.index_select(&batch, 0)?;
+                let target = targets.index_select(&batch, 0)?;
+
+                // Soft cross entropy against the teacher
+                let log_probs = log_softmax(&(student.forward(&obs)? / TEMPERATURE)?, D::Minus1)?;
+                let loss = (target * log_probs)?.sum(D::Minus1)?.mean_all()?.neg()?;
+                optimizer.backward_step(&loss)?;
+
+                loss_sum += loss.to_scalar::<f32>()?;
+                batches += 1;
+            }
+        }
+
+        println!(
+            "Distill round #{}, loss: {}, time: {:?}",
+            round + 1,
+            loss_sum / batches as f32,
+            start.elapsed()
+        );
+        save_model(&var_map, STUDENT_OUTPUT_PATH)?;
+    }
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
-use std::{collections::VecDeque, time::Instant};
+use std::time::Instant;
 
 use candle_core::{DType, Device, Result, Tensor};
 use candle_nn::{
-    Activation, AdamW, Module, Optimizer, Sequential, VarBuilder, VarMap, linear, seq,
+    Activation, AdamW, Linear, Module, Optimizer, Sequential, VarBuilder, VarMap, linear, seq,
 };
-use rand::{Rng, distr::Uniform};
+use clap::ValueEnum;
+use rand::Rng;
 
 use crate::game::ai::{
     ACTION_SPACE, Actions, DuelFloat, STATE_VECTOR_LEN, copy_var_map, env::Environment, save_model,
@@ -26,30 +27,154 @@ const START_E: f64 = 0.8;
 const END_E: f64 = 0.05;
 const EPSILON_RANGE: usize = EPISODES;
 const EPISODE_PRINT_STEP: usize = EPISODES / 1_000;
+/// How strongly TD error skews replay sampling, 0 is uniform
+const PRIORITY_ALPHA: f32 = 0.6;
+/// Importance sampling correction, annealed to 1 over training
+const PRIORITY_BETA_START: f32 = 0.4;
+/// Keeps zero error memories sampleable
+const PRIORITY_EPSILON: f32 = 1e-5;
+
+/// Which DQN improvements the trainer uses
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum DQNVariant {
+    Standard,
+    /// Online network picks the next action, target network evaluates it
+    Double,
+    /// Separate state value and action advantage heads
+    Dueling,
+    #[default]
+    DoubleDueling,
+}
+
+impl DQNVariant {
+    fn double_q(self) -> bool {
+        matches!(self, Self::Double | Self::DoubleDueling)
+    }
+
+    fn dueling(self) -> bool {
+        matches!(self, Self::Dueling | Self::DoubleDueling)
+    }
+}
+
+pub enum QNetwork {
+    Standard(Sequential),
+    Dueling {
+        body: Sequential,
+        value: Linear,
+        advantage: Linear,
+    },
+}
+
+impl Module for QNetwork {
+    fn forward(&self, xs: &Tensor) -> Result<Tensor> {
+        match self {
+            Self::Standard(model) => model.forward(xs),
+            Self::Dueling {
+                body,
+                value,
+                advantage,
+            } => {
+                let features = body.forward(xs)?;
+                let value = value.forward(&features)?;
+                let advantage = advantage.forward(&features)?;
+                // Q = V + (A - mean(A)) keeps the value/advantage split identifiable
+                let advantage = advantage.broadcast_sub(&advantage.mean_keepdim(1)?)?;
+                advantage.broadcast_add(&value)
+            }
+        }
+    }
+}
 
-pub fn make_model(var_map: &VarMap, device: &Device) -> Result<Sequential> {
+pub fn make_model(var_map: &VarMap, device: &Device, variant: DQNVariant) -> Result<QNetwork> {
     let vb = VarBuilder::from_varmap(var_map, DType::F32, device);
 
-    let agent1 = seq()
+    let body = seq()
         .add(linear(STATE_VECTOR_LEN, HIDDEN_COUNT, vb.pp("linear_in"))?)
         .add(Activation::Relu)
         .add(linear(HIDDEN_COUNT, HIDDEN_COUNT, vb.pp("hidden"))?)
-        .add(Activation::Relu)
-        .add(linear(HIDDEN_COUNT, ACTION_SPACE, vb.pp("linear_out"))?);
+        .add(Activation::Relu);
 
-    Ok(agent1)
+    if variant.dueling() {
+        Ok(QNetwork::Dueling {
+            body,
+            value: linear(HIDDEN_COUNT, 1, vb.pp("value_out"))?,
+            advantage: linear(HIDDEN_COUNT, ACTION_SPACE, vb.pp("advantage_out"))?,
+        })
+    } else {
+        Ok(QNetwork::Standard(body.add(linear(
+            HIDDEN_COUNT,
+            ACTION_SPACE,
+            vb.pp("linear_out"),
+        )?)))
+    }
 }
 
 type GameMemory = (Tensor, Actions, DuelFloat, bool, Tensor); // Init state, actions, reward, terminal_inverse, next state
+
+/// Binary tree where every node holds the sum of its children, leaves hold priorities
+struct SumTree {
+    nodes: Vec<f32>,
+    leaf_count: usize,
+}
+
+impl SumTree {
+    pub fn new(capacity: usize) -> Self {
+        let leaf_count = capacity.next_power_of_two();
+        Self {
+            nodes: vec![0.0; leaf_count * 2],
+            leaf_count,
+        }
+    }
+
+    pub fn total(&self) -> f32 {
+        self.nodes[1]
+    }
+
+    pub fn get(&self, index: usize) -> f32 {
+        self.nodes[self.leaf_count + index]
+    }
+
+    pub fn set(&mut self, index: usize, priority: f32) {
+        let mut node = self.leaf_count + index;
+        self.nodes[node] = priority;
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = self.nodes[node * 2] + self.nodes[node * 2 + 1];
+        }
+    }
+
+    /// Index of the leaf whose cumulative priority range contains `value`
+    pub fn find(&self, mut value: f32) -> usize {
+        let mut node = 1;
+        while node < self.leaf_count {
+            let left = node * 2;
+            if value < self.nodes[left] {
+                node = left;
+            } else {
+                value -= self.nodes[left];
+                node = left + 1;
+            }
+        }
+        node - self.leaf_count
+    }
+}
+
+/// Proportional prioritized replay
 struct ReplayMemory {
-    memory: VecDeque<GameMemory>,
+    memory: Vec<GameMemory>,
+    priorities: SumTree,
+    max_priority: f32,
+    next_index: usize,
     count: usize,
 }
 
 impl ReplayMemory {
     pub fn new() -> Self {
         Self {
-            memory: VecDeque::with_capacity(REPLAY_SIZE),
+            memory: Vec::with_capacity(REPLAY_SIZE),
+            priorities: SumTree::new(REPLAY_SIZE),
+            max_priority: 1.0,
+            next_index: 0,
             count: 0,
         }
     }
@@ -66,45 +191,93 @@ impl ReplayMemory {
         self.count = 0;
     }
 
+    /// New memories get the max priority seen so far so they are replayed at least once
     pub fn append(&mut self, new_memory: GameMemory) {
         self.count += 1;
-        self.memory.push_front(new_memory);
-        if self.memory.len() > REPLAY_SIZE {
-            self.memory.pop_back();
+        if self.memory.len() < REPLAY_SIZE {
+            self.memory.push(new_memory);
+        } else {
+            self.memory[self.next_index] = new_memory;
         }
+        self.priorities
+            .set(self.next_index, self.max_priority.powf(PRIORITY_ALPHA));
+        self.next_index = (self.next_index + 1) % REPLAY_SIZE;
     }
 
     pub fn get(&self, index: usize) -> GameMemory {
-        let mem = self.memory.get(index).unwrap();
+        let mem = &self.memory[index];
         (mem.0.clone(), mem.1, mem.2, mem.3, mem.4.clone())
     }
+
+    /// Stratified sample proportional to priority, returns indices and normalized importance sampling weights
+    pub fn sample(
+        &self,
+        rng: &mut rand::rngs::ThreadRng,
+        batch_size: usize,
+        beta: f32,
+    ) -> (Vec<usize>, Vec<f32>) {
+        let total = self.priorities.total();
+        let segment = total / batch_size as f32;
+        let len = self.len();
+
+        let indices = (0..batch_size)
+            .map(|i| {
+                let value = segment * (i as f32 + rng.random::<f32>());
+                self.priorities.find(value.min(total)).min(len - 1)
+            })
+            .collect::<Vec<_>>();
+
+        let weights = indices
+            .iter()
+            .map(|&i| (len as f32 * self.priorities.get(i) / total).powf(-beta))
+            .collect::<Vec<_>>();
+        let max_weight = weights.iter().copied().fold(f32::MIN, f32::max);
+
+        (
+            indices,
+            weights.into_iter().map(|w| w / max_weight).collect(),
+        )
+    }
+
+    pub fn update_priorities(&mut self, indices: &[usize], td_errors: &[f32]) {
+        for (&index, &td_error) in indices.iter().zip(td_errors) {
+            let priority = td_error.abs() + PRIORITY_EPSILON;
+            self.max_priority = self.max_priority.max(priority);
+            self.priorities.set(index, priority.powf(PRIORITY_ALPHA));
+        }
+    }
 }
 
 pub struct DQNAgent {
-    agent: Sequential,
-    target: Sequential,
+    agent: QNetwork,
+    target: QNetwork,
+    variant: DQNVariant,
     optimizer: AdamW,
     var_map_agent: VarMap,
     var_map_target: VarMap,
 }
 
 impl DQNAgent {
-    pub fn new(device: &Device) -> Result<Self> {
+    pub fn new(device: &Device, variant: DQNVariant) -> Result<Self> {
         let var_map_agent = VarMap::new();
         let var_map_target = VarMap::new();
 
-        let agent = make_model(&var_map_agent, device)?;
-        let target = make_model(&var_map_agent, device)?;
+        let agent = make_model(&var_map_agent, device, variant)?;
+        let target = make_model(&var_map_target, device, variant)?;
 
         let optimizer = AdamW::new_lr(var_map_agent.all_vars(), LEARNING_RATE)?;
 
-        Ok(Self {
+        let mut dqn_agent = Self {
             agent,
             target,
+            variant,
             optimizer,
             var_map_agent,
             var_map_target,
-        })
+        };
+        dqn_agent.update_target()?;
+
+        Ok(dqn_agent)
     }
 
     fn act(&self, obs: &Tensor, epsilon: f64, rng: &mut rand::rngs::ThreadRng) -> Result<u32> {
@@ -118,15 +291,26 @@ impl DQNAgent {
         next_states: &Tensor,
         non_final_mask: &Tensor,
         rewards: &Tensor,
-    ) -> Result<()> {
+        weights: &Tensor,
+    ) -> Result<Vec<f32>> {
         let estimated_rewards = self.agent.forward(states)?;
         let x = estimated_rewards.gather(actions, 1)?;
         let expected_rewards = self.target.forward(next_states)?.detach();
-        let y = expected_rewards.max_keepdim(1)?;
+        let y = if self.variant.double_q() {
+            let next_actions = self
+                .agent
+                .forward(next_states)?
+                .detach()
+                .argmax_keepdim(1)?;
+            expected_rewards.gather(&next_actions, 1)?
+        } else {
+            expected_rewards.max_keepdim(1)?
+        };
         let y = (y * GAMMA * non_final_mask + rewards)?;
-        let loss = candle_nn::loss::mse(&x, &y)?;
+        let td_errors = (x - y)?;
+        let loss = (td_errors.sqr()? * weights)?.mean_all()?;
         self.optimizer.backward_step(&loss)?;
-        Ok(())
+        td_errors.detach().flatten_all()?.to_vec1()
     }
 
     fn update_target(&mut self) -> Result<()> {
@@ -138,12 +322,16 @@ impl DQNAgent {
     }
 }
 
-#[allow(dead_code)]
-pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result<()> {
+pub fn train(
+    mut env: Environment<'_>,
+    device: Device,
+    start: Instant,
+    variant: DQNVariant,
+) -> Result<()> {
     let mut rng = rand::rng();
 
-    let mut agent1 = DQNAgent::new(&device)?;
-    let mut agent2 = DQNAgent::new(&device)?;
+    let mut agent1 = DQNAgent::new(&device, variant)?;
+    let mut agent2 = DQNAgent::new(&device, variant)?;
 
     let mut replay_memory = ReplayMemory::new();
 
@@ -179,7 +367,15 @@ pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result
         }
 
         if replay_memory.len() >= REPLAY_SIZE && replay_memory.count() >= BATCH_SIZE {
-            train_agents(&mut rng, &device, &mut agent1, &mut agent2, &replay_memory)?;
+            let beta = get_beta(episode);
+            train_agents(
+                &mut rng,
+                &device,
+                &mut agent1,
+                &mut agent2,
+                &mut replay_memory,
+                beta,
+            )?;
 
             replay_memory.reset_count();
         }
@@ -213,18 +409,21 @@ fn get_epsilon(episode: usize) -> f64 {
     START_E - (START_E - END_E) * (episode as f64 / EPSILON_RANGE as f64).min(1.0)
 }
 
+fn get_beta(episode: usize) -> f32 {
+    PRIORITY_BETA_START + (1.0 - PRIORITY_BETA_START) * (episode as f32 / EPISODES as f32).min(1.0)
+}
+
 fn train_agents(
     rng: &mut rand::rngs::ThreadRng,
     device: &Device,
     agent1: &mut DQNAgent,
     agent2: &mut DQNAgent,
-    memory: &ReplayMemory,
+    memory: &mut ReplayMemory,
+    beta: f32,
 ) -> Result<()> {
-    let batch = rng
-        .sample_iter(Uniform::new(0, REPLAY_SIZE).expect("Bad uniform range"))
-        .take(BATCH_SIZE)
-        .map(|i| memory.get(i))
-        .collect::<Vec<_>>();
+    let (indices, weights) = memory.sample(rng, BATCH_SIZE, beta);
+    let batch = indices.iter().map(|&i| memory.get(i)).collect::<Vec<_>>();
+    let weights = Tensor::from_vec(weights, (BATCH_SIZE, 1), device)?;
 
     let states = batch.iter().map(|e| &e.0).collect::<Vec<_>>();
     let states = Tensor::stack(&states, 0)?;
@@ -243,27 +442,37 @@ fn train_agents(
     let next_states = batch.iter().map(|e| &e.4).collect::<Vec<_>>();
     let next_states = Tensor::stack(&next_states, 0)?;
 
-    agent1.update(
+    let agent1_td = agent1.update(
         &states,
         &agent1_actions,
         &next_states,
         &non_final_mask,
         &agent1_rewards,
+        &weights,
     )?;
-    agent2.update(
+    let agent2_td = agent2.update(
         &states,
         &agent2_actions,
         &next_states,
         &non_final_mask,
         &agent2_rewards,
+        &weights,
     )?;
 
+    // Both agents share the buffer, so prioritize by whichever was more surprised
+    let td_errors = agent1_td
+        .iter()
+        .zip(&agent2_td)
+        .map(|(a, b)| a.abs().max(b.abs()))
+        .collect::<Vec<_>>();
+    memory.update_priorities(&indices, &td_errors);
+
     Ok(())
 }
 
 fn get_ai_action(
     rng: &mut rand::rngs::ThreadRng,
-    agent: &candle_nn::Sequential,
+    agent: &impl Module,
     obs: &Tensor,
     epsilon: f64,
 ) -> Result<u32> {
@@ -276,3 +485,18 @@ fn get_ai_action(
 
     Ok(ai_action)
 }
+
+#[test]
+fn test_sum_tree_find() {
+    let mut tree = SumTree::new(3);
+    tree.set(0, 1.0);
+    tree.set(1, 3.0);
+    tree.set(2, 2.0);
+
+    assert_eq!(tree.total(), 6.0);
+    assert_eq!(tree.find(0.5), 0);
+    assert_eq!(tree.find(1.0), 1);
+    assert_eq!(tree.find(3.9), 1);
+    assert_eq!(tree.find(4.0), 2);
+    assert_eq!(tree.find(5.9), 2);
+}
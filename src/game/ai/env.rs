@@ -5,7 +5,7 @@ use sdl3::render::FPoint;
 
 use crate::game::{
     GameContext, GameState, PlayerInputs, Side,
-    ai::{DuelFloat, observation_with_inv, serialize_observation, take_agent_turn},
+    ai::{DuelFloat, ObservationBuffer, take_agent_turn},
     scene::gameplay::{GameplayScene, during_round::DuringRound},
 };
 
@@ -16,12 +16,73 @@ pub struct Environment<'a> {
     state: &'a mut GameState,
 
     accumulate_rewards: DuelFloat,
+    /// Frames each chosen action is held for
+    action_repeat: usize,
+    /// Reward shaping for (agent1, agent2)
+    reward_styles: (RewardStyle, RewardStyle),
+    /// Frames simulated since the last reset
+    round_frames: usize,
+    observations: ObservationBuffer,
 }
 
 // REWARDS
 const ROUND_WIN_SCORE: f32 = 25.0;
 const ROUND_LOSE_SCORE: f32 = -12.5;
 const ROUND_TIE_SCORE: f32 = -50.0;
+/// Distance a zoner is rewarded for keeping from the opponent
+const ZONER_SPACING: f32 = 180.0;
+
+/// Reward shaping preset, used to grow agents with different play styles
+#[derive(Clone, Copy, Debug)]
+pub enum RewardStyle {
+    Balanced,
+    Aggressive,
+    Defensive,
+    Zoner,
+}
+
+struct RewardWeights {
+    damage: f32,
+    combo: f32,
+    approach: f32,
+    damage_penalty: f32,
+    spacing: f32,
+}
+
+impl RewardStyle {
+    fn weights(self) -> RewardWeights {
+        match self {
+            Self::Balanced => RewardWeights {
+                damage: 10.0,
+                combo: 10.0,
+                approach: 0.02,
+                damage_penalty: 8.0,
+                spacing: 0.0,
+            },
+            Self::Aggressive => RewardWeights {
+                damage: 14.0,
+                combo: 14.0,
+                approach: 0.04,
+                damage_penalty: 5.0,
+                spacing: 0.0,
+            },
+            Self::Defensive => RewardWeights {
+                damage: 8.0,
+                combo: 8.0,
+                approach: 0.01,
+                damage_penalty: 14.0,
+                spacing: 0.0,
+            },
+            Self::Zoner => RewardWeights {
+                damage: 10.0,
+                combo: 8.0,
+                approach: 0.0,
+                damage_penalty: 10.0,
+                spacing: 0.02,
+            },
+        }
+    }
+}
 
 impl<'a> Environment<'a> {
     pub fn new(
@@ -30,17 +91,26 @@ impl<'a> Environment<'a> {
         state: &'a mut GameState,
     ) -> Self {
         Self {
-            scene: DuringRound::new((0, 0)),
+            scene: DuringRound::new((0, 0)).without_slow_ko(),
             context,
             inputs,
             state,
             accumulate_rewards: DuelFloat::default(),
+            action_repeat: context.agent_action_repeat.max(1),
+            reward_styles: (RewardStyle::Balanced, RewardStyle::Balanced),
+            round_frames: 0,
+            observations: ObservationBuffer::new(),
         }
     }
 
+    pub fn set_reward_styles(&mut self, agent1: RewardStyle, agent2: RewardStyle) {
+        self.reward_styles = (agent1, agent2);
+    }
+
     pub fn reset(&mut self) {
         self.accumulate_rewards = DuelFloat::default();
-        self.scene = DuringRound::new((0, 0));
+        self.round_frames = 0;
+        self.scene = DuringRound::new((0, 0)).without_slow_ko();
         self.state.reset(self.context);
         self.inputs.reset_player1();
         self.inputs.reset_player2();
@@ -48,7 +118,8 @@ impl<'a> Environment<'a> {
 
     pub fn reset_on_side(&mut self, side1: Side) {
         self.accumulate_rewards = DuelFloat::default();
-        self.scene = DuringRound::new((0, 0));
+        self.round_frames = 0;
+        self.scene = DuringRound::new((0, 0)).without_slow_ko();
 
         self.inputs.reset_player1();
         self.inputs.reset_player2();
@@ -88,20 +159,39 @@ impl<'a> Environment<'a> {
         println!("___________________________\n");
     }
 
-    pub fn obs(&self, device: &Device) -> Result<Tensor> {
+    pub fn obs(&mut self, device: &Device) -> Result<Tensor> {
         let timer = self.scene.timer();
-        serialize_observation(self.context, self.state, timer, device)
+        self.observations
+            .fill_row(self.context, self.state, timer, 0);
+        self.observations.row_batch(0, device)?.squeeze(0)
     }
 
     /// Returns the state obs tensor with (player1 first in vec, player2 first in vec)
     ///
     /// Used so that the critic can have a common reference point to evaluate states in AC algorithms
-    pub fn obs_with_inv(&self, device: &Device) -> Result<(Tensor, Tensor)> {
+    pub fn obs_with_inv(&mut self, device: &Device) -> Result<(Tensor, Tensor)> {
         let timer = self.scene.timer();
-        observation_with_inv(self.context, self.state, timer, device)
+        self.observations.fill(self.context, self.state, timer);
+        // Both rows share the one batch tensor
+        let batch = self.observations.batch(device)?;
+        Ok((batch.get(0)?, batch.get(1)?))
     }
 
+    /// Holds the actions for `action_repeat` frames, or until the episode ends, summing the rewards
     pub fn step(&mut self, actions: (u32, u32)) -> (bool, DuelFloat) {
+        let mut rewards = DuelFloat::default();
+        for _ in 0..self.action_repeat {
+            let (terminal, frame_rewards) = self.step_frame(actions);
+            rewards.agent1 += frame_rewards.agent1;
+            rewards.agent2 += frame_rewards.agent2;
+            if terminal {
+                return (true, rewards);
+            }
+        }
+        (false, rewards)
+    }
+
+    fn step_frame(&mut self, actions: (u32, u32)) -> (bool, DuelFloat) {
         take_agent_turn(
             &mut self.inputs.player1,
             &mut self.state.player1_inputs,
@@ -125,6 +215,7 @@ impl<'a> Environment<'a> {
         let old_score = self.scene.score();
 
         let terminal = self.scene.update(self.context, self.state).is_some();
+        self.round_frames += 1;
 
         let rewards = self.reward(old_pos, old_hp, old_combo, old_score);
         self.accumulate_rewards.agent1 += rewards.agent1;
@@ -133,6 +224,33 @@ impl<'a> Environment<'a> {
         (terminal, rewards)
     }
 
+    /// Returns (side, distance to opponent) of agent2/player2, used by scripted opponents
+    pub fn agent2_view(&self) -> (Side, f32) {
+        let distance = (self.state.player1.pos().x - self.state.player2.pos().x).abs();
+        (self.state.player2.side(), distance)
+    }
+
+    pub fn score(&self) -> (u32, u32) {
+        self.scene.score()
+    }
+
+    /// Remaining (agent1, agent2) hp as a fraction of max hp
+    pub fn hp_per(&self) -> (f32, f32) {
+        (
+            self.state.player1.hp_per(&self.context.player1),
+            self.state.player2.hp_per(&self.context.player2),
+        )
+    }
+
+    pub fn round_frames(&self) -> usize {
+        self.round_frames
+    }
+
+    /// Where the game config wants ladder checkpoints saved
+    pub fn ladder_dir(&self) -> &str {
+        &self.context.ladder_dirpath
+    }
+
     /// Returns true if agent1/player1 won
     pub fn agent1_winner(&self) -> bool {
         let (agent1, agent2) = self.scene.score();
@@ -196,25 +314,37 @@ impl<'a> Environment<'a> {
             }
         };
 
-        let dmg_rwd1 = (old_hp.1 - new_hp.1) * 10.0;
-        let dmg_rwd2 = (old_hp.0 - new_hp.0) * 10.0;
+        let weights1 = self.reward_styles.0.weights();
+        let weights2 = self.reward_styles.1.weights();
+
+        let dmg_dealt1 = old_hp.1 - new_hp.1;
+        let dmg_dealt2 = old_hp.0 - new_hp.0;
+        let dmg_rwd1 = dmg_dealt1 * weights1.damage;
+        let dmg_rwd2 = dmg_dealt2 * weights2.damage;
 
-        let combo_rwd1 = (old_combo.1 - new_combo.1).max(0.0) * 10.0;
-        let combo_rwd2 = (old_combo.0 - new_combo.0).max(0.0) * 10.0;
+        let combo_rwd1 = (old_combo.1 - new_combo.1).max(0.0) * weights1.combo;
+        let combo_rwd2 = (old_combo.0 - new_combo.0).max(0.0) * weights2.combo;
 
         // If agent made an action to get closer then reward it
         let approached_1 =
             (old_pos.1.x - new_pos.0.x).abs().max(60.0) < (old_pos.1.x - old_pos.0.x).abs();
-        let approach_rwd1 = approached_1 as u8 as f32 * 0.02;
+        let approach_rwd1 = approached_1 as u8 as f32 * weights1.approach;
         let approached_2 =
             (old_pos.0.x - new_pos.1.x).abs().max(60.0) < (old_pos.0.x - old_pos.1.x).abs();
-        let approach_rwd2 = approached_2 as u8 as f32 * 0.02;
+        let approach_rwd2 = approached_2 as u8 as f32 * weights2.approach;
+
+        // Zoners get paid for holding the opponent at range
+        let spaced = (new_pos.0.x - new_pos.1.x).abs() >= ZONER_SPACING;
+        let spacing_rwd1 = spaced as u8 as f32 * weights1.spacing;
+        let spacing_rwd2 = spaced as u8 as f32 * weights2.spacing;
 
-        let dmg_penalty1 = dmg_rwd2 * 0.8;
-        let dmg_penalty2 = dmg_rwd1 * 0.8;
+        let dmg_penalty1 = dmg_dealt2 * weights1.damage_penalty;
+        let dmg_penalty2 = dmg_dealt1 * weights2.damage_penalty;
 
-        let agent1 = round_rwd1 + dmg_rwd1 + combo_rwd1 + approach_rwd1 - dmg_penalty1;
-        let agent2 = round_rwd2 + dmg_rwd2 + combo_rwd2 + approach_rwd2 - dmg_penalty2;
+        let agent1 =
+            round_rwd1 + dmg_rwd1 + combo_rwd1 + approach_rwd1 + spacing_rwd1 - dmg_penalty1;
+        let agent2 =
+            round_rwd2 + dmg_rwd2 + combo_rwd2 + approach_rwd2 + spacing_rwd2 - dmg_penalty2;
 
         DuelFloat { agent1, agent2 }
     }
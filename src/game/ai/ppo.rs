@@ -4,7 +4,9 @@ use candle_nn::{
 };
 use rand::{Rng, distr::weighted::WeightedIndex, rngs::ThreadRng};
 
-use crate::game::ai::{ACTION_SPACE, STATE_VECTOR_LEN, save_model};
+use crate::game::ai::{
+    ACTION_SPACE, STATE_VECTOR_LEN, copy_var_map, observation_input, save_model,
+};
 
 const HIDDEN_COUNT: usize = 256;
 const LEARNING_RATE_ACTOR: f64 = 0.001;
@@ -16,11 +18,19 @@ const GAE_LAMBDA: f32 = 0.97;
 const K_EPOCHS: usize = 20;
 const TARGET_KL: f32 = 0.01;
 
-pub fn make_model(var_map: &VarMap, device: &Device) -> Result<Sequential> {
-    let vb = VarBuilder::from_varmap(var_map, DType::F32, device);
+/// Tensor name of the actor input layer, its width is the observation length the model was trained on
+pub const ACTOR_MARKER: &str = "actor_in.weight";
 
-    let agent1 = seq()
-        .add(linear(STATE_VECTOR_LEN, HIDDEN_COUNT, vb.pp("actor_in"))?)
+pub fn make_model(var_map: &VarMap, device: &Device, input_len: usize) -> Result<Sequential> {
+    build_actor(
+        VarBuilder::from_varmap(var_map, DType::F32, device),
+        input_len,
+    )
+}
+
+pub fn build_actor(vb: VarBuilder, input_len: usize) -> Result<Sequential> {
+    let agent1 = observation_input(input_len)
+        .add(linear(input_len, HIDDEN_COUNT, vb.pp("actor_in"))?)
         .add(Activation::Relu)
         .add(linear(HIDDEN_COUNT, HIDDEN_COUNT, vb.pp("actor_hidden"))?)
         .add(Activation::Relu)
@@ -165,7 +175,7 @@ impl ActorCritic {
         let critic_map = VarMap::new();
 
         let ac = Self {
-            actor: make_model(&actor_map, device)?,
+            actor: make_model(&actor_map, device, STATE_VECTOR_LEN)?,
             critic: make_critic(&critic_map, device)?,
         };
 
@@ -248,6 +258,14 @@ impl PPOAgent {
         (self.policy.actor, self.actor_map)
     }
 
+    /// Frozen copy of the current actor
+    pub fn snapshot_policy(&self, device: &Device) -> Result<(Sequential, VarMap)> {
+        let mut var_map = VarMap::new();
+        let policy = make_model(&var_map, device, STATE_VECTOR_LEN)?;
+        copy_var_map(&self.actor_map, &mut var_map)?;
+        Ok((policy, var_map))
+    }
+
     pub fn save(&self, filename: &str) -> Result<()> {
         save_model(&self.actor_map, filename)
     }
@@ -312,6 +330,20 @@ pub fn get_agent_action(agent: &Sequential, obs: &Tensor, rng: &mut ThreadRng) -
     Ok(rng.sample(WeightedIndex::new(weights).unwrap()) as u32)
 }
 
+pub fn sample_agent_actions(
+    agent: &Sequential,
+    batch: &Tensor,
+    rng: &mut ThreadRng,
+    actions: &mut [u32],
+) -> Result<()> {
+    let estimates = agent.forward(batch)?.detach();
+    let action_probs = softmax(&estimates, D::Minus1)?.to_vec2::<f32>()?;
+    for (action, weights) in actions.iter_mut().zip(action_probs) {
+        *action = rng.sample(WeightedIndex::new(weights).unwrap()) as u32;
+    }
+    Ok(())
+}
+
 //----------------//
 /* Multithreading */
 //----------------//
@@ -1,3 +1,6 @@
+mod league;
+mod scripted;
 mod trainer_pool;
 
+pub use league::train as league;
 pub use trainer_pool::train as trainer_pool;
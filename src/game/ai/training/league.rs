@@ -0,0 +1,149 @@
+use std::{collections::VecDeque, time::Instant};
+
+use candle_core::{Device, Result};
+use rand::{Rng, rngs::ThreadRng};
+
+use crate::game::{
+    Side,
+    ai::{
+        env::{Environment, RewardStyle},
+        ppo::{PPOAgent, RolloutBuffer},
+        training::trainer_pool::{GameHistory, Opponent, STEPS_PER_EPOCH, Trainer, fight_trainer},
+    },
+};
+
+/// One concurrent learner per style, kept apart so the league does not collapse into one lineage
+const LEAGUE_STYLES: [RewardStyle; 3] = [
+    RewardStyle::Aggressive,
+    RewardStyle::Defensive,
+    RewardStyle::Zoner,
+];
+const GENERATIONS: usize = 600;
+/// Generations between freezing every learner into the snapshot pool
+const SNAPSHOT_INTERVAL: usize = 20;
+const MAX_SNAPSHOTS: usize = 24;
+/// Chance a learner spars against another live learner instead of a past snapshot
+const CROSS_PLAY_RATE: f64 = 0.5;
+const LEAGUE_OUTPUT_DIR: &str = "./ai/league";
+
+struct Learner {
+    style: RewardStyle,
+    agent: PPOAgent,
+    history: GameHistory,
+}
+
+impl Learner {
+    fn output_path(&self) -> String {
+        format!(
+            "{LEAGUE_OUTPUT_DIR}/{}_NEW.safetensors",
+            format!("{:?}", self.style).to_lowercase()
+        )
+    }
+}
+
+/// Trains several learners with different reward styles against each other and their past selves
+///
+/// Swap in for the trainer pool in `ai::train`
+pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result<()> {
+    let mut rng = rand::rng();
+    let mut buffer = RolloutBuffer::new(STEPS_PER_EPOCH);
+
+    let mut learners = LEAGUE_STYLES
+        .iter()
+        .map(|&style| {
+            Ok(Learner {
+                style,
+                agent: PPOAgent::new(&device)?,
+                history: GameHistory::new(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut snapshots = VecDeque::with_capacity(MAX_SNAPSHOTS);
+    push_snapshots(&mut snapshots, &learners, &device)?;
+
+    for generation in 1..GENERATIONS + 1 {
+        // Freeze the live learners so they can face each other this generation
+        let live = learners
+            .iter()
+            .map(|learner| Trainer::snapshot(&learner.agent, &device))
+            .collect::<Result<Vec<_>>>()?;
+
+        let side = if generation.is_multiple_of(2) {
+            Side::Left
+        } else {
+            Side::Right
+        };
+
+        for (index, learner) in learners.iter_mut().enumerate() {
+            let trainer = pick_trainer(index, &live, &snapshots, &mut rng);
+            let opponent = Opponent::pick(trainer, &mut rng);
+
+            env.set_reward_styles(learner.style, RewardStyle::Balanced);
+            let round_score = fight_trainer(
+                side,
+                &mut env,
+                &learner.agent,
+                opponent,
+                &mut buffer,
+                &device,
+                &mut rng,
+            )?;
+            learner.agent.update(&buffer, &device)?;
+            buffer.reset();
+
+            if let Opponent::Trainer(_) = opponent {
+                learner.history.push(round_score, 1);
+            }
+        }
+
+        if generation.is_multiple_of(SNAPSHOT_INTERVAL) {
+            push_snapshots(&mut snapshots, &learners, &device)?;
+
+            println!("Generation #{generation}, Snapshots: {}", snapshots.len());
+            for learner in &learners {
+                println!(
+                    "  {:?}: winrate {}",
+                    learner.style,
+                    learner.history.win_rate()
+                );
+                learner.agent.save(&learner.output_path())?;
+            }
+        }
+    }
+
+    println!("Completed in {:?} secs", start.elapsed());
+    for learner in &learners {
+        learner.agent.save(&learner.output_path())?;
+    }
+    Ok(())
+}
+
+fn push_snapshots(
+    snapshots: &mut VecDeque<Trainer>,
+    learners: &[Learner],
+    device: &Device,
+) -> Result<()> {
+    for learner in learners {
+        if snapshots.len() >= MAX_SNAPSHOTS {
+            snapshots.pop_back();
+        }
+        snapshots.push_front(Trainer::snapshot(&learner.agent, device)?);
+    }
+    Ok(())
+}
+
+/// Another live learner for cross play, otherwise any frozen snapshot
+fn pick_trainer<'a>(
+    index: usize,
+    live: &'a [Trainer],
+    snapshots: &'a VecDeque<Trainer>,
+    rng: &mut ThreadRng,
+) -> &'a Trainer {
+    if live.len() > 1 && rng.random_bool(CROSS_PLAY_RATE) {
+        let other = (index + rng.random_range(1..live.len())) % live.len();
+        &live[other]
+    } else {
+        &snapshots[rng.random_range(0..snapshots.len())]
+    }
+}
@@ -0,0 +1,54 @@
+use rand::{Rng, rngs::ThreadRng};
+
+use crate::game::{
+    Side,
+    ai::{ACTION_SPACE, Action, encode_ai_action},
+    input::{ButtonFlag, Direction},
+};
+
+/// Distance at which the scripted opponent stops walking in and starts attacking
+const ATTACK_RANGE: f32 = 140.0;
+/// Chance per frame that the scripted opponent blocks instead of attacking when in range
+const BLOCK_CHANCE: f64 = 0.35;
+/// Chance per frame that the scripted opponent jumps in from range
+const JUMP_IN_CHANCE: f64 = 0.02;
+
+/// Uniform random action, used to expose the challenger to out of distribution play
+pub fn random_action(rng: &mut ThreadRng) -> Action {
+    rng.random_range(0..ACTION_SPACE as Action)
+}
+
+/// Simple rushdown heuristic: walk in, then mix attacks with blocking
+pub fn scripted_action(side: Side, distance: f32, rng: &mut ThreadRng) -> Action {
+    let (forward, back, down_back) = match side {
+        Side::Left => (Direction::Right, Direction::Left, Direction::DownLeft),
+        Side::Right => (Direction::Left, Direction::Right, Direction::DownRight),
+    };
+
+    if distance > ATTACK_RANGE {
+        if rng.random_bool(JUMP_IN_CHANCE) {
+            let jump_forward = match side {
+                Side::Left => Direction::UpRight,
+                Side::Right => Direction::UpLeft,
+            };
+            return encode_ai_action(jump_forward, ButtonFlag::NONE);
+        }
+        return encode_ai_action(forward, ButtonFlag::NONE);
+    }
+
+    if rng.random_bool(BLOCK_CHANCE) {
+        let block = if rng.random_bool(0.5) {
+            back
+        } else {
+            down_back
+        };
+        return encode_ai_action(block, ButtonFlag::NONE);
+    }
+
+    let button = match rng.random_range(0..3) {
+        0 => ButtonFlag::L,
+        1 => ButtonFlag::M,
+        _ => ButtonFlag::H,
+    };
+    encode_ai_action(Direction::Neutral, button)
+}
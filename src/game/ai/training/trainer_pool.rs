@@ -2,7 +2,7 @@ use std::{collections::VecDeque, io::Write, time::Instant};
 
 use candle_core::{Device, Result, Tensor};
 use candle_nn::{Sequential, VarMap};
-use rand::rngs::ThreadRng;
+use rand::{Rng, rngs::ThreadRng};
 
 use crate::game::{
     Side,
@@ -10,6 +10,7 @@ use crate::game::{
         env::Environment,
         ppo::{PPOAgent, RolloutBuffer, get_agent_action},
         save_model,
+        training::scripted::{random_action, scripted_action},
     },
 };
 
@@ -18,13 +19,20 @@ const WINRATE_THRESH: f32 = 0.60;
 const WINRATE_WINDOW: usize = 32;
 const MIN_ROUNDS_PER_TRAINER: usize = 16;
 const MAX_GAMES: usize = 3000;
-const STEPS_PER_EPOCH: usize = 8_000;
+pub(super) const STEPS_PER_EPOCH: usize = 8_000;
+
+/// Fraction of sparring rounds played against the scripted heuristic instead of a pool trainer
+const SCRIPTED_ROUND_RATE: f64 = 0.15;
+/// Fraction of sparring rounds played against a uniformly random agent instead of a pool trainer
+const RANDOM_ROUND_RATE: f64 = 0.05;
 
 const EPOCHS: usize = 32;
 const BEST_AGENT_OUTPUT_PATH: &str = "./ai/ppo/best_NEW.safetensors";
 const RUNNER_UP_OUTPUT_PATH: &str = "./ai/ppo/runner_up_NEW.safetensors";
+/// Promoted trainers are copied here every few epochs so they can be fought as a boss ladder
+const LADDER_INTERVAL: usize = 4;
 
-struct Trainer {
+pub(super) struct Trainer {
     policy: Sequential,
     var_map: VarMap,
 }
@@ -35,11 +43,38 @@ impl Trainer {
         Self { policy, var_map }
     }
 
-    fn save(&self, filename: &str) -> Result<()> {
+    /// Frozen copy of an agent that keeps training
+    pub(super) fn snapshot(agent: &PPOAgent, device: &Device) -> Result<Self> {
+        let (policy, var_map) = agent.snapshot_policy(device)?;
+        Ok(Self { policy, var_map })
+    }
+
+    pub(super) fn save(&self, filename: &str) -> Result<()> {
         save_model(&self.var_map, filename)
     }
 }
 
+#[derive(Clone, Copy)]
+pub(super) enum Opponent<'a> {
+    Trainer(&'a Trainer),
+    Scripted,
+    Random,
+}
+
+impl<'a> Opponent<'a> {
+    /// Swaps out the pool trainer for an out of distribution opponent at the configured rates
+    pub(super) fn pick(trainer: &'a Trainer, rng: &mut ThreadRng) -> Self {
+        let roll = rng.random::<f64>();
+        if roll < SCRIPTED_ROUND_RATE {
+            Self::Scripted
+        } else if roll < SCRIPTED_ROUND_RATE + RANDOM_ROUND_RATE {
+            Self::Random
+        } else {
+            Self::Trainer(trainer)
+        }
+    }
+}
+
 struct TrainerPool {
     trainers: VecDeque<Trainer>,
 }
@@ -74,7 +109,7 @@ impl TrainerPool {
     }
 }
 
-struct GameHistory {
+pub(super) struct GameHistory {
     history: VecDeque<(usize, usize)>,
     wins: usize,
     window_games: usize,
@@ -82,7 +117,7 @@ struct GameHistory {
 }
 
 impl GameHistory {
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         Self {
             history: VecDeque::with_capacity(WINRATE_WINDOW),
             wins: 0,
@@ -91,7 +126,7 @@ impl GameHistory {
         }
     }
 
-    fn push(&mut self, wins: usize, games: usize) {
+    pub(super) fn push(&mut self, wins: usize, games: usize) {
         if self.history.len() >= WINRATE_WINDOW {
             let (old_wins, old_games) = self.history.pop_back().unwrap();
             self.wins -= old_wins;
@@ -111,7 +146,10 @@ impl GameHistory {
         self.window_games
     }
 
-    fn win_rate(&self) -> f32 {
+    pub(super) fn win_rate(&self) -> f32 {
+        if self.window_games == 0 {
+            return 0.0;
+        }
         self.wins as f32 / self.window_games as f32
     }
 
@@ -123,7 +161,6 @@ impl GameHistory {
     }
 }
 
-#[allow(dead_code)]
 pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result<()> {
     let mut trainer_pool = TrainerPool::new();
     let first_trainer = Trainer::from_ppo_aget(PPOAgent::new(&device)?);
@@ -151,6 +188,7 @@ pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result
                 }
             }
             let mut wins = 0;
+            let mut games = 0;
             let challenger_side = if epoch.is_multiple_of(2) {
                 Side::Left
             } else {
@@ -158,21 +196,27 @@ pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result
             };
 
             for trainer in trainer_pool.iter() {
+                let opponent = Opponent::pick(trainer, &mut rng);
                 let round_score = fight_trainer(
                     challenger_side,
                     &mut env,
                     &challenger,
-                    trainer,
+                    opponent,
                     &mut buffer,
                     &device,
                     &mut rng,
                 )?;
                 challenger.update(&buffer, &device)?;
                 buffer.reset();
-                wins += round_score;
+
+                // Only rounds against the pool count towards promotion
+                if let Opponent::Trainer(_) = opponent {
+                    wins += round_score;
+                    games += 1;
+                }
             }
 
-            game_history.push(wins, trainer_pool.count());
+            game_history.push(wins, games);
             print!(
                 "\r\x1b[KRounds: {}, WindowRounds: {}, winrate: {}",
                 game_history.total_games(),
@@ -185,6 +229,9 @@ pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result
         challenger.save(BEST_AGENT_OUTPUT_PATH)?;
 
         let new_trainer = Trainer::from_ppo_aget(challenger);
+        if epoch.is_multiple_of(LADDER_INTERVAL) || epoch == EPOCHS {
+            new_trainer.save(&format!("{}/rung_{epoch:03}.safetensors", env.ladder_dir()))?;
+        }
         trainer_pool.push(new_trainer);
         game_history.clear();
     }
@@ -196,12 +243,12 @@ pub fn train(mut env: Environment<'_>, device: Device, start: Instant) -> Result
     Ok(())
 }
 
-/// Returns (wins, games)
-fn fight_trainer(
+/// Returns 1 if the challenger won more rounds than it lost
+pub(super) fn fight_trainer(
     challenger_side: Side,
     env: &mut Environment,
     challenger: &PPOAgent,
-    trainer: &Trainer,
+    opponent: Opponent,
     buffer: &mut RolloutBuffer,
     device: &Device,
     rng: &mut ThreadRng,
@@ -211,7 +258,7 @@ fn fight_trainer(
 
     for step in 0..STEPS_PER_EPOCH {
         let (obs, obs_inv) = env.obs_with_inv(device)?;
-        let actions = take_agent_turns(challenger, trainer, buffer, &obs, &obs_inv, rng)?;
+        let actions = take_agent_turns(env, challenger, opponent, buffer, &obs, &obs_inv, rng)?;
 
         // Update environment
         let (terminal, rewards) = env.step(actions);
@@ -246,8 +293,9 @@ fn fight_trainer(
 }
 
 fn take_agent_turns(
+    env: &Environment,
     challenger: &PPOAgent,
-    trainer: &Trainer,
+    opponent: Opponent,
     buffer: &mut RolloutBuffer,
     obs: &Tensor,
     obs_inv: &Tensor,
@@ -255,7 +303,14 @@ fn take_agent_turns(
 ) -> Result<(u32, u32)> {
     let (action1, logprob, state_val) = challenger.step(obs, rng)?;
     buffer.push_agent(action1, logprob, state_val);
-    let action2 = get_agent_action(&trainer.policy, obs_inv, rng)?;
+    let action2 = match opponent {
+        Opponent::Trainer(trainer) => get_agent_action(&trainer.policy, obs_inv, rng)?,
+        Opponent::Scripted => {
+            let (side, distance) = env.agent2_view();
+            scripted_action(side, distance, rng)
+        }
+        Opponent::Random => random_action(rng),
+    };
 
     Ok((action1, action2))
 }
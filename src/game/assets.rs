@@ -0,0 +1,130 @@
+use std::rc::Rc;
+
+/// Where configs, images and sounds are read from, paths are the ones written in the configs
+///
+/// Errors leave out the path, callers say which file they were after
+pub trait AssetSource {
+    fn read(&self, path: &str) -> Result<Vec<u8>, String>;
+
+    fn exists(&self, path: &str) -> bool;
+
+    /// Names of the files directly inside `dir`, empty if there is no such directory
+    fn list(&self, dir: &str) -> Vec<String>;
+
+    fn read_to_string(&self, path: &str) -> Result<String, String> {
+        String::from_utf8(self.read(path)?).map_err(|err| err.to_string())
+    }
+}
+
+/// Reads everything straight off the disk
+pub struct FileSource;
+
+impl AssetSource for FileSource {
+    fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(path).map_err(|err| err.to_string())
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        std::path::Path::new(path).is_file()
+    }
+
+    fn list(&self, dir: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                if !entry.file_type().ok()?.is_file() {
+                    return None;
+                }
+                Some(entry.file_name().to_string_lossy().into_owned())
+            })
+            .collect()
+    }
+}
+
+/// The archive at `archive` if one is given, the disk otherwise
+pub fn open(archive: Option<&str>) -> Result<Rc<dyn AssetSource>, String> {
+    match archive {
+        #[cfg(feature = "archive")]
+        Some(archive) => Ok(Rc::new(archive::ArchiveSource::open(archive)?)),
+        #[cfg(not(feature = "archive"))]
+        Some(archive) => Err(format!(
+            "Can't read '{archive}', this build has no archive support"
+        )),
+        None => Ok(Rc::new(FileSource)),
+    }
+}
+
+/// Config paths start with "./" and archive entries don't
+#[cfg_attr(not(feature = "archive"), allow(dead_code))]
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+#[cfg(feature = "archive")]
+mod archive {
+    use std::{collections::HashMap, io::Read};
+
+    use crate::game::assets::{AssetSource, FileSource, normalize};
+
+    /// Every file of a zip archive held in memory, files it doesn't have are read from disk so
+    /// settings, replays and mods keep working
+    pub struct ArchiveSource {
+        files: HashMap<String, Vec<u8>>,
+    }
+
+    impl ArchiveSource {
+        pub fn open(path: &str) -> Result<Self, String> {
+            let file = std::fs::File::open(path).map_err(|err| format!("File: '{path}': {err}"))?;
+            let mut archive =
+                zip::ZipArchive::new(file).map_err(|err| format!("File: '{path}': {err}"))?;
+            let mut files = HashMap::new();
+            for i in 0..archive.len() {
+                let mut entry = archive
+                    .by_index(i)
+                    .map_err(|err| format!("File: '{path}': {err}"))?;
+                if entry.is_dir() {
+                    continue;
+                }
+                let mut bytes = Vec::with_capacity(entry.size() as usize);
+                entry
+                    .read_to_end(&mut bytes)
+                    .map_err(|err| format!("File: '{path}': {}: {err}", entry.name()))?;
+                files.insert(normalize(entry.name()), bytes);
+            }
+            if cfg!(feature = "debug") {
+                println!("Opened archive '{path}' with {} files", files.len());
+            }
+            Ok(Self { files })
+        }
+    }
+
+    impl AssetSource for ArchiveSource {
+        fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+            match self.files.get(&normalize(path)) {
+                Some(bytes) => Ok(bytes.clone()),
+                None => FileSource.read(path),
+            }
+        }
+
+        fn exists(&self, path: &str) -> bool {
+            self.files.contains_key(&normalize(path)) || FileSource.exists(path)
+        }
+
+        fn list(&self, dir: &str) -> Vec<String> {
+            let prefix = normalize(dir).trim_end_matches('/').to_string() + "/";
+            let mut names = FileSource.list(dir);
+            for path in self.files.keys() {
+                if let Some(name) = path.strip_prefix(&prefix)
+                    && !name.contains('/')
+                    && !names.iter().any(|listed| listed == name)
+                {
+                    names.push(name.to_string());
+                }
+            }
+            names
+        }
+    }
+}
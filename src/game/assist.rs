@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+use sdl3::render::FPoint;
+
+use crate::game::{
+    Side,
+    boxes::{HitBox, HurtBox},
+    character::MoveInput,
+    projectile::Flight,
+    render::animation::Animation,
+};
+
+/// Helper a character can call in, it acts on its own while the caller keeps fighting
+pub struct AssistData {
+    /// Frames after the assist leaves before it can be called again
+    cooldown: usize,
+    /// Damage the assist takes before it is knocked out of its move
+    hp: f32,
+    moves: Vec<AssistMove>,
+}
+
+impl AssistData {
+    pub fn new(cooldown: usize, hp: f32, moves: Vec<AssistMove>) -> Self {
+        Self {
+            cooldown,
+            hp,
+            moves,
+        }
+    }
+
+    pub fn cooldown(&self) -> usize {
+        self.cooldown
+    }
+
+    pub fn hp(&self) -> f32 {
+        self.hp
+    }
+
+    pub fn moves(&self) -> &[AssistMove] {
+        &self.moves
+    }
+}
+
+/// One of the assist's attacks, offset and velocity point the way the caller faces
+pub struct AssistMove {
+    input: MoveInput,
+    offset: FPoint,
+    vel: FPoint,
+    duration: usize,
+    active: Range<usize>,
+    hit_box: HitBox,
+    /// Out for the whole move, the opponent's strikes and projectiles land on them
+    hurt_boxes: Vec<HurtBox>,
+    animation: Animation,
+}
+
+impl AssistMove {
+    pub fn new(
+        input: MoveInput,
+        offset: FPoint,
+        vel: FPoint,
+        duration: usize,
+        active: Range<usize>,
+        hit_box: HitBox,
+        hurt_boxes: Vec<HurtBox>,
+        animation: Animation,
+    ) -> Self {
+        Self {
+            input,
+            offset,
+            vel,
+            duration,
+            active,
+            hit_box,
+            hurt_boxes,
+            animation,
+        }
+    }
+
+    pub fn input(&self) -> &MoveInput {
+        &self.input
+    }
+
+    pub fn hurt_boxes(&self) -> &[HurtBox] {
+        &self.hurt_boxes
+    }
+
+    pub fn animation(&self) -> &Animation {
+        &self.animation
+    }
+}
+
+/// An assist on screen, like projectiles it only keeps indices so it rolls back with the caller's state
+#[derive(Clone, PartialEq, Debug)]
+pub struct Assist {
+    mov: usize,
+    flight: Flight,
+    hp: f32,
+    /// Each call can only land once
+    hit_connected: bool,
+}
+
+impl Assist {
+    pub fn spawn(data: &AssistData, mov: usize, caller_pos: FPoint, side: Side) -> Self {
+        Self {
+            mov,
+            flight: Flight::launch(caller_pos, data.moves[mov].offset, side),
+            hp: data.hp,
+            hit_connected: false,
+        }
+    }
+
+    /// Returns false once the assist has finished its move
+    pub fn update(&mut self, data: &AssistMove) -> bool {
+        self.flight.step(data.vel, data.duration)
+    }
+
+    /// The move's hit box while it is active and hasn't landed yet
+    pub fn active_hit_box<'a>(&self, data: &'a AssistMove) -> Option<&'a HitBox> {
+        (!self.hit_connected && data.active.contains(&self.flight.frame())).then_some(&data.hit_box)
+    }
+
+    pub fn connect(&mut self) {
+        self.hit_connected = true;
+    }
+
+    /// Returns false once the damage has knocked the assist out
+    pub fn take_hit(&mut self, dmg: f32) -> bool {
+        self.hp -= dmg;
+        self.hp > 0.0
+    }
+
+    pub fn mov(&self) -> usize {
+        self.mov
+    }
+
+    pub fn flight(&self) -> &Flight {
+        &self.flight
+    }
+
+    pub fn pos(&self) -> FPoint {
+        self.flight.pos()
+    }
+
+    pub fn side(&self) -> Side {
+        self.flight.side()
+    }
+
+    pub fn frame(&self) -> usize {
+        self.flight.frame()
+    }
+}
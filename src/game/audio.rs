@@ -0,0 +1,200 @@
+use std::{collections::HashMap, io::Cursor, rc::Rc, sync::Arc};
+
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source, source::ChannelVolume};
+
+use crate::game::assets::AssetSource;
+
+/// Ticks a track takes to fade in, or out once another replaces it
+const CROSSFADE_FRAMES: usize = 60;
+const MUSIC_VOLUME: f32 = 0.6;
+/// Share of the music volume left while ducked
+const DUCKED_VOLUME: f32 = 0.35;
+/// Ticks the music takes to duck, or come back up
+const DUCK_FRAMES: usize = 12;
+
+/// A sound effect made during a game frame, played once the tick is over
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SoundCue {
+    /// 0 for player1, 1 for player2, whose sounds `sound` indexes into
+    pub player: usize,
+    pub sound: usize,
+    /// -1 is all the way left, 1 all the way right
+    pub pan: f32,
+}
+
+/// A looping track and how far it has faded in
+struct Track {
+    path: String,
+    sink: Sink,
+    frame: usize,
+}
+
+impl Track {
+    /// `level` is the volume once fully faded in
+    fn volume(&self, level: f32) -> f32 {
+        level * self.frame as f32 / CROSSFADE_FRAMES as f32
+    }
+}
+
+/// Plays the music, without an output device everything is silently skipped
+pub struct Audio {
+    /// The stream stops playing once dropped, so it is kept with its handle
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    assets: Rc<dyn AssetSource>,
+    music: Option<Track>,
+    /// Replaced tracks still fading out, stopped once silent
+    fading: Vec<Track>,
+    /// Sound effects are read the first time they play
+    effects: HashMap<String, Arc<[u8]>>,
+    ducked: bool,
+    /// Ticks into ducking, eases back down to 0 once no longer ducked
+    duck_frame: usize,
+    /// Picked in the settings, 0 to 1
+    music_volume: f32,
+    effects_volume: f32,
+}
+
+impl Audio {
+    pub fn new(assets: Rc<dyn AssetSource>) -> Self {
+        let output = OutputStream::try_default()
+            .inspect_err(|err| {
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] No audio output, playing without sound: {err}");
+                }
+            })
+            .ok();
+        Self {
+            output,
+            assets,
+            music: None,
+            fading: Vec::new(),
+            effects: HashMap::new(),
+            ducked: false,
+            duck_frame: 0,
+            music_volume: 1.0,
+            effects_volume: 1.0,
+        }
+    }
+
+    /// Crossfades into the track at `path`, or out to silence, the track already playing carries on
+    pub fn play_music(&mut self, path: Option<&str>) {
+        if self.music.as_ref().map(|track| track.path.as_str()) == path {
+            return;
+        }
+        self.fading.extend(self.music.take());
+
+        let (Some((_, handle)), Some(path)) = (&self.output, path) else {
+            return;
+        };
+        match open_track(handle, self.assets.as_ref(), path) {
+            Ok(sink) => {
+                self.music = Some(Track {
+                    path: path.to_string(),
+                    sink,
+                    frame: 0,
+                });
+            }
+            Err(err) => {
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] Failed to play music: {err}");
+                }
+            }
+        }
+    }
+
+    /// Plays the sound effect at `path` once over the music, panned by `pan`
+    pub fn play_sound(&mut self, path: &str, pan: f32) {
+        let Some((_, handle)) = &self.output else {
+            return;
+        };
+        let effects = &mut self.effects;
+        let volume = self.effects_volume;
+        if let Err(err) = play_effect(handle, self.assets.as_ref(), effects, path, pan, volume) {
+            if cfg!(feature = "debug") {
+                println!("[WARNING] Failed to play sound: {err}");
+            }
+        }
+    }
+
+    /// Turns the music down for cinematics, it comes back up once they're over
+    pub fn set_ducked(&mut self, ducked: bool) {
+        self.ducked = ducked;
+    }
+
+    /// Both 0 to 1, the music picks it up on the next `update`
+    pub fn set_volumes(&mut self, music: f32, effects: f32) {
+        self.music_volume = music;
+        self.effects_volume = effects;
+    }
+
+    /// Steps the crossfades and ducking, once per tick
+    pub fn update(&mut self) {
+        self.duck_frame = if self.ducked {
+            (self.duck_frame + 1).min(DUCK_FRAMES)
+        } else {
+            self.duck_frame.saturating_sub(1)
+        };
+        let duck = 1.0 - (1.0 - DUCKED_VOLUME) * self.duck_frame as f32 / DUCK_FRAMES as f32;
+        let level = MUSIC_VOLUME * self.music_volume * duck;
+
+        if let Some(track) = &mut self.music {
+            track.frame = (track.frame + 1).min(CROSSFADE_FRAMES);
+            track.sink.set_volume(track.volume(level));
+        }
+        for track in &mut self.fading {
+            track.frame = track.frame.saturating_sub(1);
+            track.sink.set_volume(track.volume(level));
+        }
+        // Dropping a sink stops it
+        self.fading.retain(|track| track.frame > 0);
+    }
+}
+
+/// Starts the track looping silently, `update` fades it in
+fn open_track(
+    handle: &OutputStreamHandle,
+    assets: &dyn AssetSource,
+    path: &str,
+) -> Result<Sink, String> {
+    let bytes = assets
+        .read(path)
+        .map_err(|err| format!("File: '{path}': {err}"))?;
+    let source =
+        Decoder::new_looped(Cursor::new(bytes)).map_err(|err| format!("File: '{path}': {err}"))?;
+    let sink = Sink::try_new(handle).map_err(|err| format!("File: '{path}': {err}"))?;
+    sink.set_volume(0.0);
+    sink.append(source);
+    Ok(sink)
+}
+
+fn play_effect(
+    handle: &OutputStreamHandle,
+    assets: &dyn AssetSource,
+    effects: &mut HashMap<String, Arc<[u8]>>,
+    path: &str,
+    pan: f32,
+    volume: f32,
+) -> Result<(), String> {
+    let bytes = match effects.get(path) {
+        Some(bytes) => bytes.clone(),
+        None => {
+            let bytes: Arc<[u8]> = assets
+                .read(path)
+                .map_err(|err| format!("File: '{path}': {err}"))?
+                .into();
+            effects.insert(path.to_string(), bytes.clone());
+            bytes
+        }
+    };
+    let source =
+        Decoder::new(Cursor::new(bytes)).map_err(|err| format!("File: '{path}': {err}"))?;
+    // Constant power, so a sound doesn't dip in loudness as it crosses the middle
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+    let panned = ChannelVolume::new(
+        source.convert_samples(),
+        vec![angle.cos() * volume, angle.sin() * volume],
+    );
+    handle
+        .play_raw(panned)
+        .map_err(|err| format!("File: '{path}': {err}"))
+}
@@ -9,6 +9,22 @@ pub enum BlockType {
     High,
 }
 
+/// Picks which of the victim's ground hit reactions plays
+#[derive(Clone, Copy, Debug)]
+pub enum HitStrength {
+    Light,
+    Medium,
+    Heavy,
+}
+
+/// What an attack counts as when checking invulnerability
+#[derive(Clone, Copy, Debug)]
+pub enum AttackKind {
+    Strike,
+    Throw,
+    Projectile,
+}
+
 #[derive(Clone, Debug)]
 pub struct HitBox {
     pos: FRect,
@@ -17,6 +33,82 @@ pub struct HitBox {
     block_stun: u32,
     cancel_window: usize,
     block_type: BlockType,
+    juggle_points: u32,
+    proration: Proration,
+    /// Which hit of a multi-hit move the box belongs to
+    hit: u32,
+    launch: Option<Launch>,
+    /// Frames both players freeze for when the hit connects
+    hit_stop: usize,
+    /// Added to the victim's dizzy gauge when the hit lands unblocked
+    dizzy: f32,
+    strength: HitStrength,
+    /// Push on block, the defender takes it unless cornered, then it goes to the attacker
+    block_push_back: f32,
+    /// Indices into the attacker's sounds, played on impact
+    hit_sound: Option<usize>,
+    block_sound: Option<usize>,
+}
+
+/// Knockback given to a launched victim, x pushes away from the attacker
+#[derive(Clone, Copy, Debug)]
+pub struct Launch {
+    vel: FPoint,
+    /// Frames before the victim can recover in the air, never if None
+    untech: Option<usize>,
+}
+
+impl Launch {
+    pub fn new(vel: FPoint, untech: Option<usize>) -> Self {
+        Self { vel, untech }
+    }
+
+    pub fn vel(&self) -> FPoint {
+        self.vel
+    }
+
+    pub fn untech(&self) -> Option<usize> {
+        self.untech
+    }
+}
+
+/// How much a hit cuts the damage of the rest of the combo
+#[derive(Clone, Copy, Debug)]
+pub struct Proration {
+    /// Taken off the combo scaling when the hit starts the combo
+    initial: f32,
+    /// Taken off the combo scaling when the hit lands mid combo
+    forced: f32,
+    /// Share of the damage the hit deals however scaled the combo is, for supers
+    min_dmg_scaling: f32,
+}
+
+impl Proration {
+    pub fn new(initial: f32, forced: f32, min_dmg_scaling: f32) -> Self {
+        Self {
+            initial,
+            forced,
+            min_dmg_scaling,
+        }
+    }
+
+    pub fn initial(&self) -> f32 {
+        self.initial
+    }
+
+    pub fn forced(&self) -> f32 {
+        self.forced
+    }
+
+    pub fn min_dmg_scaling(&self) -> f32 {
+        self.min_dmg_scaling
+    }
+}
+
+impl Default for Proration {
+    fn default() -> Self {
+        Self::new(0.1, 0.1, 0.1)
+    }
 }
 
 impl HitBox {
@@ -27,6 +119,7 @@ impl HitBox {
         hit_stun: u32,
         cancel_window: usize,
         block_type: BlockType,
+        juggle_points: u32,
     ) -> Self {
         Self {
             pos,
@@ -35,9 +128,60 @@ impl HitBox {
             hit_stun,
             cancel_window,
             block_type,
+            juggle_points,
+            proration: Proration::default(),
+            hit: 0,
+            launch: None,
+            hit_stop: 0,
+            dizzy: 0.0,
+            strength: HitStrength::Medium,
+            block_push_back: 0.0,
+            hit_sound: None,
+            block_sound: None,
         }
     }
 
+    pub fn with_sounds(mut self, hit_sound: Option<usize>, block_sound: Option<usize>) -> Self {
+        self.hit_sound = hit_sound;
+        self.block_sound = block_sound;
+        self
+    }
+
+    pub fn with_block_push_back(mut self, block_push_back: f32) -> Self {
+        self.block_push_back = block_push_back;
+        self
+    }
+
+    pub fn with_strength(mut self, strength: HitStrength) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    pub fn with_dizzy(mut self, dizzy: f32) -> Self {
+        self.dizzy = dizzy;
+        self
+    }
+
+    pub fn with_hit_stop(mut self, hit_stop: usize) -> Self {
+        self.hit_stop = hit_stop;
+        self
+    }
+
+    pub fn with_launch(mut self, launch: Option<Launch>) -> Self {
+        self.launch = launch;
+        self
+    }
+
+    pub fn with_hit(mut self, hit: u32) -> Self {
+        self.hit = hit;
+        self
+    }
+
+    pub fn with_proration(mut self, proration: Proration) -> Self {
+        self.proration = proration;
+        self
+    }
+
     pub fn on_side(&self, side: Side, offset: FPoint) -> FRect {
         match side {
             Side::Left => FRect {
@@ -59,6 +203,26 @@ impl HitBox {
         self.dmg
     }
 
+    pub fn scale_dmg(mut self, dmg_mult: f32) -> Self {
+        self.dmg *= dmg_mult;
+        self
+    }
+
+    pub fn set_dmg(&mut self, dmg: f32) {
+        self.dmg = dmg;
+    }
+
+    /// Hits that always launch keep their hit stun, it isn't a frame count
+    pub fn set_hit_stun(&mut self, hit_stun: u32) {
+        if self.hit_stun != u32::MAX {
+            self.hit_stun = hit_stun;
+        }
+    }
+
+    pub fn set_block_stun(&mut self, block_stun: u32) {
+        self.block_stun = block_stun;
+    }
+
     pub fn block_stun(&self) -> usize {
         self.block_stun as usize
     }
@@ -74,6 +238,107 @@ impl HitBox {
     pub fn block_type(&self) -> BlockType {
         self.block_type
     }
+
+    /// Share of the victim's juggle limit this hit uses up when it lands in the air
+    pub fn juggle_points(&self) -> u32 {
+        self.juggle_points
+    }
+
+    pub fn proration(&self) -> Proration {
+        self.proration
+    }
+
+    pub fn hit(&self) -> u32 {
+        self.hit
+    }
+
+    pub fn launch(&self) -> Option<Launch> {
+        self.launch
+    }
+
+    pub fn dizzy(&self) -> f32 {
+        self.dizzy
+    }
+
+    pub fn strength(&self) -> HitStrength {
+        self.strength
+    }
+
+    pub fn block_push_back(&self) -> f32 {
+        self.block_push_back
+    }
+
+    pub fn hit_stop(&self) -> usize {
+        self.hit_stop
+    }
+
+    pub fn sound(&self, blocked: bool) -> Option<usize> {
+        if blocked {
+            self.block_sound
+        } else {
+            self.hit_sound
+        }
+    }
+}
+
+/// Grab range of a throw, ignores blocking but only catches grounded opponents
+#[derive(Clone, Debug)]
+pub struct ThrowBox {
+    pos: FRect,
+    dmg: f32,
+    tech_window: usize,
+    release_offset: FPoint,
+}
+
+impl ThrowBox {
+    pub fn new(pos: FRect, dmg: f32, tech_window: usize, release_offset: FPoint) -> Self {
+        Self {
+            pos,
+            dmg,
+            tech_window,
+            release_offset,
+        }
+    }
+
+    pub fn on_side(&self, side: Side, offset: FPoint) -> FRect {
+        match side {
+            Side::Left => FRect {
+                x: self.pos.x + offset.x,
+                y: self.pos.y + offset.y,
+                w: self.pos.w,
+                h: self.pos.h,
+            },
+            Side::Right => FRect {
+                x: -self.pos.x + offset.x - self.pos.w,
+                y: self.pos.y + offset.y,
+                w: self.pos.w,
+                h: self.pos.h,
+            },
+        }
+    }
+
+    /// Where the thrown player ends up, x is relative to the way the thrower faces
+    pub fn release_pos(&self, side: Side, thrower_pos: FPoint) -> FPoint {
+        match side {
+            Side::Left => FPoint::new(
+                thrower_pos.x + self.release_offset.x,
+                thrower_pos.y + self.release_offset.y,
+            ),
+            Side::Right => FPoint::new(
+                thrower_pos.x - self.release_offset.x,
+                thrower_pos.y + self.release_offset.y,
+            ),
+        }
+    }
+
+    pub fn dmg(&self) -> f32 {
+        self.dmg
+    }
+
+    /// Max frames between both players' throw inputs for the throw to be broken
+    pub fn tech_window(&self) -> usize {
+        self.tech_window
+    }
 }
 
 pub struct HurtBox {
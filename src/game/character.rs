@@ -2,12 +2,16 @@ use std::ops::Range;
 
 use crate::game::{
     Side,
-    boxes::{BlockType, CollisionBox, HitBox, HurtBox},
+    assist::{Assist, AssistData, AssistMove},
+    boxes::{AttackKind, BlockType, CollisionBox, HitBox, HitStrength, HurtBox, Launch, ThrowBox},
     input::{ButtonFlag, Inputs, RelativeDirection, RelativeMotion},
-    physics::{friction_system, gravity_system, velocity_system},
+    physics::{
+        check_hit_box_clash, check_hit_collisions, friction_system, gravity_system, velocity_system,
+    },
+    projectile::{Projectile, ProjectileData},
     render::{
         Camera, animation::Animation, draw_collision_box_system, draw_hit_boxes_system,
-        draw_hurt_boxes_system,
+        draw_hit_flash_system, draw_hurt_boxes_system, draw_shadow_system, draw_throw_box_system,
     },
     stage::Stage,
 };
@@ -18,16 +22,44 @@ use sdl3::{
 };
 
 type StateIndex = usize;
-const HIT_GRAVITY_MULT: f32 = 1.2;
+/// Extra gravity for every hit taken in the air, so long juggles drop sooner
+const JUGGLE_GRAVITY_PER_HIT: f32 = 0.2;
+/// State every character starts in, soft knockdowns land straight into it
+const NEUTRAL_STATE: StateIndex = 0;
 const HIT_PUSH_BACK: f32 = -6.0;
 const CHIP_DMG_PERCENTAGE: f32 = 0.1;
-const COMBO_SCALE_PER_HIT: f32 = 0.1;
-const MIN_COMBO_SCALING: f32 = 0.1;
+/// Share of the damage from follow up combo hits that can be recovered
+const COMBO_RECOVERABLE_SHARE: f32 = 0.5;
+/// Frames without taking damage before recoverable health starts to come back
+const RECOVERY_DELAY: usize = 90;
+const RECOVERY_PER_FRAME: f32 = 0.5;
+pub const MAX_METER: f32 = 100.0;
+/// Meter the attacker gains per point of damage dealt, the defender gains half
+const METER_PER_DMG: f32 = 0.2;
+const GUARD_CANCEL_COST: f32 = 50.0;
+/// Frames a dizzied player is helpless for
+const DIZZY_FRAMES: usize = 120;
+/// Frames without getting hit before the dizzy gauge starts to drain
+const DIZZY_DECAY_DELAY: usize = 60;
+const DIZZY_DECAY_PER_FRAME: f32 = 2.0;
+/// Stun frames that serialize as 1.0
+const STUN_SERIAL_SCALE: f32 = 60.0;
+/// States past this many only show up in the frame data features of the observation
+const SERIAL_STATE_SLOTS: usize = 25;
+const THROW_TECH_STUN: usize = 12;
+/// Frames after getting up from a knockdown that throws whiff
+const WAKEUP_THROW_INVULN: usize = 6;
+const THROW_TECH_PUSH_BACK: f32 = -8.0;
+/// Frames a player flashes after absorbing a hit with armor or a guard point
+const HIT_FLASH_FRAMES: usize = 8;
+/// Length of the observation before the buff features
+const BUFF_SERIAL_START: usize = 43;
 
 pub struct StateData {
     // Cancel data
     cancel_window: Range<usize>,
-    cancel_options: Range<usize>,
+    cancel_options: CancelOptions,
+    chains: Vec<Chain>,
     // Boxes
     hit_boxes_start: usize,
     hurt_boxes_start: usize,
@@ -35,9 +67,14 @@ pub struct StateData {
     start_behaviors: StartBehavior,
     flags: StateFlags,
     end_behaviors: EndBehavior,
+    events: Vec<FrameEvent>,
+    super_flash: usize,
+    /// Played when the state starts, index into the character's sounds
+    sound: Option<usize>,
 
     // Physics
     collision: CollisionBox,
+    throw: Option<ThrowData>,
 
     // Render
     animation: Animation,
@@ -46,40 +83,193 @@ pub struct StateData {
 impl StateData {
     pub fn new(
         cancel_window: Range<usize>,
-        cancel_options: Range<usize>,
+        cancel_options: CancelOptions,
+        chains: Vec<Chain>,
         hit_boxes_start: usize,
         hurt_boxes_start: usize,
         start_behaviors: StartBehavior,
         flags: StateFlags,
         end_behaviors: EndBehavior,
+        events: Vec<FrameEvent>,
         collision: CollisionBox,
+        throw: Option<ThrowData>,
         animation: Animation,
     ) -> Self {
         Self {
             cancel_window,
             cancel_options,
+            chains,
             hit_boxes_start,
             hurt_boxes_start,
             start_behaviors,
             flags,
             end_behaviors,
+            events,
+            super_flash: 0,
+            sound: None,
             collision,
+            throw,
             animation,
         }
     }
+
+    /// Freezes the opponent for `frames` once the state starts
+    pub fn with_super_flash(mut self, frames: usize) -> Self {
+        self.super_flash = frames;
+        self
+    }
+
+    pub fn with_sound(mut self, sound: Option<usize>) -> Self {
+        self.sound = sound;
+        self
+    }
+}
+
+/// Ranges into the cancel options run, which one is open depends on how the state's attack went
+pub struct CancelOptions {
+    on_hit: Range<usize>,
+    on_block: Range<usize>,
+    on_whiff: Range<usize>,
+}
+
+impl CancelOptions {
+    pub fn new(on_hit: Range<usize>, on_block: Range<usize>, on_whiff: Range<usize>) -> Self {
+        Self {
+            on_hit,
+            on_block,
+            on_whiff,
+        }
+    }
+}
+
+impl StateData {
+    /// Free to act, the state is in its cancel window with moves open even without landing anything
+    fn actionable(&self, frame: usize) -> bool {
+        self.cancel_window.contains(&frame) && !self.cancel_options.on_whiff.is_empty()
+    }
+}
+
+/// Timed modifiers started by an `ApplyBuff` event, they outlive the state that applied them
+pub struct Buff {
+    duration: usize,
+    dmg_mult: f32,
+    speed_mult: f32,
+    /// Open on top of the current state's cancel options during its cancel window
+    cancel_options: Vec<StateIndex>,
+}
+
+impl Buff {
+    pub fn new(
+        duration: usize,
+        dmg_mult: f32,
+        speed_mult: f32,
+        cancel_options: Vec<StateIndex>,
+    ) -> Self {
+        Self {
+            duration,
+            dmg_mult,
+            speed_mult,
+            cancel_options,
+        }
+    }
+}
+
+/// Only one buff runs at a time, applying another replaces it
+#[derive(Clone, PartialEq, Debug)]
+struct ActiveBuff {
+    buff: usize,
+    frames_left: usize,
+}
+
+/// Target combo, the input only leads `into` its move from this state after an attack connects
+pub struct Chain {
+    window: Range<usize>,
+    input: MoveInput,
+    into: StateIndex,
+}
+
+impl Chain {
+    pub fn new(window: Range<usize>, input: MoveInput, into: StateIndex) -> Self {
+        Self {
+            window,
+            input,
+            into,
+        }
+    }
+}
+
+/// Fires `event` when its state reaches `frame`
+pub struct FrameEvent {
+    frame: usize,
+    event: AnimationEvent,
+}
+
+impl FrameEvent {
+    pub fn new(frame: usize, event: AnimationEvent) -> Self {
+        Self { frame, event }
+    }
+}
+
+pub enum AnimationEvent {
+    SetVel {
+        x: f32,
+        y: f32,
+    },
+    AddFrictionVel {
+        x: f32,
+        y: f32,
+    },
+    /// Turns invulnerability flags on or off until the state ends
+    SetInvuln {
+        flags: StateFlags,
+        enabled: bool,
+    },
+    SpawnProjectile(ProjectileData),
+    /// Swaps the movelist cancels pick from
+    SetStance(usize),
+    ApplyBuff(usize),
+}
+
+/// Marks a state as a throw, the box grabs during the active frames
+pub struct ThrowData {
+    active: Range<usize>,
+    throw_box: ThrowBox,
+}
+
+impl ThrowData {
+    pub fn new(active: Range<usize>, throw_box: ThrowBox) -> Self {
+        Self { active, throw_box }
+    }
 }
 
 pub struct Context {
     name: String,
     // Init data
     max_hp: f32,
+    juggle_limit: u32,
+    dizzy_threshold: f32,
+    gravity: f32,
+    /// Multiplies the friction velocity every frame, lower stops pushback sooner
+    friction: f32,
     start_side: Side,
     start_pos: FPoint,
 
     // Special cached states
     block_stun_state: StateIndex,
-    ground_hit_state: StateIndex,
+    /// Ground hit reactions by light, medium and heavy strength
+    ground_hit_states: [StateIndex; 3],
     launch_hit_state: StateIndex,
+    dizzy_state: StateIndex,
+    /// Entered from a hit state once per round to escape a combo
+    burst_state: Option<StateIndex>,
+    /// Entered from the launch state by pressing a button once the untech time is over
+    air_recovery_state: Option<StateIndex>,
+    /// Entered from block stun by spending meter
+    guard_cancel_state: Option<StateIndex>,
+    /// Per stance, the move each cancel option turns into, stance 0 is the base movelist
+    stances: Vec<Vec<StateIndex>>,
+    buffs: Vec<Buff>,
+    assist: Option<AssistData>,
 
     // Run length stuff
     run_length_hit_boxes: Vec<(usize, Range<usize>)>, // Frames active, global hitboxes index range
@@ -92,17 +282,98 @@ pub struct Context {
     // Moves/states
     state_inputs: Vec<MoveInput>,
     states: Vec<StateData>,
+    frame_data: Vec<FrameData>,
+
+    /// Shown on the victory screen, the first state's animation stands in without one
+    win_pose: Option<Animation>,
+    /// Config names of the states, for the move list
+    move_names: Vec<String>,
+    /// Paths of the sounds the character's states and hit boxes play
+    sounds: Vec<String>,
+}
+
+/// Startup/active/recovery lengths of a state, derived from its hit boxes and end frame
+#[derive(Clone, Copy, Default)]
+struct FrameData {
+    startup: usize,
+    active: usize,
+    recovery: usize,
+}
+
+impl FrameData {
+    fn new(state: &StateData, run_length_hit_boxes: &[(usize, Range<usize>)]) -> Self {
+        let mut frame = 0usize;
+        let mut first_active = None;
+        let mut active_end = 0usize;
+        for (frames, range) in &run_length_hit_boxes[state.hit_boxes_start..] {
+            if !range.is_empty() {
+                first_active.get_or_insert(frame);
+                active_end = frame.saturating_add(*frames);
+            }
+            if *frames == usize::MAX {
+                break;
+            }
+            frame += frames;
+        }
+
+        let Some(startup) = first_active else {
+            return Self::default();
+        };
+        let total = match state.end_behaviors {
+            EndBehavior::OnFrameXToStateY { x, .. } => x.max(startup),
+            _ => active_end,
+        };
+        let active_end = active_end.min(total);
+
+        Self {
+            startup,
+            active: active_end - startup,
+            recovery: total - active_end,
+        }
+    }
+
+    /// Normalized (startup, active, recovery) progress, all zeros for states that never hit
+    fn progress(&self, frame: usize) -> [f32; 3] {
+        if self.active == 0 {
+            return [0.0; 3];
+        }
+        let phase = |start: usize, len: usize| {
+            if frame < start {
+                0.0
+            } else if len == 0 {
+                1.0
+            } else {
+                ((frame - start) as f32 / len as f32).min(1.0)
+            }
+        };
+        [
+            phase(0, self.startup),
+            phase(self.startup, self.active),
+            phase(self.startup + self.active, self.recovery),
+        ]
+    }
 }
 
 impl Context {
     pub fn new(
         name: String,
         max_hp: f32,
+        juggle_limit: u32,
+        dizzy_threshold: f32,
+        gravity: f32,
+        friction: f32,
         start_side: Side,
         start_pos: FPoint,
         block_stun_state: StateIndex,
-        ground_hit_state: StateIndex,
+        ground_hit_states: [StateIndex; 3],
         launch_hit_state: StateIndex,
+        dizzy_state: StateIndex,
+        burst_state: Option<StateIndex>,
+        air_recovery_state: Option<StateIndex>,
+        guard_cancel_state: Option<StateIndex>,
+        stances: Vec<Vec<StateIndex>>,
+        buffs: Vec<Buff>,
+        assist: Option<AssistData>,
         run_length_hit_boxes: Vec<(usize, Range<usize>)>,
         run_length_hurt_boxes: Vec<(usize, Range<usize>)>,
         run_length_cancel_options: Vec<StateIndex>,
@@ -111,14 +382,30 @@ impl Context {
         state_inputs: Vec<MoveInput>,
         states: Vec<StateData>,
     ) -> Self {
+        let frame_data = states
+            .iter()
+            .map(|state| FrameData::new(state, &run_length_hit_boxes))
+            .collect();
+
         Self {
             name,
             max_hp,
+            juggle_limit,
+            dizzy_threshold,
+            gravity,
+            friction,
             start_side,
             start_pos,
             block_stun_state,
-            ground_hit_state,
+            ground_hit_states,
             launch_hit_state,
+            dizzy_state,
+            burst_state,
+            air_recovery_state,
+            guard_cancel_state,
+            stances,
+            buffs,
+            assist,
 
             run_length_hit_boxes,
             run_length_hurt_boxes,
@@ -128,8 +415,27 @@ impl Context {
 
             state_inputs,
             states,
+            frame_data,
+            win_pose: None,
+            move_names: Vec::new(),
+            sounds: Vec::new(),
         }
     }
+
+    pub fn with_win_pose(mut self, win_pose: Option<Animation>) -> Self {
+        self.win_pose = win_pose;
+        self
+    }
+
+    pub fn with_move_names(mut self, move_names: Vec<String>) -> Self {
+        self.move_names = move_names;
+        self
+    }
+
+    pub fn with_sounds(mut self, sounds: Vec<String>) -> Self {
+        self.sounds = sounds;
+        self
+    }
 }
 
 impl Context {
@@ -159,9 +465,82 @@ impl Context {
         }
     }
 
+    pub fn start_side(&self) -> Side {
+        self.start_side
+    }
+
     pub fn start_pos(&self) -> FPoint {
         self.start_pos
     }
+
+    fn projectile_data(&self, (state, event): (StateIndex, usize)) -> Option<&ProjectileData> {
+        match &self.states[state].events.get(event)?.event {
+            AnimationEvent::SpawnProjectile(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sound(&self, sound: usize) -> &str {
+        &self.sounds[sound]
+    }
+
+    pub fn win_pose(&self) -> &Animation {
+        self.win_pose.as_ref().unwrap_or(&self.states[0].animation)
+    }
+
+    /// (move name, numpad command) of every move done with a button or a motion
+    pub fn move_list(&self) -> Vec<(&str, String)> {
+        self.move_names
+            .iter()
+            .zip(&self.state_inputs)
+            .filter_map(|(name, input)| Some((name.as_str(), input.notation()?)))
+            .collect()
+    }
+
+    pub fn states_len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Every hit box of the state in config order, across all of its frames
+    pub fn state_hit_boxes(&self, state: StateIndex) -> &[HitBox] {
+        let range = self.state_hit_box_range(state);
+        &self.hit_box_data[range]
+    }
+
+    pub fn state_hit_boxes_mut(&mut self, state: StateIndex) -> &mut [HitBox] {
+        let range = self.state_hit_box_range(state);
+        &mut self.hit_box_data[range]
+    }
+
+    fn state_hit_box_range(&self, state: StateIndex) -> Range<usize> {
+        let runs = &self.run_length_hit_boxes[self.states[state].hit_boxes_start..];
+        let last = runs
+            .iter()
+            .position(|(frames, _)| *frames == usize::MAX)
+            .unwrap_or(runs.len() - 1);
+        runs[0].1.start..runs[last].1.end
+    }
+
+    pub fn cancel_window(&self, state: StateIndex) -> Range<usize> {
+        self.states[state].cancel_window.clone()
+    }
+
+    pub fn set_cancel_window(&mut self, state: StateIndex, cancel_window: Range<usize>) {
+        self.states[state].cancel_window = cancel_window;
+    }
+
+    pub fn has_burst(&self) -> bool {
+        self.burst_state.is_some()
+    }
+
+    /// Meter is only spent on guard cancels, so characters without one don't show it
+    pub fn has_guard_cancel(&self) -> bool {
+        self.guard_cancel_state.is_some()
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -175,8 +554,47 @@ pub struct State {
     friction_vel: FPoint,
     gravity_mult: f32,
     hit_connected: bool,
+    hit_blocked: bool,
+    /// Latest hit of the current move that connected, its boxes stay off until a later hit re-arms
+    last_hit: Option<u32>,
     stun: usize,
     combo_scaling: f32,
+    /// Hits taken in a row without getting out of hit stun
+    combo_hits: u32,
+    /// Armor only soaks the first hit of each state
+    armor_used: bool,
+    hit_flash: usize,
+    /// Frames left of the current state's super flash, the opponent is frozen until it runs out
+    super_flash: usize,
+    /// Juggle points spent in the current air combo
+    juggle_points: u32,
+    /// Hit after the juggle limit ran out, can't be hit again until landing
+    soft_knockdown: bool,
+    /// Launch state frame from which the player can air recover
+    untech: Option<usize>,
+    stance: usize,
+    buff: Option<ActiveBuff>,
+    /// Invulnerability switched on by the current state's events
+    event_invuln: StateFlags,
+    /// Refilled every round by the reset
+    burst_available: bool,
+    meter: f32,
+    /// Landed from a launch and hasn't got up yet
+    knocked_down: bool,
+    wakeup_throw_invuln: usize,
+    /// Fills with every hit taken, the player gets dizzied once it passes the threshold
+    dizzy: f32,
+    dizzy_decay_delay: usize,
+    dizzied: bool,
+    /// Lost health that slowly comes back while the player isn't being hit
+    recoverable_hp: f32,
+    recovery_delay: usize,
+    projectiles: Vec<Projectile>,
+    assist: Option<Assist>,
+    /// Frames until the assist can be called again
+    assist_cooldown: usize,
+    /// Sounds of the states started since the last `take_sounds`
+    sounds: Vec<usize>,
 }
 
 impl State {
@@ -191,13 +609,39 @@ impl State {
             friction_vel: FPoint::new(0.0, 0.0),
             gravity_mult: 1.0,
             hit_connected: false,
+            hit_blocked: false,
+            last_hit: None,
             stun: 0,
             combo_scaling: 1.0,
+            combo_hits: 0,
+            armor_used: false,
+            hit_flash: 0,
+            super_flash: 0,
+            juggle_points: 0,
+            soft_knockdown: false,
+            untech: None,
+            stance: 0,
+            buff: None,
+            event_invuln: StateFlags::NONE,
+            burst_available: true,
+            meter: 0.0,
+            knocked_down: false,
+            wakeup_throw_invuln: 0,
+            dizzy: 0.0,
+            dizzy_decay_delay: 0,
+            dizzied: false,
+            recoverable_hp: 0.0,
+            recovery_delay: 0,
+            projectiles: Vec::new(),
+            assist: None,
+            assist_cooldown: 0,
+            sounds: Vec::new(),
         }
     }
 
-    pub fn serialize(&self, context: &Context, stage: &Stage) -> [f32; 37] {
-        let mut data = [0.0; 37];
+    /// The first 37 values are the original layout, frame data then buff features follow so older models can ignore them
+    pub fn serialize(&self, context: &Context, stage: &Stage) -> [f32; 46] {
+        let mut data = [0.0; 46];
 
         // Normal floats
         data[0] = self.hp / context.max_hp;
@@ -214,20 +658,85 @@ impl State {
         data[10] = self.stun as f32 / 60.0;
         // bools / enums
         data[11] = (self.side == Side::Left) as usize as f32;
-        data[12 + self.current_state] = 1.0;
+        if self.current_state < SERIAL_STATE_SLOTS {
+            data[12 + self.current_state] = 1.0;
+        }
+        // Frame data
+        let progress = context.frame_data[self.current_state].progress(self.current_frame);
+        data[37..40].copy_from_slice(&progress);
+        data[40] = context.states[self.current_state]
+            .cancel_window
+            .contains(&self.current_frame) as usize as f32;
+        data[41] = self.in_cancel_window(context) as usize as f32;
+        data[42] = self.stun_remaining(context) as f32 / STUN_SERIAL_SCALE;
+        // Buff, no buff reads as unmodified
+        data[BUFF_SERIAL_START..].copy_from_slice(&match self.active_buff(context) {
+            Some((active, buff)) => [
+                active.frames_left as f32 / buff.duration as f32,
+                buff.dmg_mult,
+                buff.speed_mult,
+            ],
+            None => [0.0, 1.0, 1.0],
+        });
 
         data
     }
 
+    fn active_buff<'a>(&self, context: &'a Context) -> Option<(&ActiveBuff, &'a Buff)> {
+        self.buff
+            .as_ref()
+            .map(|active| (active, &context.buffs[active.buff]))
+    }
+
+    /// Scales the damage of a hit this player landed by the running buff
+    pub fn buff_hit(&self, context: &Context, hit: HitBox) -> HitBox {
+        match self.active_buff(context) {
+            Some((_, buff)) => hit.scale_dmg(buff.dmg_mult),
+            None => hit,
+        }
+    }
+
+    /// Frames left before a stun state ends, 0 outside of stun
+    fn stun_remaining(&self, context: &Context) -> usize {
+        match context.states[self.current_state].end_behaviors {
+            EndBehavior::OnStunEndToStateY { .. } => self.stun.saturating_sub(self.current_frame),
+            _ => 0,
+        }
+    }
+
     pub fn combo_scaling(&self) -> f32 {
         self.combo_scaling
     }
 
-    pub fn state_update(&mut self, inputs: &Inputs, context: &Context) {
+    /// Hits in the combo being taken, or in the last one once it's over
+    pub fn combo_hits(&self) -> u32 {
+        self.combo_hits
+    }
+
+    pub fn hp(&self) -> f32 {
+        self.hp
+    }
+
+    pub fn burst_available(&self) -> bool {
+        self.burst_available
+    }
+
+    pub fn meter(&self) -> f32 {
+        self.meter
+    }
+
+    fn gain_meter(&mut self, amount: f32) {
+        self.meter = (self.meter + amount).min(MAX_METER);
+    }
+
+    /// `opponent_pos` picks between close and far versions of a move
+    pub fn state_update(&mut self, inputs: &Inputs, context: &Context, opponent_pos: FPoint) {
+        let distance = (self.pos.x - opponent_pos.x).abs();
         match self.side {
             Side::Left => {
                 self.check_transitions(
                     context,
+                    distance,
                     inputs.dir().on_left_side(),
                     &inputs
                         .move_buf()
@@ -238,6 +747,7 @@ impl State {
             Side::Right => {
                 self.check_transitions(
                     context,
+                    distance,
                     inputs.dir().on_right_side(),
                     &inputs
                         .move_buf()
@@ -249,16 +759,20 @@ impl State {
     }
 
     pub fn movement_update(&mut self, context: &Context) {
-        self.pos = velocity_system(self.pos, self.vel_on_side());
+        let mut vel = self.vel_on_side();
+        if let Some((_, buff)) = self.active_buff(context) {
+            vel.x *= buff.speed_mult;
+        }
+        self.pos = velocity_system(self.pos, vel);
 
-        self.friction_vel = friction_system(self.friction_vel);
+        self.friction_vel = friction_system(self.friction_vel, context.friction);
 
         if context.states[self.current_state]
             .flags
             .contains(StateFlags::Airborne)
         {
             let (new_pos, new_vel, grounded) =
-                gravity_system(self.pos, self.vel, self.gravity_mult);
+                gravity_system(self.pos, self.vel, context.gravity * self.gravity_mult);
             self.pos = new_pos;
             self.vel = new_vel;
             if grounded {
@@ -274,7 +788,13 @@ impl State {
         camera: &Camera,
         global_textures: &[Texture],
         context: &Context,
+        show_boxes: bool,
     ) -> Result<(), sdl3::Error> {
+        let ground_box = self
+            .get_collision_box(context)
+            .on_side(self.side, FPoint::new(self.pos.x, 0.0));
+        draw_shadow_system(canvas, camera, ground_box, self.pos.y)?;
+
         let animation = &context.states[self.current_state].animation;
         camera.render_animation_on_side(
             canvas,
@@ -285,7 +805,44 @@ impl State {
             self.side,
         )?;
 
-        if cfg!(feature = "debug") {
+        for projectile in &self.projectiles {
+            let Some(data) = context.projectile_data(projectile.source()) else {
+                continue;
+            };
+            camera.render_animation_on_side(
+                canvas,
+                global_textures,
+                projectile.pos(),
+                data.animation(),
+                projectile.frame(),
+                projectile.side(),
+            )?;
+        }
+        if let Some((assist, data)) = self.active_assist(context) {
+            camera.render_animation_on_side(
+                canvas,
+                global_textures,
+                assist.pos(),
+                data.animation(),
+                assist.frame(),
+                assist.side(),
+            )?;
+        }
+
+        if self.hit_flash > 0 {
+            let intensity = self.hit_flash as f32 / HIT_FLASH_FRAMES as f32;
+            let hurtboxes = self.get_hurt_boxes(context);
+            draw_hit_flash_system(canvas, camera, self.side, self.pos, hurtboxes, intensity)?;
+        }
+
+        if self.super_flash > 0 {
+            let intensity =
+                self.super_flash as f32 / context.states[self.current_state].super_flash as f32;
+            let hurtboxes = self.get_hurt_boxes(context);
+            draw_hit_flash_system(canvas, camera, self.side, self.pos, hurtboxes, intensity)?;
+        }
+
+        if show_boxes {
             canvas.set_blend_mode(sdl3::render::BlendMode::Blend);
             let collision_box = self.get_collision_box(context);
             draw_collision_box_system(canvas, camera, self.side, self.pos, collision_box)?;
@@ -296,6 +853,40 @@ impl State {
             let hurtboxes = self.get_hurt_boxes(context);
             draw_hurt_boxes_system(canvas, camera, self.side, self.pos, hurtboxes)?;
 
+            if let Some(throw_box) = self.get_throw_box(context) {
+                draw_throw_box_system(canvas, camera, self.side, self.pos, throw_box)?;
+            }
+
+            for projectile in &self.projectiles {
+                if let Some(data) = context.projectile_data(projectile.source()) {
+                    draw_hit_boxes_system(
+                        canvas,
+                        camera,
+                        projectile.side(),
+                        projectile.pos(),
+                        std::slice::from_ref(data.hit_box()),
+                    )?;
+                }
+            }
+            if let Some((assist, data)) = self.active_assist(context) {
+                if let Some(hit_box) = assist.active_hit_box(data) {
+                    draw_hit_boxes_system(
+                        canvas,
+                        camera,
+                        assist.side(),
+                        assist.pos(),
+                        std::slice::from_ref(hit_box),
+                    )?;
+                }
+                draw_hurt_boxes_system(
+                    canvas,
+                    camera,
+                    assist.side(),
+                    assist.pos(),
+                    data.hurt_boxes(),
+                )?;
+            }
+
             canvas.set_blend_mode(sdl3::render::BlendMode::None);
         }
 
@@ -312,8 +903,38 @@ impl State {
         *self = State::new(context.max_hp, pos, side)
     }
 
-    pub fn advance_frame(&mut self) {
+    pub fn advance_frame(&mut self, context: &Context) {
         self.current_frame += 1;
+        self.hit_flash = self.hit_flash.saturating_sub(1);
+        self.wakeup_throw_invuln = self.wakeup_throw_invuln.saturating_sub(1);
+        if let Some(active) = &mut self.buff {
+            active.frames_left = active.frames_left.saturating_sub(1);
+            if active.frames_left == 0 {
+                self.buff = None;
+            }
+        }
+        self.projectiles.retain_mut(|projectile| {
+            context
+                .projectile_data(projectile.source())
+                .is_some_and(|data| projectile.update(data))
+        });
+        self.update_assist(context);
+        self.recover_hp(context);
+        self.decay_dizzy(context);
+        self.fire_events(context);
+    }
+
+    pub fn super_flash(&self) -> usize {
+        self.super_flash
+    }
+
+    pub fn take_sounds(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.sounds)
+    }
+
+    /// Counts down apart from `advance_frame`, so it still runs out while both players are frozen
+    pub fn tick_super_flash(&mut self) {
+        self.super_flash = self.super_flash.saturating_sub(1);
     }
 
     pub fn pos(&self) -> FPoint {
@@ -333,6 +954,43 @@ impl State {
         self.hp / context.max_hp
     }
 
+    pub fn actionable(&self, context: &Context) -> bool {
+        context.states[self.current_state].actionable(self.current_frame)
+    }
+
+    /// In hit or block stun
+    pub fn stunned(&self, context: &Context) -> bool {
+        self.in_hit_state(context) || self.current_state == context.block_stun_state
+    }
+
+    /// Holding off a blocked hit
+    pub fn in_block_stun(&self, context: &Context) -> bool {
+        self.current_state == context.block_stun_state
+    }
+
+    /// Whether the damage just taken was chip, from blocking or armor
+    pub fn took_chip(&self, context: &Context) -> bool {
+        self.current_state == context.block_stun_state
+            || (self.armor_used
+                && context.states[self.current_state]
+                    .flags
+                    .contains(StateFlags::Armor))
+    }
+
+    /// Dizzy gauge as a share of the threshold
+    pub fn dizzy_per(&self, context: &Context) -> f32 {
+        (self.dizzy / context.dizzy_threshold).min(1.0)
+    }
+
+    pub fn dizzied(&self) -> bool {
+        self.dizzied
+    }
+
+    /// Health plus what can still be recovered, as a share of max health
+    pub fn recoverable_hp_per(&self, context: &Context) -> f32 {
+        (self.hp + self.recoverable_hp) / context.max_hp
+    }
+
     pub fn set_side(&mut self, context: &Context, new_side: Side) {
         if !context.states[self.current_state]
             .flags
@@ -347,10 +1005,15 @@ impl State {
     }
 
     pub fn get_hit_boxes<'a>(&self, context: &'a Context) -> &'a [HitBox] {
-        if self.hit_connected {
+        let hit_boxes = context.active_hit_boxes(self.current_state, self.current_frame);
+        let already_hit = hit_boxes.first().is_some_and(|hit_box| {
+            self.last_hit
+                .is_some_and(|last_hit| hit_box.hit() <= last_hit)
+        });
+        if already_hit {
             &context.hit_box_data[0..0]
         } else {
-            context.active_hit_boxes(self.current_state, self.current_frame)
+            hit_boxes
         }
     }
 
@@ -358,7 +1021,99 @@ impl State {
         context.active_hurt_boxes(self.current_state, self.current_frame)
     }
 
-    pub fn receive_hit(&mut self, context: &Context, hit: &HitBox) -> bool {
+    /// Hurt boxes an attack of `kind` can touch, none while invulnerable to it
+    pub fn get_hurt_boxes_against<'a>(
+        &self,
+        context: &'a Context,
+        kind: AttackKind,
+    ) -> &'a [HurtBox] {
+        let invuln_flag = match kind {
+            AttackKind::Strike => StateFlags::StrikeInvuln,
+            AttackKind::Throw => StateFlags::ThrowInvuln,
+            AttackKind::Projectile => StateFlags::ProjectileInvuln,
+        };
+        if self.soft_knockdown
+            || context.states[self.current_state]
+                .flags
+                .union(self.event_invuln)
+                .contains(invuln_flag)
+        {
+            &context.hurt_box_data[0..0]
+        } else {
+            self.get_hurt_boxes(context)
+        }
+    }
+
+    pub fn get_throw_box<'a>(&self, context: &'a Context) -> Option<&'a ThrowBox> {
+        if self.hit_connected {
+            return None;
+        }
+        context.states[self.current_state]
+            .throw
+            .as_ref()
+            .filter(|throw| throw.active.contains(&self.current_frame))
+            .map(|throw| &throw.throw_box)
+    }
+
+    /// Only grounded players can be thrown
+    /// Stun, knockdowns and the first frames of wakeup can't be thrown whatever the config says
+    pub fn throwable(&self, context: &Context) -> bool {
+        self.pos.y <= 0.0
+            && !context.states[self.current_state]
+                .flags
+                .contains(StateFlags::Airborne)
+            && !self.stunned(context)
+            && !self.knocked_down
+            && self.wakeup_throw_invuln == 0
+    }
+
+    /// True if this player also went for a throw within the tech window of the thrower's input
+    pub fn techs_throw(&self, context: &Context, thrower: &State, throw_box: &ThrowBox) -> bool {
+        context.states[self.current_state].throw.is_some()
+            && self.current_frame.abs_diff(thrower.current_frame) <= throw_box.tech_window()
+    }
+
+    /// Throws ignore blocking and knock the opponent down at the throw's release position
+    pub fn receive_throw(
+        &mut self,
+        context: &Context,
+        throw_box: &ThrowBox,
+        thrower_side: Side,
+        thrower_pos: FPoint,
+    ) {
+        self.take_dmg(throw_box.dmg(), 0.0);
+        self.pos = throw_box.release_pos(thrower_side, thrower_pos);
+        self.set_hit_state(context, u32::MAX as usize, 0, None, HitStrength::Heavy);
+    }
+
+    pub fn successful_throw(&mut self) {
+        self.hit_connected = true;
+    }
+
+    /// Broken throws push both players apart into block stun
+    pub fn tech_throw(&mut self, context: &Context) {
+        self.set_block_stun_state(context, THROW_TECH_STUN);
+        self.friction_vel.x += THROW_TECH_PUSH_BACK;
+        self.hit_connected = true;
+        self.hit_blocked = true;
+    }
+
+    /// Blocking mid-screen pushes the defender away, `cornered` leaves the push to the attacker
+    pub fn receive_hit(&mut self, context: &Context, hit: &HitBox, cornered: bool) -> bool {
+        let flags = context.states[self.current_state].flags;
+        // Guard points block anything without leaving the state
+        if flags.contains(StateFlags::GuardPoint) {
+            self.hit_flash = HIT_FLASH_FRAMES;
+            return true;
+        }
+        // Armor takes the hit as chip damage and keeps the state going
+        if flags.contains(StateFlags::Armor) && !self.armor_used {
+            self.armor_used = true;
+            self.hit_flash = HIT_FLASH_FRAMES;
+            self.take_dmg(hit.dmg() * CHIP_DMG_PERCENTAGE, 1.0);
+            return false;
+        }
+
         let blocking_flag = match hit.block_type() {
             BlockType::Low => StateFlags::LowBlock,
             BlockType::Mid => StateFlags::LowBlock | StateFlags::HighBlock,
@@ -368,37 +1123,429 @@ impl State {
             .flags
             .intersects(blocking_flag);
 
-        let dmg = if blocking {
+        let (dmg, recoverable_share) = if blocking {
             self.set_block_stun_state(context, hit.block_stun());
-            hit.dmg() * CHIP_DMG_PERCENTAGE
+            if !cornered {
+                self.friction_vel.x -= hit.block_push_back();
+            }
+            (hit.dmg() * CHIP_DMG_PERCENTAGE, 1.0)
         } else {
-            self.combo_scaling = (self.combo_scaling - COMBO_SCALE_PER_HIT).max(MIN_COMBO_SCALING);
-            self.set_hit_state(context, hit.hit_stun());
-            hit.dmg() * self.combo_scaling
+            let proration = hit.proration();
+            let (scale_by, recoverable_share) = if self.in_hit_state(context) {
+                self.combo_hits += 1;
+                (proration.forced(), COMBO_RECOVERABLE_SHARE)
+            } else {
+                self.combo_hits = 1;
+                (proration.initial(), 0.0)
+            };
+            self.combo_scaling = (self.combo_scaling - scale_by).max(0.0);
+            self.set_hit_state(
+                context,
+                hit.hit_stun(),
+                hit.juggle_points(),
+                hit.launch(),
+                hit.strength(),
+            );
+            self.add_dizzy(hit.dizzy());
+            (
+                hit.dmg() * self.combo_scaling.max(proration.min_dmg_scaling()),
+                recoverable_share,
+            )
         };
-        self.hp = (self.hp - dmg).max(0.0);
+        self.take_dmg(dmg, recoverable_share);
+        self.gain_meter(dmg * METER_PER_DMG * 0.5);
 
         blocking
     }
 
-    pub fn successful_hit(&mut self, context: &Context, _hit: &HitBox, _blocked: bool) {
+    /// Any damage pauses recovery, a KO throws away whatever was recoverable
+    fn take_dmg(&mut self, dmg: f32, recoverable_share: f32) {
+        self.hp = (self.hp - dmg).max(0.0);
+        self.recoverable_hp = if self.hp > 0.0 {
+            self.recoverable_hp + dmg * recoverable_share
+        } else {
+            0.0
+        };
+        self.recovery_delay = RECOVERY_DELAY;
+    }
+
+    /// Dizzied players don't build the gauge back up until they are free again
+    fn add_dizzy(&mut self, dizzy: f32) {
+        if !self.dizzied {
+            self.dizzy += dizzy;
+            self.dizzy_decay_delay = DIZZY_DECAY_DELAY;
+        }
+    }
+
+    fn decay_dizzy(&mut self, context: &Context) {
+        if self.stunned(context) || self.dizzied {
+            return;
+        }
+        if self.dizzy_decay_delay > 0 {
+            self.dizzy_decay_delay -= 1;
+            return;
+        }
+        self.dizzy = (self.dizzy - DIZZY_DECAY_PER_FRAME).max(0.0);
+    }
+
+    /// A full gauge takes over whatever the player would have recovered from stun or a knockdown into
+    fn check_dizzy(&mut self, context: &Context) -> bool {
+        if self.dizzy < context.dizzy_threshold || !(self.stunned(context) || self.knocked_down) {
+            return false;
+        }
+
+        self.dizzy = 0.0;
+        self.stun = DIZZY_FRAMES;
+        self.combo_scaling = 1.0;
+        self.enter_state(context, context.dizzy_state);
+        self.dizzied = true;
+        true
+    }
+
+    fn recover_hp(&mut self, context: &Context) {
+        if self.stunned(context) {
+            return;
+        }
+        if self.recovery_delay > 0 {
+            self.recovery_delay -= 1;
+            return;
+        }
+        let recovered = self.recoverable_hp.min(RECOVERY_PER_FRAME);
+        self.recoverable_hp -= recovered;
+        self.hp += recovered;
+    }
+
+    /// Wears down overlapping projectiles of both players, returns where each clash happened
+    pub fn clash_projectiles(
+        &mut self,
+        context: &Context,
+        other: &mut State,
+        other_context: &Context,
+    ) -> Vec<FPoint> {
+        let mut clashes = Vec::new();
+        for projectile in &mut self.projectiles {
+            let Some(data) = context.projectile_data(projectile.source()) else {
+                continue;
+            };
+            for other_projectile in &mut other.projectiles {
+                let Some(other_data) = other_context.projectile_data(other_projectile.source())
+                else {
+                    continue;
+                };
+                if let Some(pos) = check_hit_box_clash(
+                    projectile.side(),
+                    projectile.pos(),
+                    data.hit_box(),
+                    other_projectile.side(),
+                    other_projectile.pos(),
+                    other_data.hit_box(),
+                ) && projectile.clash(other_projectile)
+                {
+                    clashes.push(pos);
+                }
+            }
+        }
+
+        self.projectiles
+            .retain(|projectile| projectile.durability() > 0);
+        other
+            .projectiles
+            .retain(|projectile| projectile.durability() > 0);
+        clashes
+    }
+
+    /// Checks the assist's active hit box against the opponent, it can only land once per call
+    pub fn assist_hit(
+        &mut self,
+        context: &Context,
+        hurt_side: Side,
+        hurt_pos: FPoint,
+        hurt_boxes: &[HurtBox],
+    ) -> Option<HitBox> {
+        let data = context.assist.as_ref()?;
+        let assist = self.assist.as_mut()?;
+        let hit_box = assist.active_hit_box(&data.moves()[assist.mov()])?;
+        let hit = assist
+            .flight()
+            .hits(hit_box, hurt_side, hurt_pos, hurt_boxes)?;
+        assist.connect();
+        Some(self.buff_hit(context, hit))
+    }
+
+    /// Checks the live projectiles against the opponent, the one that connects is used up
+    pub fn projectile_hit(
+        &mut self,
+        context: &Context,
+        hurt_side: Side,
+        hurt_pos: FPoint,
+        hurt_boxes: &[HurtBox],
+    ) -> Option<HitBox> {
+        let index = self.projectile_touching(context, hurt_side, hurt_pos, hurt_boxes)?;
+        let projectile = self.projectiles.remove(index);
+        context
+            .projectile_data(projectile.source())
+            .map(|data| self.buff_hit(context, data.hit_box().clone()))
+    }
+
+    /// Index of the first live projectile whose hit box touches the hurt boxes
+    fn projectile_touching(
+        &self,
+        context: &Context,
+        hurt_side: Side,
+        hurt_pos: FPoint,
+        hurt_boxes: &[HurtBox],
+    ) -> Option<usize> {
+        self.projectiles.iter().position(|projectile| {
+            context
+                .projectile_data(projectile.source())
+                .and_then(|data| {
+                    projectile
+                        .flight()
+                        .hits(data.hit_box(), hurt_side, hurt_pos, hurt_boxes)
+                })
+                .is_some()
+        })
+    }
+
+    /// Lands the active strike, or else a live projectile, on the opponent's assist
+    ///
+    /// Either is used up on it like on a character, the assist leaves once its hp runs out
+    pub fn hit_assist(
+        &mut self,
+        context: &Context,
+        other: &mut State,
+        other_context: &Context,
+    ) -> Option<HitBox> {
+        let data = other_context.assist.as_ref()?;
+        let assist = other.assist.as_mut()?;
+        let hurt_boxes = data.moves()[assist.mov()].hurt_boxes();
+        let strike = check_hit_collisions(
+            self.side,
+            self.pos,
+            self.get_hit_boxes(context),
+            assist.side(),
+            assist.pos(),
+            hurt_boxes,
+        );
+        let hit = match strike {
+            Some(hit) => {
+                let hit = self.buff_hit(context, hit);
+                self.successful_hit(context, &hit, false, false);
+                hit
+            }
+            None => {
+                let index =
+                    self.projectile_touching(context, assist.side(), assist.pos(), hurt_boxes)?;
+                let projectile = self.projectiles.remove(index);
+                let projectile_data = context.projectile_data(projectile.source())?;
+                self.buff_hit(context, projectile_data.hit_box().clone())
+            }
+        };
+        if !assist.take_hit(hit.dmg()) {
+            other.assist = None;
+            other.assist_cooldown = data.cooldown();
+        }
+        Some(hit)
+    }
+
+    /// Blocked hits against a cornered opponent push the attacker out instead, so pressure runs out
+    pub fn successful_hit(
+        &mut self,
+        context: &Context,
+        hit: &HitBox,
+        blocked: bool,
+        opponent_cornered: bool,
+    ) {
         if !context.states[self.current_state]
             .flags
             .contains(StateFlags::Airborne)
         {
-            self.friction_vel.x += HIT_PUSH_BACK;
+            if !blocked {
+                self.friction_vel.x += HIT_PUSH_BACK;
+            } else if opponent_cornered {
+                self.friction_vel.x -= hit.block_push_back();
+            }
         }
         self.hit_connected = true;
+        self.hit_blocked = blocked;
+        self.last_hit = Some(hit.hit());
+        let dmg = if blocked {
+            hit.dmg() * CHIP_DMG_PERCENTAGE
+        } else {
+            hit.dmg()
+        };
+        self.gain_meter(dmg * METER_PER_DMG);
     }
 }
 
 impl State {
-    fn check_transitions<T>(&mut self, context: &Context, dir: RelativeDirection, move_iter: &T)
-    where
+    fn check_transitions<T>(
+        &mut self,
+        context: &Context,
+        distance: f32,
+        dir: RelativeDirection,
+        move_iter: &T,
+    ) where
         T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
     {
+        if self.check_burst(context, dir, move_iter)
+            || self.check_air_recovery(context, move_iter)
+            || self.check_guard_cancel(context, dir, move_iter)
+        {
+            return;
+        }
         self.check_state_end(context);
-        self.check_cancels(context, dir, move_iter);
+        if self.check_assist(context, dir, move_iter) || self.check_chains(context, dir, move_iter)
+        {
+            return;
+        }
+        self.check_cancels(context, distance, dir, move_iter);
+    }
+
+    /// Any button recovers once the untech time is over, unless the juggle limit already ran out
+    fn check_air_recovery<T>(&mut self, context: &Context, move_iter: &T) -> bool
+    where
+        T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
+    {
+        let (Some(air_recovery_state), Some(untech)) = (context.air_recovery_state, self.untech)
+        else {
+            return false;
+        };
+        if self.current_state != context.launch_hit_state
+            || self.current_frame < untech
+            || self.soft_knockdown
+            || !move_iter.clone().any(|(_, buttons)| !buttons.is_empty())
+        {
+            return false;
+        }
+
+        self.untech = None;
+        self.combo_scaling = 1.0;
+        self.gravity_mult = 1.0;
+        self.juggle_points = 0;
+        self.enter_state(context, air_recovery_state);
+        true
+    }
+
+    /// Spends meter to leave block stun straight into the guard cancel state
+    fn check_guard_cancel<T>(
+        &mut self,
+        context: &Context,
+        dir: RelativeDirection,
+        move_iter: &T,
+    ) -> bool
+    where
+        T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
+    {
+        let Some(guard_cancel_state) = context.guard_cancel_state else {
+            return false;
+        };
+        if self.current_state != context.block_stun_state
+            || self.meter < GUARD_CANCEL_COST
+            || !context.state_inputs[guard_cancel_state].matches(dir, move_iter)
+        {
+            return false;
+        }
+
+        self.meter -= GUARD_CANCEL_COST;
+        self.enter_state(context, guard_cancel_state);
+        true
+    }
+
+    /// Calls the assist whose input matches, not while stuck in hit or block stun or dizzied
+    fn check_assist<T>(&mut self, context: &Context, dir: RelativeDirection, move_iter: &T) -> bool
+    where
+        T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
+    {
+        let Some(data) = &context.assist else {
+            return false;
+        };
+        if self.assist.is_some()
+            || self.assist_cooldown > 0
+            || self.stunned(context)
+            || self.dizzied
+        {
+            return false;
+        }
+
+        let Some(mov) = data
+            .moves()
+            .iter()
+            .position(|assist_move| assist_move.input().matches(dir, move_iter))
+        else {
+            return false;
+        };
+        self.assist = Some(Assist::spawn(data, mov, self.pos, self.side));
+        true
+    }
+
+    fn update_assist(&mut self, context: &Context) {
+        let Some(data) = &context.assist else {
+            return;
+        };
+        match &mut self.assist {
+            Some(assist) => {
+                if !assist.update(&data.moves()[assist.mov()]) {
+                    self.assist = None;
+                    self.assist_cooldown = data.cooldown();
+                }
+            }
+            None => self.assist_cooldown = self.assist_cooldown.saturating_sub(1),
+        }
+    }
+
+    fn active_assist<'a>(&'a self, context: &'a Context) -> Option<(&'a Assist, &'a AssistMove)> {
+        let data = context.assist.as_ref()?;
+        let assist = self.assist.as_ref()?;
+        Some((assist, &data.moves()[assist.mov()]))
+    }
+
+    /// Target combos win over the generic cancel options
+    fn check_chains<T>(&mut self, context: &Context, dir: RelativeDirection, move_iter: &T) -> bool
+    where
+        T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
+    {
+        if !self.hit_connected {
+            return false;
+        }
+
+        let chain = context.states[self.current_state]
+            .chains
+            .iter()
+            .find(|chain| {
+                chain.window.contains(&self.current_frame) && chain.input.matches(dir, move_iter)
+            });
+        match chain {
+            Some(chain) => {
+                self.enter_state(context, chain.into);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Bursting out of a hit state spends the round's burst and drops the combo being taken
+    fn check_burst<T>(&mut self, context: &Context, dir: RelativeDirection, move_iter: &T) -> bool
+    where
+        T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
+    {
+        let Some(burst_state) = context.burst_state else {
+            return false;
+        };
+        if !self.burst_available
+            || !self.in_hit_state(context)
+            || !context.state_inputs[burst_state].matches(dir, move_iter)
+        {
+            return false;
+        }
+
+        self.burst_available = false;
+        self.combo_scaling = 1.0;
+        self.gravity_mult = 1.0;
+        self.juggle_points = 0;
+        self.soft_knockdown = false;
+        self.enter_state(context, burst_state);
+        true
     }
 
     fn check_state_end(&mut self, context: &Context) {
@@ -407,7 +1554,7 @@ impl State {
             EndBehavior::OnStunEndToStateY {
                 y: transition_state,
             } => {
-                if self.current_frame >= self.stun {
+                if self.current_frame >= self.stun && !self.check_dizzy(context) {
                     self.enter_state(context, transition_state);
                 }
             }
@@ -415,7 +1562,7 @@ impl State {
                 x: end_frame,
                 y: transition_state,
             } => {
-                if self.current_frame >= end_frame {
+                if self.current_frame >= end_frame && !self.check_dizzy(context) {
                     self.enter_state(context, transition_state);
                     self.combo_scaling = 1.0;
                 }
@@ -424,8 +1571,13 @@ impl State {
         }
     }
 
-    fn check_cancels<T>(&mut self, context: &Context, dir: RelativeDirection, move_iter: &T)
-    where
+    fn check_cancels<T>(
+        &mut self,
+        context: &Context,
+        distance: f32,
+        dir: RelativeDirection,
+        move_iter: &T,
+    ) where
         T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
     {
         // Check if not in cancel window
@@ -433,40 +1585,63 @@ impl State {
             return;
         }
 
-        let cancel_options_range = context.states[self.current_state].cancel_options.clone();
-        let cancel_options = &context.run_length_cancel_options[cancel_options_range];
-        for i in cancel_options {
-            let cancel_option = &context.state_inputs[*i];
-            if !cancel_option.dir.matches_or_is_none(dir) {
-                continue;
-            }
-
-            let maybe_index = move_iter.clone().position(|(buf_motion, buf_buttons)| {
-                buf_motion.contains(cancel_option.motion)
-                    && buf_buttons.contains(cancel_option.button)
-            });
-
-            if maybe_index.is_some() {
-                self.enter_state(context, *i);
+        let buff_cancel_options = self
+            .active_buff(context)
+            .map_or(&[][..], |(_, buff)| &buff.cancel_options);
+        for i in self
+            .cancel_options(context)
+            .iter()
+            .chain(buff_cancel_options)
+        {
+            let next_state = context.stances[self.stance][*i];
+            let input = &context.state_inputs[next_state];
+            if input.in_range(distance) && input.matches(dir, move_iter) {
+                self.enter_state(context, next_state);
                 break;
             }
         }
     }
 
+    fn in_hit_state(&self, context: &Context) -> bool {
+        context.ground_hit_states.contains(&self.current_state)
+            || self.current_state == context.launch_hit_state
+    }
+
+    fn cancel_options<'a>(&self, context: &'a Context) -> &'a [StateIndex] {
+        let cancel_options = &context.states[self.current_state].cancel_options;
+        let range = match (self.hit_connected, self.hit_blocked) {
+            (false, _) => cancel_options.on_whiff.clone(),
+            (true, true) => cancel_options.on_block.clone(),
+            (true, false) => cancel_options.on_hit.clone(),
+        };
+        &context.run_length_cancel_options[range]
+    }
+
     fn in_cancel_window(&self, context: &Context) -> bool {
         context.states[self.current_state]
             .cancel_window
             .contains(&self.current_frame)
-            && (self.hit_connected
-                || context.states[self.current_state]
-                    .flags
-                    .contains(StateFlags::CancelOnWhiff))
+            && (!self.cancel_options(context).is_empty()
+                || self
+                    .active_buff(context)
+                    .is_some_and(|(_, buff)| !buff.cancel_options.is_empty()))
     }
 
     fn enter_state(&mut self, context: &Context, new_state: StateIndex) {
+        // Leaving the knockdown state is getting up
+        if self.knocked_down {
+            self.knocked_down = false;
+            self.wakeup_throw_invuln = WAKEUP_THROW_INVULN;
+        }
+        self.dizzied = false;
         self.current_state = new_state;
         self.current_frame = 0;
         self.hit_connected = false;
+        self.hit_blocked = false;
+        self.last_hit = None;
+        self.armor_used = false;
+        self.super_flash = context.states[new_state].super_flash;
+        self.sounds.extend(context.states[new_state].sound);
         match context.states[new_state].start_behaviors {
             StartBehavior::None => {}
             StartBehavior::SetVel { x, y } => {
@@ -477,6 +1652,46 @@ impl State {
                 self.friction_vel = FPoint::new(self.friction_vel.x + x, self.friction_vel.y + y);
             }
         }
+        self.event_invuln = StateFlags::NONE;
+        self.fire_events(context);
+    }
+
+    fn fire_events(&mut self, context: &Context) {
+        let events = &context.states[self.current_state].events;
+        for (index, frame_event) in events.iter().enumerate() {
+            if frame_event.frame != self.current_frame {
+                continue;
+            }
+            match &frame_event.event {
+                AnimationEvent::SetVel { x, y } => {
+                    self.vel = FPoint::new(*x, *y);
+                }
+                AnimationEvent::AddFrictionVel { x, y } => {
+                    self.friction_vel =
+                        FPoint::new(self.friction_vel.x + x, self.friction_vel.y + y);
+                }
+                AnimationEvent::SetInvuln { flags, enabled } => {
+                    self.event_invuln.set(*flags, *enabled);
+                }
+                AnimationEvent::SetStance(stance) => {
+                    self.stance = *stance;
+                }
+                AnimationEvent::ApplyBuff(buff) => {
+                    self.buff = Some(ActiveBuff {
+                        buff: *buff,
+                        frames_left: context.buffs[*buff].duration,
+                    });
+                }
+                AnimationEvent::SpawnProjectile(data) => {
+                    self.projectiles.push(Projectile::spawn(
+                        data,
+                        (self.current_state, index),
+                        self.pos,
+                        self.side,
+                    ));
+                }
+            }
+        }
     }
 
     fn vel_on_side(&self) -> FPoint {
@@ -496,8 +1711,19 @@ impl State {
         if let EndBehavior::OnGroundedToStateY { y } =
             context.states[self.current_state].end_behaviors
         {
-            self.enter_state(context, y);
+            let landing_state = if self.soft_knockdown {
+                NEUTRAL_STATE
+            } else {
+                y
+            };
+            let knocked_down =
+                self.current_state == context.launch_hit_state && landing_state != NEUTRAL_STATE;
+            self.enter_state(context, landing_state);
+            self.knocked_down = knocked_down;
             self.gravity_mult = 1.0;
+            self.juggle_points = 0;
+            self.soft_knockdown = false;
+            self.untech = None;
         }
     }
 
@@ -506,17 +1732,39 @@ impl State {
         self.enter_state(context, context.block_stun_state);
     }
 
-    fn set_hit_state(&mut self, context: &Context, hit_stun: usize) {
+    fn set_hit_state(
+        &mut self,
+        context: &Context,
+        hit_stun: usize,
+        juggle_points: u32,
+        launch: Option<Launch>,
+        strength: HitStrength,
+    ) {
         let should_launch = self.pos.y != 0.0;
         if should_launch
             || self.current_state == context.launch_hit_state
             || hit_stun == u32::MAX as usize
         {
+            // Hits past the juggle limit still land, but end the combo
+            if self.juggle_points >= context.juggle_limit {
+                self.soft_knockdown = true;
+            }
+            self.juggle_points += juggle_points;
             self.enter_state(context, context.launch_hit_state);
-            self.gravity_mult *= HIT_GRAVITY_MULT;
+            self.gravity_mult += JUGGLE_GRAVITY_PER_HIT;
+            // Without a launch vector the launch state's own start behavior moves the victim
+            if let Some(launch) = launch {
+                self.vel = FPoint::new(-launch.vel().x, launch.vel().y);
+                self.untech = launch.untech();
+            }
         } else {
             self.stun = hit_stun;
-            self.enter_state(context, context.ground_hit_state);
+            let ground_hit_state = match strength {
+                HitStrength::Light => context.ground_hit_states[0],
+                HitStrength::Medium => context.ground_hit_states[1],
+                HitStrength::Heavy => context.ground_hit_states[2],
+            };
+            self.enter_state(context, ground_hit_state);
         }
     }
 }
@@ -526,6 +1774,8 @@ pub struct MoveInput {
     button: ButtonFlag,
     motion: RelativeMotion,
     dir: RelativeDirection,
+    /// Furthest the opponent can be on the x axis for the move to come out as a cancel
+    max_distance: Option<f32>,
 }
 
 impl MoveInput {
@@ -534,8 +1784,48 @@ impl MoveInput {
             button,
             motion,
             dir,
+            max_distance: None,
         }
     }
+
+    pub fn with_max_distance(mut self, max_distance: Option<f32>) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Numpad notation like `236H`, plain movement without a motion has none
+    pub fn notation(&self) -> Option<String> {
+        if self.button == ButtonFlag::NONE && self.motion == RelativeMotion::NONE {
+            return None;
+        }
+        let buttons = self
+            .button
+            .iter_names()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>()
+            .join("+");
+        let prefix = if self.motion != RelativeMotion::NONE {
+            self.motion.numpad()
+        } else {
+            self.dir.numpad()
+        };
+        Some(format!("{prefix}{buttons}"))
+    }
+
+    fn in_range(&self, distance: f32) -> bool {
+        self.max_distance
+            .is_none_or(|max_distance| distance <= max_distance)
+    }
+
+    fn matches<T>(&self, dir: RelativeDirection, move_iter: &T) -> bool
+    where
+        T: Iterator<Item = (RelativeMotion, ButtonFlag)> + Clone,
+    {
+        self.dir.matches_or_is_none(dir)
+            && move_iter.clone().any(|(buf_motion, buf_buttons)| {
+                buf_motion.contains(self.motion) && buf_buttons.contains(self.button)
+            })
+    }
 }
 
 #[derive(Debug)]
@@ -558,9 +1848,50 @@ bitflags! {
     pub struct StateFlags: u32 {
         const NONE = 0;
         const Airborne =      0b0000_0001;
-        const CancelOnWhiff = 0b0000_0010;
         const LockSide =      0b0000_0100;
         const LowBlock =      0b0000_1000;
         const HighBlock =     0b0001_0000;
+        const Armor =         0b0010_0000;
+        const GuardPoint =    0b0100_0000;
+        const StrikeInvuln =  0b1000_0000;
+        const ThrowInvuln =   0b0001_0000_0000;
+        const ProjectileInvuln = 0b0010_0000_0000;
+    }
+}
+
+#[test]
+fn test_proration_keeps_minimum_damage() {
+    let (context, mut state) = crate::game::deserialize::deserialize_character(
+        &mut crate::game::render::HeadlessTextureLoader::default(),
+        "./resources/character1/config.json",
+        Side::Left,
+        FPoint::new(0.0, 0.0),
+        0,
+        None,
+    )
+    .unwrap();
+    let hit = HitBox::new(
+        sdl3::render::FRect::new(0.0, 0.0, 10.0, 10.0),
+        100.0,
+        10,
+        20,
+        0,
+        BlockType::Mid,
+        0,
+    )
+    .with_proration(crate::game::boxes::Proration::new(0.5, 0.5, 0.2));
+    let start_hp = state.hp();
+
+    // The opener only takes off the initial proration
+    assert!(!state.receive_hit(&context, &hit, false));
+    assert!((start_hp - state.hp() - 50.0).abs() < 0.01);
+
+    // Forced proration drains the scaling, supers still deal their minimum share
+    for _ in 0..2 {
+        let hp = state.hp();
+        assert!(!state.receive_hit(&context, &hit, false));
+        assert!((hp - state.hp() - 20.0).abs() < 0.01);
     }
+    assert_eq!(state.combo_hits(), 3);
+    assert_eq!(state.combo_scaling(), 0.0);
 }
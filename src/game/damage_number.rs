@@ -0,0 +1,51 @@
+use sdl3::render::FPoint;
+
+/// Frames a damage number stays on screen
+pub const DAMAGE_NUMBER_FRAMES: usize = 40;
+/// Game units a damage number rises over its life
+const DAMAGE_NUMBER_RISE: f32 = 60.0;
+
+/// Rises off a player that took damage, lives in the game state so rollbacks replay it
+#[derive(Clone, PartialEq, Debug)]
+pub struct DamageNumber {
+    pos: FPoint,
+    dmg: f32,
+    /// Taken while blocking or through armor
+    chip: bool,
+    frame: usize,
+}
+
+impl DamageNumber {
+    pub fn new(pos: FPoint, dmg: f32, chip: bool) -> Self {
+        Self {
+            pos,
+            dmg,
+            chip,
+            frame: 0,
+        }
+    }
+
+    /// Returns false once the number has faded
+    pub fn update(&mut self) -> bool {
+        self.frame += 1;
+        self.frame < DAMAGE_NUMBER_FRAMES
+    }
+
+    pub fn pos(&self) -> FPoint {
+        let risen = self.frame as f32 / DAMAGE_NUMBER_FRAMES as f32 * DAMAGE_NUMBER_RISE;
+        FPoint::new(self.pos.x, self.pos.y + risen)
+    }
+
+    /// Rounded up so small chip damage still shows
+    pub fn label(&self) -> String {
+        (self.dmg.ceil() as u32).to_string()
+    }
+
+    pub fn chip(&self) -> bool {
+        self.chip
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+}
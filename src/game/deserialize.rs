@@ -1,22 +1,113 @@
-use sdl3::{
-    render::{FPoint, FRect, Texture, TextureCreator},
-    video::WindowContext,
-};
-use serde::Deserialize;
+use sdl3::render::{FPoint, FRect};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+use serde_path_to_error::{Path, Segment};
+
+use crate::game::deserialize::migrate::{Migration, migrate};
 
 use crate::game::{
     Side,
     character::StateFlags,
     render::{
+        TextureLoader, TextureRegion,
         animation::{Animation, AnimationLayout},
-        load_texture,
     },
 };
 
 mod character;
 mod game;
+mod migrate;
+mod pack;
+mod stage;
+mod template;
+
+pub use character::{check as check_character, deserialize as deserialize_character};
+pub use game::{deserialize, deserialize_simulation};
+pub use template::create as create_character;
+
+/// Parses a config, errors point at the offending field's JSON path
+fn parse_json<T: DeserializeOwned>(config: &str, src: &str) -> Result<T, String> {
+    let json = &mut serde_json::Deserializer::from_str(src);
+    serde_path_to_error::deserialize(json).map_err(|err| {
+        format!(
+            "Failed to parse: '{config}' at {}: {}",
+            named_path(serde_json::from_str::<Value>(src).ok().as_ref(), err.path()),
+            err.inner()
+        )
+    })
+}
+
+/// Parses a config with a `schema_version`, older ones are migrated up to the latest first
+fn parse_versioned_json<T: DeserializeOwned>(
+    config: &str,
+    src: &str,
+    migrations: &[Migration],
+) -> Result<T, String> {
+    let mut json: Value =
+        serde_json::from_str(src).map_err(|err| format!("Failed to parse: '{config}': {err}"))?;
+    let version = migrate(&mut json, migrations)
+        .map_err(|err| format!("Failed to migrate: '{config}': {err}"))?;
+    // Up to date configs parse from the text, so errors keep their line and column
+    if version == migrations.len() {
+        return parse_json(config, src);
+    }
+    if cfg!(feature = "debug") {
+        println!(
+            "[WARNING] '{config}' is on schema version {version}, migrated to {}",
+            migrations.len()
+        );
+    }
+    parse_value(config, &json)
+}
+
+/// Parses a config already read into JSON, errors point at the offending field's JSON path
+fn parse_value<T: DeserializeOwned>(config: &str, json: &Value) -> Result<T, String> {
+    serde_path_to_error::deserialize(json).map_err(|err| {
+        format!(
+            "Failed to parse: '{config}' at {}: {}",
+            named_path(Some(json), err.path()),
+            err.inner()
+        )
+    })
+}
 
-pub use game::deserialize;
+/// The path with the name of each named entry it passes through, "moves[3]('5L').hit_boxes[1].frame"
+fn named_path(root: Option<&Value>, path: &Path) -> String {
+    let mut value = root;
+    let mut named = String::new();
+    for segment in path.iter() {
+        match segment {
+            Segment::Seq { index } => {
+                value = value.and_then(|value| value.get(*index));
+                named.push_str(&format!("[{index}]"));
+                if let Some(name) = value
+                    .and_then(|value| value.get("name"))
+                    .and_then(Value::as_str)
+                {
+                    named.push_str(&format!("('{name}')"));
+                }
+            }
+            Segment::Map { key } => {
+                value = value.and_then(|value| value.get(key));
+                if !named.is_empty() {
+                    named.push('.');
+                }
+                named.push_str(key);
+            }
+            Segment::Enum { variant } => {
+                if !named.is_empty() {
+                    named.push('.');
+                }
+                named.push_str(variant);
+            }
+            Segment::Unknown => named.push_str(".?"),
+        }
+    }
+    if named.is_empty() {
+        named.push('.');
+    }
+    named
+}
 
 #[derive(Deserialize, Clone, Copy)]
 #[serde(tag = "type")]
@@ -66,12 +157,8 @@ struct TextureJson {
 }
 
 impl TextureJson {
-    pub fn make_texture<'a>(
-        &self,
-        texture_creator: &'a TextureCreator<WindowContext>,
-        global_textures: &mut Vec<Texture<'a>>,
-    ) -> Result<usize, String> {
-        load_texture(texture_creator, global_textures, &self.texture_path)
+    pub fn make_texture(&self, loader: &mut dyn TextureLoader) -> Result<TextureRegion, String> {
+        loader.load_texture(&self.texture_path)
     }
 }
 
@@ -82,23 +169,55 @@ struct AnimationJson {
     frames: u32,
     w: u32,
     h: u32,
+    /// Per frame (x, y) the sprite is drawn away from the position, for sprites that lunge
+    #[serde(default)]
+    offsets: Vec<FPointJson>,
+    /// Per frame game frames the sprite is shown for, defaults to 1 each
+    #[serde(default)]
+    durations: Vec<usize>,
 }
 
 impl AnimationJson {
-    pub fn make_animation<'a>(
-        &self,
-        texture_creator: &'a TextureCreator<WindowContext>,
-        global_textures: &mut Vec<Texture<'a>>,
-    ) -> Result<Animation, String> {
-        Animation::load(
-            texture_creator,
-            global_textures,
+    pub fn make_animation(&self, loader: &mut dyn TextureLoader) -> Result<Animation, String> {
+        if !self.offsets.is_empty() && self.offsets.len() != self.frames as usize {
+            return Err(format!(
+                "'{}' has {} frames but {} offsets",
+                self.texture_path,
+                self.frames,
+                self.offsets.len()
+            ));
+        }
+        if !self.durations.is_empty() && self.durations.len() != self.frames as usize {
+            return Err(format!(
+                "'{}' has {} frames but {} durations",
+                self.texture_path,
+                self.frames,
+                self.durations.len()
+            ));
+        }
+        if self.durations.contains(&0) {
+            return Err(format!(
+                "'{}' has a frame lasting 0 frames",
+                self.texture_path
+            ));
+        }
+
+        let animation = Animation::load(
+            loader,
             &self.texture_path,
             self.w,
             self.h,
             self.frames,
             self.layout.to_animation_layout(),
-        )
+        )?;
+        Ok(animation
+            .with_offsets(
+                self.offsets
+                    .iter()
+                    .map(|offset| offset.to_fpoint())
+                    .collect(),
+            )
+            .with_durations(self.durations.clone()))
     }
 }
 
@@ -122,20 +241,28 @@ impl AnimationLayoutJson {
 #[serde(tag = "type")]
 enum FlagsJson {
     Airborne,
-    CancelOnWhiff,
     LockSide,
     LowBlock,
     HighBlock,
+    Armor,
+    GuardPoint,
+    StrikeInvuln,
+    ThrowInvuln,
+    ProjectileInvuln,
 }
 
 impl FlagsJson {
     fn to_state_json(&self) -> StateFlags {
         match self {
             FlagsJson::Airborne => StateFlags::Airborne,
-            FlagsJson::CancelOnWhiff => StateFlags::CancelOnWhiff,
             FlagsJson::LockSide => StateFlags::LockSide,
             FlagsJson::HighBlock => StateFlags::HighBlock,
             FlagsJson::LowBlock => StateFlags::LowBlock,
+            FlagsJson::Armor => StateFlags::Armor,
+            FlagsJson::GuardPoint => StateFlags::GuardPoint,
+            FlagsJson::StrikeInvuln => StateFlags::StrikeInvuln,
+            FlagsJson::ThrowInvuln => StateFlags::ThrowInvuln,
+            FlagsJson::ProjectileInvuln => StateFlags::ProjectileInvuln,
         }
     }
 }
@@ -1,29 +1,499 @@
-use std::{collections::HashMap, ops::Range};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::{Component, Path, PathBuf},
+};
 
 use crate::game::{
-    boxes::{BlockType, CollisionBox, HitBox, HurtBox},
-    character::{self, EndBehavior, MoveInput, StartBehavior, StateData, StateFlags},
-    deserialize::{AnimationJson, FlagsJson, RectJson, game::PlayerJson},
+    Side,
+    assets::AssetSource,
+    assist::{AssistData, AssistMove},
+    boxes::{BlockType, CollisionBox, HitBox, HitStrength, HurtBox, Launch, Proration, ThrowBox},
+    character::{
+        self, AnimationEvent, Buff, CancelOptions, Chain, EndBehavior, FrameEvent, MoveInput,
+        StartBehavior, StateData, StateFlags, ThrowData,
+    },
+    deserialize::{
+        AnimationJson, FPointJson, FlagsJson, RectJson,
+        migrate::{CHARACTER_MIGRATIONS, migrate},
+        pack::{PackLoader, PackPaths},
+        parse_value, parse_versioned_json,
+    },
     input::{ButtonFlag, RelativeDirection, RelativeMotion},
+    physics::{DEFAULT_FRICTION, DEFAULT_GRAVITY},
+    projectile::ProjectileData,
+    render::{HeadlessTextureLoader, TextureLoader, Tint},
 };
 
-use sdl3::{
-    render::{Texture, TextureCreator},
-    video::WindowContext,
-};
+use sdl3::render::FPoint;
 use serde::Deserialize;
+use serde_json::{Map, Value};
+
+const DEFAULT_JUGGLE_LIMIT: u32 = 6;
+const DEFAULT_JUGGLE_POINTS: u32 = 1;
+const DEFAULT_HIT_STOP: usize = 4;
+const DEFAULT_PROJECTILE_DURABILITY: u32 = 1;
+const DEFAULT_DIZZY_THRESHOLD: f32 = 500.0;
+const DEFAULT_BLOCK_PUSH_BACK: f32 = 6.0;
+/// An assist is called with one of at most this many moves
+const MAX_ASSIST_MOVES: usize = 2;
+/// Name of the base movelist for `SetStance` events
+const BASE_STANCE: &str = "default";
+/// Tint for player2 in a mirror match on the same palette, unless the config sets `mirror_color`
+const DEFAULT_MIRROR_COLOR: [u8; 3] = [140, 140, 140];
 
-pub fn deserialize<'a>(
-    texture_creator: &'a TextureCreator<WindowContext>,
-    global_textures: &mut Vec<Texture<'a>>,
-    character_data: &PlayerJson,
+/// Palette 0 is the untinted sprites, higher ones wrap around the palettes in the config
+///
+/// `mirror_of` is the other player's palette when they play the same character, landing on the
+/// same colors swaps in the mirror color instead
+pub fn deserialize(
+    loader: &mut dyn TextureLoader,
+    config: &str,
+    start_side: Side,
+    start_pos: FPoint,
+    palette: usize,
+    mirror_of: Option<usize>,
 ) -> Result<(character::Context, character::State), String> {
-    let config = &character_data.config;
-    let src = std::fs::read_to_string(config)
+    let character_json = parse_character(loader.assets(), config)?;
+    validate(&character_json).map_err(|problems| format!("Invalid: '{config}':\n{problems}"))?;
+    if cfg!(feature = "debug") {
+        for warning in lint(&character_json) {
+            println!("[WARNING] '{config}': {warning}");
+        }
+    }
+
+    let palettes = character_json.palettes.len() + 1;
+    let palette = palette % palettes;
+    let tint = if mirror_of.is_some_and(|other| other % palettes == palette) {
+        let [r, g, b] = character_json.mirror_color;
+        Some((r, g, b))
+    } else {
+        palette
+            .checked_sub(1)
+            .map(|palette| character_json.palettes[palette].to_tint())
+    };
+    let pack = PackPaths::of(config);
+    let loader = &mut PackLoader::new(loader, &pack);
+    loader.set_tint(tint);
+    let character = build_character(loader, character_json, start_side, start_pos, &pack);
+    loader.set_tint(None);
+    character
+}
+
+/// Parses the config laid over the base character it `extends`, if it names one
+fn parse_character(assets: &dyn AssetSource, config: &str) -> Result<CharacterJson, String> {
+    let src = assets
+        .read_to_string(config)
         .map_err(|err| format!("Failed to open: '{config}': {err}"))?;
-    let character_json: CharacterJson =
+    let json: Value =
         serde_json::from_str(&src).map_err(|err| format!("Failed to parse: '{config}': {err}"))?;
+    if json.get("extends").is_none() {
+        return parse_versioned_json(config, &src, CHARACTER_MIGRATIONS);
+    }
+    let json = resolve_extends(assets, config, &mut Vec::new())?;
+    parse_value(config, &json)
+}
+
+/// The config's JSON over everything it extends, `extended` holds the configs already on the way
+///
+/// A base's path is relative to the config that extends it
+fn resolve_extends(
+    assets: &dyn AssetSource,
+    config: &str,
+    extended: &mut Vec<PathBuf>,
+) -> Result<Value, String> {
+    let path = lexical_path(config);
+    if extended.contains(&path) {
+        return Err(format!("'{config}' ends up extending itself"));
+    }
+    extended.push(path);
 
+    let src = assets
+        .read_to_string(config)
+        .map_err(|err| format!("Failed to open: '{config}': {err}"))?;
+    let mut json: Value =
+        serde_json::from_str(&src).map_err(|err| format!("Failed to parse: '{config}': {err}"))?;
+    migrate(&mut json, CHARACTER_MIGRATIONS)
+        .map_err(|err| format!("Failed to migrate: '{config}': {err}"))?;
+    let Value::Object(mut character) = json else {
+        return Err(format!("Failed to parse: '{config}': expected an object"));
+    };
+    let Some(base) = character.remove("extends") else {
+        return Ok(Value::Object(character));
+    };
+    let base = base
+        .as_str()
+        .ok_or_else(|| format!("Failed to parse: '{config}' at extends: expected a path"))?;
+    let base = Path::new(config)
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(base);
+    let mut json = resolve_extends(assets, &base.to_string_lossy(), extended)?;
+    if let Value::Object(base) = &mut json {
+        extend(base, character);
+    }
+    Ok(json)
+}
+
+/// The path with its "." and ".." steps worked out, archived configs can't be canonicalized
+fn lexical_path(config: &str) -> PathBuf {
+    let mut path = PathBuf::new();
+    for component in Path::new(config).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(path.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                path.pop();
+            }
+            component => path.push(component),
+        }
+    }
+    path
+}
+
+/// Lays the character's fields over its base's, each move replaces the base's move of the same name
+/// or is added after them
+fn extend(base: &mut Map<String, Value>, mut character: Map<String, Value>) {
+    let moves = character.remove("moves");
+    base.extend(character);
+    match (moves, base.get_mut("moves")) {
+        (Some(Value::Array(moves)), Some(Value::Array(base_moves))) => {
+            for mov in moves {
+                let name = mov.get("name").cloned();
+                match base_moves
+                    .iter_mut()
+                    .find(|base_move| name.is_some() && base_move.get("name") == name.as_ref())
+                {
+                    Some(base_move) => *base_move = mov,
+                    None => base_moves.push(mov),
+                }
+            }
+        }
+        (Some(moves), _) => {
+            base.insert(String::from("moves"), moves);
+        }
+        (None, _) => {}
+    }
+}
+
+/// Loads the character the way the game would but without a window, Ok with its warnings if it loads
+///
+/// Debug builds print the warnings while loading as well
+pub fn check(config: &str) -> Result<Vec<String>, String> {
+    let mut loader = HeadlessTextureLoader::default();
+    deserialize(
+        &mut loader,
+        config,
+        Side::Left,
+        FPoint::new(0.0, 0.0),
+        0,
+        None,
+    )?;
+    let character_json = parse_character(loader.assets(), config)?;
+    Ok(lint(&character_json))
+}
+
+/// Things that load fine but are likely mistakes, moves nothing leads into, hits without damage,
+/// moves with no way out and windows or hit boxes the move ends before
+fn lint(character_json: &CharacterJson) -> Vec<String> {
+    let moves = &character_json.moves;
+    let names: HashMap<&str, usize> = moves
+        .iter()
+        .enumerate()
+        .map(|(i, mov)| (mov.name.as_str(), i))
+        .collect();
+    let mut stance_swaps: HashMap<&str, Vec<&str>> = HashMap::new();
+    for stance in &character_json.stances {
+        for (from, to) in &stance.moves {
+            stance_swaps
+                .entry(from.as_str())
+                .or_default()
+                .push(to.as_str());
+        }
+    }
+
+    // Players start in the first move, the rest are reached through it or forced on them
+    let forced = [
+        Some(&character_json.block_stun_state),
+        Some(&character_json.ground_hit_state),
+        Some(&character_json.launch_hit_state),
+        character_json.hit_reactions.light.as_ref(),
+        character_json.hit_reactions.medium.as_ref(),
+        character_json.hit_reactions.heavy.as_ref(),
+        character_json.dizzy_state.as_ref(),
+        character_json.burst_state.as_ref(),
+        character_json.air_recovery_state.as_ref(),
+        character_json.guard_cancel_state.as_ref(),
+    ];
+    let buff_cancels = character_json
+        .buffs
+        .iter()
+        .flat_map(|buff| &buff.cancel_options);
+    let mut unvisited: Vec<usize> = forced
+        .into_iter()
+        .flatten()
+        .chain(buff_cancels)
+        .filter_map(|name| names.get(name.as_str()).copied())
+        .chain((!moves.is_empty()).then_some(0))
+        .collect();
+    let mut reachable = vec![false; moves.len()];
+    while let Some(i) = unvisited.pop() {
+        if std::mem::replace(&mut reachable[i], true) {
+            continue;
+        }
+        let mov = &moves[i];
+        let cancel_options = &mov.cancel_options;
+        let next = cancel_options
+            .on_hit
+            .iter()
+            .chain(&cancel_options.on_block)
+            .chain(&cancel_options.on_whiff)
+            .map(String::as_str)
+            .chain(mov.chains.iter().map(|chain| chain.into.as_str()))
+            .chain(mov.end_behavior.target())
+            .chain(
+                stance_swaps
+                    .get(mov.name.as_str())
+                    .into_iter()
+                    .flatten()
+                    .copied(),
+            );
+        unvisited.extend(next.filter_map(|name| names.get(name).copied()));
+    }
+
+    let mut warnings = Vec::new();
+    for (i, mov) in moves.iter().enumerate() {
+        let at = format!("moves[{i}]('{}')", mov.name);
+        if !reachable[i] {
+            warnings.push(format!(
+                "{at}: unreachable, nothing cancels, chains or ends into it"
+            ));
+        }
+        for (j, group) in mov.hit_boxes.iter().enumerate() {
+            for (k, hit_box) in group.boxes.iter().enumerate() {
+                if hit_box.dmg == 0 {
+                    warnings.push(format!(
+                        "{at}.hit_boxes[{j}].boxes[{k}].dmg: deals no damage"
+                    ));
+                }
+            }
+        }
+        for (j, group) in mov.hurt_boxes.iter().enumerate() {
+            if group.rearm {
+                warnings.push(format!(
+                    "{at}.hurt_boxes[{j}].rearm: only hit box groups re-arm"
+                ));
+            }
+        }
+
+        let cancel_options = &mov.cancel_options;
+        let can_cancel = !(cancel_options.on_hit.is_empty()
+            && cancel_options.on_block.is_empty()
+            && cancel_options.on_whiff.is_empty()
+            && mov.chains.is_empty());
+        let end_frame = match &mov.end_behavior {
+            EndBehaviorJson::OnFrameXToStateY { x, .. } => Some(*x),
+            _ => None,
+        };
+        if matches!(mov.end_behavior, EndBehaviorJson::Endless) {
+            if !can_cancel {
+                warnings.push(format!(
+                    "{at}: no way out, it never ends and has nothing to cancel or chain into"
+                ));
+            }
+            // The last group runs until the move ends
+            if let Some(last) = mov.hit_boxes.last()
+                && !last.boxes.is_empty()
+            {
+                warnings.push(format!(
+                    "{at}.hit_boxes[{}]: stays active forever, the move never ends",
+                    mov.hit_boxes.len() - 1
+                ));
+            }
+        }
+        let Some(end_frame) = end_frame else {
+            continue;
+        };
+        for (j, group) in mov.hit_boxes.iter().enumerate() {
+            if group.frame >= end_frame && !group.boxes.is_empty() {
+                warnings.push(format!(
+                    "{at}.hit_boxes[{j}].frame: {} is after the move ends on frame {end_frame}",
+                    group.frame
+                ));
+            }
+        }
+        let windows = std::iter::once((String::from("cancel_window"), mov.cancel_window)).chain(
+            mov.chains
+                .iter()
+                .enumerate()
+                .map(|(j, chain)| (format!("chains[{j}].window"), chain.window)),
+        );
+        for (field, window) in windows {
+            let range = window.to_range();
+            if window.start.is_none() {
+                continue;
+            }
+            if range.is_empty() {
+                warnings.push(format!(
+                    "{at}.{field}: starts on frame {} but ends on {}, it never opens",
+                    range.start, range.end
+                ));
+            } else if range.start >= end_frame {
+                warnings.push(format!(
+                    "{at}.{field}.start: {} is after the move ends on frame {end_frame}",
+                    range.start
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// End behaviors that hand off on frame 0 in a loop, the player would flick between them forever
+fn zero_frame_cycles(character_json: &CharacterJson) -> Vec<String> {
+    let moves = &character_json.moves;
+    let names: HashMap<&str, usize> = moves
+        .iter()
+        .enumerate()
+        .map(|(i, mov)| (mov.name.as_str(), i))
+        .collect();
+    let next: Vec<Option<usize>> = moves
+        .iter()
+        .map(|mov| match &mov.end_behavior {
+            EndBehaviorJson::OnFrameXToStateY { x: 0, y } => names.get(y.as_str()).copied(),
+            _ => None,
+        })
+        .collect();
+
+    let mut problems = Vec::new();
+    for start in 0..moves.len() {
+        let mut cycle = vec![start];
+        let mut i = start;
+        while let Some(to) = next[i] {
+            if to == start {
+                // Reported once, from the cycle's first move
+                if cycle.iter().all(|other| *other >= start) {
+                    let names: Vec<&str> = cycle
+                        .iter()
+                        .chain([&start])
+                        .map(|i| moves[*i].name.as_str())
+                        .collect();
+                    problems.push(format!(
+                        "moves[{start}]('{}').end_behavior: ends into itself on frame 0 through {}",
+                        moves[start].name,
+                        names.join(" -> ")
+                    ));
+                }
+                break;
+            }
+            if cycle.contains(&to) {
+                break;
+            }
+            cycle.push(to);
+            i = to;
+        }
+    }
+    problems
+}
+
+/// Checks every cross reference up front so a broken config reports all its problems at once
+fn validate(character_json: &CharacterJson) -> Result<(), String> {
+    let names: HashSet<&str> = character_json
+        .moves
+        .iter()
+        .map(|mov| mov.name.as_str())
+        .collect();
+    let mut problems = Vec::new();
+    let mut check_move = |path: String, name: &str| {
+        if !names.contains(name) {
+            problems.push(format!("{path}: no move named '{name}'"));
+        }
+    };
+
+    check_move(
+        String::from("block_stun_state"),
+        &character_json.block_stun_state,
+    );
+    check_move(
+        String::from("ground_hit_state"),
+        &character_json.ground_hit_state,
+    );
+    check_move(
+        String::from("launch_hit_state"),
+        &character_json.launch_hit_state,
+    );
+    let optional_states = [
+        ("hit_reactions.light", &character_json.hit_reactions.light),
+        ("hit_reactions.medium", &character_json.hit_reactions.medium),
+        ("hit_reactions.heavy", &character_json.hit_reactions.heavy),
+        ("dizzy_state", &character_json.dizzy_state),
+        ("burst_state", &character_json.burst_state),
+        ("air_recovery_state", &character_json.air_recovery_state),
+        ("guard_cancel_state", &character_json.guard_cancel_state),
+    ];
+    for (path, name) in optional_states {
+        if let Some(name) = name {
+            check_move(String::from(path), name);
+        }
+    }
+
+    for (i, mov) in character_json.moves.iter().enumerate() {
+        let at = format!("moves[{i}]('{}')", mov.name);
+        let cancel_options = [
+            ("on_hit", &mov.cancel_options.on_hit),
+            ("on_block", &mov.cancel_options.on_block),
+            ("on_whiff", &mov.cancel_options.on_whiff),
+        ];
+        for (field, targets) in cancel_options {
+            for (j, target) in targets.iter().enumerate() {
+                check_move(format!("{at}.cancel_options.{field}[{j}]"), target);
+            }
+        }
+        for (j, chain) in mov.chains.iter().enumerate() {
+            check_move(format!("{at}.chains[{j}].into"), &chain.into);
+        }
+        if let Some(target) = mov.end_behavior.target() {
+            check_move(format!("{at}.end_behavior.y"), target);
+        }
+    }
+
+    for (i, mov) in character_json.moves.iter().enumerate() {
+        let at = format!("moves[{i}]('{}')", mov.name);
+        let hit_frames = mov.hit_boxes.iter().map(|group| group.frame);
+        let hurt_frames = mov.hurt_boxes.iter().map(|group| group.frame);
+        for (field, frames) in [
+            ("hit_boxes", hit_frames.collect::<Vec<_>>()),
+            ("hurt_boxes", hurt_frames.collect()),
+        ] {
+            for (j, pair) in frames.windows(2).enumerate() {
+                if pair[1] <= pair[0] {
+                    problems.push(format!(
+                        "{at}.{field}[{}].frame: {} does not come after the previous {}",
+                        j + 1,
+                        pair[1],
+                        pair[0]
+                    ));
+                }
+            }
+        }
+    }
+    problems.extend(zero_frame_cycles(character_json));
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("\n"))
+    }
+}
+
+fn build_character(
+    loader: &mut dyn TextureLoader,
+    character_json: CharacterJson,
+    start_side: Side,
+    start_pos: FPoint,
+    pack: &PackPaths,
+) -> Result<(character::Context, character::State), String> {
     let move_names_to_pos: HashMap<_, _> = character_json
         .moves
         .iter()
@@ -31,6 +501,42 @@ pub fn deserialize<'a>(
         .map(|(i, mov)| (mov.name.as_str(), i))
         .collect();
 
+    let event_names = EventNames {
+        stances: std::iter::once(BASE_STANCE)
+            .chain(
+                character_json
+                    .stances
+                    .iter()
+                    .map(|stance| stance.name.as_str()),
+            )
+            .enumerate()
+            .map(|(i, name)| (name, i))
+            .collect(),
+        buffs: character_json
+            .buffs
+            .iter()
+            .enumerate()
+            .map(|(i, buff)| (buff.name.as_str(), i))
+            .collect(),
+    };
+    let cancel_priorities: Vec<_> = character_json
+        .moves
+        .iter()
+        .map(MoveJson::cancel_priority)
+        .collect();
+    let stances = make_stance_tables(&character_json, &move_names_to_pos)?;
+    let buffs = character_json
+        .buffs
+        .iter()
+        .map(|buff| buff.to_buff(&move_names_to_pos, &cancel_priorities))
+        .collect::<Result<_, _>>()?;
+    let mut sounds = SoundTable::new(pack.clone());
+    let assist = character_json
+        .assist
+        .as_ref()
+        .map(|assist| assist.to_assist_data(loader, &mut sounds))
+        .transpose()?;
+
     let mut state_data = Vec::new();
 
     let mut hit_box_data = Vec::new();
@@ -51,6 +557,7 @@ pub fn deserialize<'a>(
             &mut hit_box_data,
             &mut run_length_hit_boxes,
             &mut hit_box_offset,
+            &mut sounds,
         )?;
         let hurt_boxes_start = append_hurt_box_data(
             mov,
@@ -61,11 +568,16 @@ pub fn deserialize<'a>(
         let cancel_options = append_cancel_options_data(
             mov,
             &move_names_to_pos,
+            &cancel_priorities,
             &mut run_length_cancel_options,
             &mut cancel_options_offset,
         )?;
         let collision = mov.collision_box.to_collision_box();
-        let start_behaviors = mov.start_behavior.to_start_behavior();
+        let throw = mov.throw.map(ThrowJson::to_throw_data);
+        let start_behaviors = mov
+            .start_behavior
+            .behavior
+            .to_start_behavior(&character_json.movement);
 
         let end_behaviors = mov
             .end_behavior
@@ -83,23 +595,50 @@ pub fn deserialize<'a>(
 
         let cancel_window = mov.cancel_window.to_range();
 
-        let animation = mov
-            .animation
-            .make_animation(texture_creator, global_textures)?;
+        let chains = mov
+            .chains
+            .iter()
+            .map(|chain| chain.to_chain(&move_names_to_pos))
+            .collect::<Result<_, _>>()
+            .map_err(|missing_move| {
+                format!(
+                    "Move '{}', chains: Could not find move '{}'",
+                    mov.name, missing_move
+                )
+            })?;
 
-        state_data.push(StateData::new(
-            cancel_window,
-            cancel_options,
-            hit_boxes_start,
-            hurt_boxes_start,
-            start_behaviors,
-            flags,
-            end_behaviors,
-            collision,
-            animation,
-        ));
+        let animation = mov.animation.make_animation(loader)?;
 
-        let input = mov.input.to_move_input();
+        let events = mov
+            .events
+            .iter()
+            .map(|frame_event| frame_event.to_frame_event(loader, &event_names, &mut sounds))
+            .collect::<Result<_, _>>()
+            .map_err(|err| format!("Move '{}', events: {err}", mov.name))?;
+
+        state_data.push(
+            StateData::new(
+                cancel_window,
+                cancel_options,
+                chains,
+                hit_boxes_start,
+                hurt_boxes_start,
+                start_behaviors,
+                flags,
+                end_behaviors,
+                events,
+                collision,
+                throw,
+                animation,
+            )
+            .with_super_flash(mov.start_behavior.super_flash)
+            .with_sound(sounds.add(&mov.sound)),
+        );
+
+        let input = mov
+            .input
+            .to_move_input()
+            .with_max_distance(mov.max_distance);
         state_inputs.push(input);
     }
 
@@ -117,6 +656,18 @@ pub fn deserialize<'a>(
             character_json.ground_hit_state
         ));
     };
+    let hit_reaction = |name: &Option<String>| match name {
+        Some(name) => move_names_to_pos
+            .get(name.as_str())
+            .copied()
+            .ok_or_else(|| format!("Invalid hit reaction: '{name}'")),
+        None => Ok(ground_hit_state),
+    };
+    let ground_hit_states = [
+        hit_reaction(&character_json.hit_reactions.light)?,
+        hit_reaction(&character_json.hit_reactions.medium)?,
+        hit_reaction(&character_json.hit_reactions.heavy)?,
+    ];
     let Some(&launch_hit_state) = move_names_to_pos.get(character_json.launch_hit_state.as_str())
     else {
         return Err(format!(
@@ -125,17 +676,84 @@ pub fn deserialize<'a>(
         ));
     };
 
-    let start_side = character_data.start_side.to_side();
-    let start_pos = character_data.start_pos.to_fpoint();
+    let burst_state = character_json
+        .burst_state
+        .as_ref()
+        .map(|burst_state| {
+            move_names_to_pos
+                .get(burst_state.as_str())
+                .copied()
+                .ok_or_else(|| format!("Invalid burst_state: '{burst_state}'"))
+        })
+        .transpose()?;
+
+    let air_recovery_state = character_json
+        .air_recovery_state
+        .as_ref()
+        .map(|air_recovery_state| {
+            move_names_to_pos
+                .get(air_recovery_state.as_str())
+                .copied()
+                .ok_or_else(|| format!("Invalid air_recovery_state: '{air_recovery_state}'"))
+        })
+        .transpose()?;
+
+    let dizzy_state = character_json
+        .dizzy_state
+        .as_ref()
+        .map(|dizzy_state| {
+            move_names_to_pos
+                .get(dizzy_state.as_str())
+                .copied()
+                .ok_or_else(|| format!("Invalid dizzy_state: '{dizzy_state}'"))
+        })
+        .transpose()?
+        .unwrap_or(ground_hit_states[1]);
+
+    let guard_cancel_state = character_json
+        .guard_cancel_state
+        .as_ref()
+        .map(|guard_cancel_state| {
+            move_names_to_pos
+                .get(guard_cancel_state.as_str())
+                .copied()
+                .ok_or_else(|| format!("Invalid guard_cancel_state: '{guard_cancel_state}'"))
+        })
+        .transpose()?;
+
+    let win_pose = character_json
+        .win_pose
+        .as_ref()
+        .map(|win_pose| win_pose.make_animation(loader))
+        .transpose()
+        .map_err(|err| format!("win_pose: {err}"))?;
 
+    let move_names = character_json
+        .moves
+        .iter()
+        .map(|mov| mov.name.clone())
+        .collect();
     let context = character::Context::new(
         character_json.name,
         character_json.hp as f32,
+        character_json.juggle_limit.unwrap_or(DEFAULT_JUGGLE_LIMIT),
+        character_json
+            .dizzy_threshold
+            .unwrap_or(DEFAULT_DIZZY_THRESHOLD),
+        character_json.movement.gravity,
+        character_json.movement.friction,
         start_side,
         start_pos,
         block_stun_state,
-        ground_hit_state,
+        ground_hit_states,
         launch_hit_state,
+        dizzy_state,
+        burst_state,
+        air_recovery_state,
+        guard_cancel_state,
+        stances,
+        buffs,
+        assist,
         run_length_hit_boxes,
         run_length_hurt_boxes,
         run_length_cancel_options,
@@ -143,19 +761,50 @@ pub fn deserialize<'a>(
         hurt_box_data,
         state_inputs,
         state_data,
-    );
+    )
+    .with_win_pose(win_pose)
+    .with_move_names(move_names)
+    .with_sounds(sounds.paths);
     let state = character::State::new(character_json.hp as f32, start_pos, start_side);
 
     Ok((context, state))
 }
 
+/// Base movelist first, then one table per stance with its overridden moves swapped in
+fn make_stance_tables(
+    character_json: &CharacterJson,
+    map: &HashMap<&str, usize>,
+) -> Result<Vec<Vec<usize>>, String> {
+    let base: Vec<usize> = (0..character_json.moves.len()).collect();
+    let mut stances = vec![base.clone()];
+    for stance in &character_json.stances {
+        let mut table = base.clone();
+        for (from, to) in &stance.moves {
+            let find = |name: &str| {
+                map.get(name).copied().ok_or_else(|| {
+                    format!("Stance '{}': Could not find move '{name}'", stance.name)
+                })
+            };
+            table[find(from)?] = find(to)?;
+        }
+        stances.push(table);
+    }
+    Ok(stances)
+}
+
 fn append_hit_box_data(
     mov: &MoveJson,
     hit_box_data: &mut Vec<HitBox>,
     run_length_hit_boxes: &mut Vec<(usize, Range<usize>)>,
     offset: &mut usize,
+    sounds: &mut SoundTable,
 ) -> Result<usize, String> {
     let hit_boxes_start = run_length_hit_boxes.len();
+    // Hits are numbered by the groups that re-arm, the move can connect once per number
+    let mut hits = mov.hit_boxes.iter().scan(0, |hit, group| {
+        *hit += group.rearm as u32;
+        Some(*hit)
+    });
 
     for pair in mov.hit_boxes.windows(2) {
         let first = &pair[0];
@@ -164,15 +813,26 @@ fn append_hit_box_data(
         let range = *offset..*offset + first.boxes.len();
         *offset += first.boxes.len();
 
+        let hit = hits.next().unwrap_or_default();
         run_length_hit_boxes.push((duration, range));
-        hit_box_data.extend(first.boxes.iter().map(|hit_box| hit_box.to_hit_box()));
+        hit_box_data.extend(
+            first
+                .boxes
+                .iter()
+                .map(|hit_box| hit_box.to_hit_box(sounds).with_hit(hit)),
+        );
     }
     if let Some(last) = mov.hit_boxes.last() {
         let range = *offset..*offset + last.boxes.len();
         *offset += last.boxes.len();
 
+        let hit = hits.next().unwrap_or_default();
         run_length_hit_boxes.push((usize::MAX, range));
-        hit_box_data.extend(last.boxes.iter().map(|hit_box| hit_box.to_hit_box()));
+        hit_box_data.extend(
+            last.boxes
+                .iter()
+                .map(|hit_box| hit_box.to_hit_box(sounds).with_hit(hit)),
+        );
     } else {
         let range = *offset..*offset;
         run_length_hit_boxes.push((usize::MAX, range));
@@ -216,19 +876,37 @@ fn append_hurt_box_data(
 fn append_cancel_options_data(
     mov: &MoveJson,
     map: &HashMap<&str, usize>,
+    priorities: &[(i32, u32)],
     run_length_cancel_options: &mut Vec<usize>,
     offset: &mut usize,
-) -> Result<Range<usize>, String> {
-    let range = *offset..*offset + mov.cancel_options.len();
-    *offset += mov.cancel_options.len();
+) -> Result<CancelOptions, String> {
+    let mut append = |cancel_options: &[String]| {
+        let range = *offset..*offset + cancel_options.len();
+        *offset += cancel_options.len();
 
-    for cancel_option in &mov.cancel_options {
-        let index = map
-            .get(cancel_option.as_str())
-            .ok_or_else(|| format!("Could not find a move named: {cancel_option}"))?;
-        run_length_cancel_options.push(*index);
-    }
-    Ok(range)
+        let mut indices = cancel_options
+            .iter()
+            .map(|cancel_option| {
+                map.get(cancel_option.as_str())
+                    .copied()
+                    .ok_or_else(|| format!("Could not find a move named: {cancel_option}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        sort_by_priority(&mut indices, priorities);
+        run_length_cancel_options.extend(indices);
+        Ok::<_, String>(range)
+    };
+
+    Ok(CancelOptions::new(
+        append(&mov.cancel_options.on_hit)?,
+        append(&mov.cancel_options.on_block)?,
+        append(&mov.cancel_options.on_whiff)?,
+    ))
+}
+
+/// Cancels take the first option that matches, so the highest priority goes first, ties keep the JSON order
+fn sort_by_priority(cancel_options: &mut [usize], priorities: &[(i32, u32)]) {
+    cancel_options.sort_by_key(|index| std::cmp::Reverse(priorities[*index]));
 }
 
 fn get_running_length_duration(
@@ -247,30 +925,319 @@ fn get_running_length_duration(
 
 #[derive(Deserialize)]
 struct CharacterJson {
+    /// Read by the migrations before the rest is parsed, missing on configs from before it was added
+    #[allow(dead_code)]
+    #[serde(default)]
+    schema_version: usize,
     name: String,
     hp: usize,
+    /// Juggle points an air combo can spend before the victim is knocked out of it
+    juggle_limit: Option<u32>,
+    /// Dizzy gauge it takes to get dizzied
+    dizzy_threshold: Option<f32>,
+    #[serde(default)]
+    movement: MovementJson,
     moves: Vec<MoveJson>,
     block_stun_state: String,
     ground_hit_state: String,
+    /// Ground hit states picked by the hit's strength, missing ones use the ground hit state
+    #[serde(default)]
+    hit_reactions: HitReactionsJson,
     launch_hit_state: String,
+    /// Stun based move dizzied players are stuck in, the ground hit state if missing
+    dizzy_state: Option<String>,
+    /// Move used to burst out of hit stun, characters without one can't burst
+    burst_state: Option<String>,
+    /// Move launched players recover into once a hit's untech time is over
+    air_recovery_state: Option<String>,
+    /// Move blocking players can spend meter to counter attack with
+    guard_cancel_state: Option<String>,
+    #[serde(default)]
+    stances: Vec<StanceJson>,
+    #[serde(default)]
+    buffs: Vec<BuffJson>,
+    assist: Option<AssistJson>,
+    /// Alternate colors, picked at character select
+    #[serde(default)]
+    palettes: Vec<PaletteJson>,
+    /// Tint telling player2 apart when both players picked the same colors
+    #[serde(default = "default_mirror_color")]
+    mirror_color: [u8; 3],
+    /// Played for the winner on the victory screen
+    win_pose: Option<AnimationJson>,
+}
+
+fn default_mirror_color() -> [u8; 3] {
+    DEFAULT_MIRROR_COLOR
+}
+
+#[derive(Deserialize, Default)]
+struct HitReactionsJson {
+    light: Option<String>,
+    medium: Option<String>,
+    heavy: Option<String>,
+}
+
+/// Every sprite of the character is color modulated by `color`
+#[derive(Deserialize)]
+struct PaletteJson {
+    #[allow(dead_code)]
+    name: String,
+    color: [u8; 3],
+}
+
+impl PaletteJson {
+    fn to_tint(&self) -> Tint {
+        let [r, g, b] = self.color;
+        (r, g, b)
+    }
+}
+
+/// Mobility shared by the movement start behaviors, speeds point the way the character faces
+#[derive(Deserialize)]
+#[serde(default)]
+struct MovementJson {
+    walk_speed: f32,
+    back_walk_speed: f32,
+    dash_speed: f32,
+    back_dash_speed: f32,
+    air_dash_speed: f32,
+    air_back_dash_speed: f32,
+    jump_vel: f32,
+    jump_forward_speed: f32,
+    jump_back_speed: f32,
+    gravity: f32,
+    friction: f32,
+}
+
+impl Default for MovementJson {
+    fn default() -> Self {
+        Self {
+            walk_speed: 3.0,
+            back_walk_speed: 2.0,
+            dash_speed: 10.0,
+            back_dash_speed: 10.0,
+            air_dash_speed: 10.0,
+            air_back_dash_speed: 10.0,
+            jump_vel: 10.0,
+            jump_forward_speed: 4.0,
+            jump_back_speed: 4.0,
+            gravity: DEFAULT_GRAVITY,
+            friction: DEFAULT_FRICTION,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AssistJson {
+    cooldown: usize,
+    /// Damage the assist takes before it is knocked out of its move
+    hp: f32,
+    moves: Vec<AssistMoveJson>,
+}
+
+impl AssistJson {
+    fn to_assist_data(
+        &self,
+        loader: &mut dyn TextureLoader,
+        sounds: &mut SoundTable,
+    ) -> Result<AssistData, String> {
+        if !(1..=MAX_ASSIST_MOVES).contains(&self.moves.len()) {
+            return Err(format!(
+                "Assist needs between 1 and {MAX_ASSIST_MOVES} moves, found {}",
+                self.moves.len()
+            ));
+        }
+        let moves = self
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(i, mov)| {
+                mov.to_assist_move(loader, sounds)
+                    .map_err(|err| format!("Assist move {i}: {err}"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(AssistData::new(self.cooldown, self.hp, moves))
+    }
+}
+
+/// Offset and velocity point the way the caller faces, the hit box is out from `start` until `end`
+/// and the hurt boxes for the whole move
+#[derive(Deserialize)]
+struct AssistMoveJson {
+    input: InputJson,
+    offset: FPointJson,
+    vel: FPointJson,
+    duration: usize,
+    start: usize,
+    end: usize,
+    hit_box: HitBoxJson,
+    hurt_boxes: Vec<HurtBoxJson>,
+    animation: AnimationJson,
+}
+
+impl AssistMoveJson {
+    fn to_assist_move(
+        &self,
+        loader: &mut dyn TextureLoader,
+        sounds: &mut SoundTable,
+    ) -> Result<AssistMove, String> {
+        Ok(AssistMove::new(
+            self.input.to_move_input(),
+            self.offset.to_fpoint(),
+            self.vel.to_fpoint(),
+            self.duration,
+            self.start..self.end,
+            self.hit_box.to_hit_box(sounds),
+            self.hurt_boxes
+                .iter()
+                .copied()
+                .map(HurtBoxJson::to_hurt_box)
+                .collect(),
+            self.animation.make_animation(loader)?,
+        ))
+    }
+}
+
+/// Alternate movelist, cancels into a move listed here go to its replacement instead
+#[derive(Deserialize)]
+struct StanceJson {
+    name: String,
+    moves: HashMap<String, String>,
+}
+
+/// Timed modifiers started by an `ApplyBuff` event, missing multipliers leave that stat alone
+#[derive(Deserialize)]
+struct BuffJson {
+    name: String,
+    duration: usize,
+    dmg_mult: Option<f32>,
+    speed_mult: Option<f32>,
+    /// Extra moves that can be cancelled into while the buff runs
+    #[serde(default)]
+    cancel_options: Vec<String>,
+}
+
+impl BuffJson {
+    fn to_buff(
+        &self,
+        map: &HashMap<&str, usize>,
+        priorities: &[(i32, u32)],
+    ) -> Result<Buff, String> {
+        if self.duration == 0 {
+            return Err(format!("Buff '{}': duration must be above 0", self.name));
+        }
+        let mut cancel_options: Vec<_> = self
+            .cancel_options
+            .iter()
+            .map(|name| {
+                map.get(name.as_str())
+                    .copied()
+                    .ok_or_else(|| format!("Buff '{}': Could not find move '{name}'", self.name))
+            })
+            .collect::<Result<_, _>>()?;
+        sort_by_priority(&mut cancel_options, priorities);
+        Ok(Buff::new(
+            self.duration,
+            self.dmg_mult.unwrap_or(1.0),
+            self.speed_mult.unwrap_or(1.0),
+            cancel_options,
+        ))
+    }
+}
+
+/// Names events can refer to, mapped to their index
+struct EventNames<'a> {
+    stances: HashMap<&'a str, usize>,
+    buffs: HashMap<&'a str, usize>,
+}
+
+/// Sound paths the character plays, moves and hit boxes refer to them by index
+struct SoundTable {
+    paths: Vec<String>,
+    indices: HashMap<String, usize>,
+    pack: PackPaths,
+}
+
+impl SoundTable {
+    fn new(pack: PackPaths) -> Self {
+        Self {
+            paths: Vec::new(),
+            indices: HashMap::new(),
+            pack,
+        }
+    }
+
+    /// Each path is listed once however many moves share it, paths leaving the pack play nothing
+    fn add(&mut self, path: &Option<String>) -> Option<usize> {
+        let path = match self.pack.resolve(path.as_ref()?) {
+            Ok(path) => path,
+            Err(err) => {
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] Skipping sound: {err}");
+                }
+                return None;
+            }
+        };
+        let index = *self.indices.entry(path.clone()).or_insert_with(|| {
+            self.paths.push(path.clone());
+            self.paths.len() - 1
+        });
+        Some(index)
+    }
 }
 
 #[derive(Deserialize)]
 struct MoveJson {
     name: String,
     input: InputJson,
-    hit_boxes: Vec<RunLenJson<HitBoxJson>>,
-    hurt_boxes: Vec<RunLenJson<HurtBoxJson>>,
+    /// Cancel options that share an input resolve to the higher priority, defaults to 0
+    #[serde(default)]
+    priority: i32,
+    /// Only comes out while the opponent is at most this far away, for close normals
+    max_distance: Option<f32>,
+    hit_boxes: Vec<HitRunLenJson>,
+    hurt_boxes: Vec<HurtRunLenJson>,
     collision_box: CollisionBoxJson,
+    /// Present on throws, which grab instead of hitting
+    #[serde(default)]
+    throw: Option<ThrowJson>,
 
-    start_behavior: StartBehaviorJson,
+    start_behavior: StartJson,
     flags: Vec<FlagsJson>,
     end_behavior: EndBehaviorJson,
+    #[serde(default)]
+    events: Vec<FrameEventJson>,
 
     cancel_window: CancelWindowJson,
-    cancel_options: Vec<String>,
+    cancel_options: CancelOptionsJson,
+    /// Target combos that only start from this move
+    #[serde(default)]
+    chains: Vec<ChainJson>,
 
     animation: AnimationJson,
+    /// Played when the move starts, the whoosh of an attack
+    sound: Option<String>,
+}
+
+impl MoveJson {
+    /// Explicit priority first, then the harder input wins so a DP isn't eaten by the plain button,
+    /// then a close version wins over the far one on the same input
+    fn cancel_priority(&self) -> (i32, u32) {
+        (
+            self.priority,
+            self.input.complexity() * 2 + self.max_distance.is_some() as u32,
+        )
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct StartJson {
+    #[serde(flatten)]
+    behavior: StartBehaviorJson,
+    /// Frames the opponent freezes for under a darkened screen, for supers
+    #[serde(default)]
+    super_flash: usize,
 }
 
 #[derive(Deserialize, Clone, Copy)]
@@ -279,18 +1246,150 @@ enum StartBehaviorJson {
     None,
     SetVel { x: f32, y: f32 },
     AddFrictionVel { x: f32, y: f32 },
+    // Velocities taken from the character's movement section
+    Walk,
+    BackWalk,
+    Dash,
+    BackDash,
+    AirDash,
+    AirBackDash,
+    Jump,
+    JumpForward,
+    JumpBack,
 }
 
 impl StartBehaviorJson {
-    fn to_start_behavior(self) -> StartBehavior {
+    fn to_start_behavior(self, movement: &MovementJson) -> StartBehavior {
+        let set_vel = |x: f32, y: f32| StartBehavior::SetVel { x, y };
         match self {
             StartBehaviorJson::None => StartBehavior::None,
             StartBehaviorJson::SetVel { x, y } => StartBehavior::SetVel { x, y },
             StartBehaviorJson::AddFrictionVel { x, y } => StartBehavior::AddFrictionVel { x, y },
+            StartBehaviorJson::Walk => set_vel(movement.walk_speed, 0.0),
+            StartBehaviorJson::BackWalk => set_vel(-movement.back_walk_speed, 0.0),
+            StartBehaviorJson::Dash => set_vel(movement.dash_speed, 0.0),
+            StartBehaviorJson::BackDash => set_vel(-movement.back_dash_speed, 0.0),
+            StartBehaviorJson::AirDash => set_vel(movement.air_dash_speed, 0.0),
+            StartBehaviorJson::AirBackDash => set_vel(-movement.air_back_dash_speed, 0.0),
+            StartBehaviorJson::Jump => set_vel(0.0, movement.jump_vel),
+            StartBehaviorJson::JumpForward => {
+                set_vel(movement.jump_forward_speed, movement.jump_vel)
+            }
+            StartBehaviorJson::JumpBack => set_vel(-movement.jump_back_speed, movement.jump_vel),
         }
     }
 }
 
+#[derive(Deserialize)]
+struct FrameEventJson {
+    frame: usize,
+    event: AnimationEventJson,
+}
+
+impl FrameEventJson {
+    fn to_frame_event(
+        &self,
+        loader: &mut dyn TextureLoader,
+        names: &EventNames,
+        sounds: &mut SoundTable,
+    ) -> Result<FrameEvent, String> {
+        Ok(FrameEvent::new(
+            self.frame,
+            self.event.to_animation_event(loader, names, sounds)?,
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum AnimationEventJson {
+    SetVel {
+        x: f32,
+        y: f32,
+    },
+    AddFrictionVel {
+        x: f32,
+        y: f32,
+    },
+    SetInvuln {
+        flags: Vec<FlagsJson>,
+        enabled: bool,
+    },
+    SpawnProjectile {
+        offset: FPointJson,
+        vel: FPointJson,
+        lifetime: usize,
+        /// Clashes it can take from other projectiles, defaults to 1
+        durability: Option<u32>,
+        hit_box: HitBoxJson,
+        /// Boxed so the other events stay small
+        animation: Box<AnimationJson>,
+    },
+    /// "default" goes back to the base movelist
+    SetStance {
+        stance: String,
+    },
+    ApplyBuff {
+        buff: String,
+    },
+}
+
+impl AnimationEventJson {
+    fn to_animation_event(
+        &self,
+        loader: &mut dyn TextureLoader,
+        names: &EventNames,
+        sounds: &mut SoundTable,
+    ) -> Result<AnimationEvent, String> {
+        Ok(match self {
+            Self::SetVel { x, y } => AnimationEvent::SetVel { x: *x, y: *y },
+            Self::AddFrictionVel { x, y } => AnimationEvent::AddFrictionVel { x: *x, y: *y },
+            Self::SetInvuln { flags, enabled } => {
+                let flags = flags.iter().fold(StateFlags::NONE, |flags, next| {
+                    flags.union(next.to_state_json())
+                });
+                let invuln_flags = StateFlags::StrikeInvuln
+                    | StateFlags::ThrowInvuln
+                    | StateFlags::ProjectileInvuln;
+                if !invuln_flags.contains(flags) {
+                    return Err(String::from("SetInvuln only takes invulnerability flags"));
+                }
+                AnimationEvent::SetInvuln {
+                    flags,
+                    enabled: *enabled,
+                }
+            }
+            Self::SpawnProjectile {
+                offset,
+                vel,
+                lifetime,
+                durability,
+                hit_box,
+                animation,
+            } => AnimationEvent::SpawnProjectile(ProjectileData::new(
+                offset.to_fpoint(),
+                vel.to_fpoint(),
+                *lifetime,
+                durability.unwrap_or(DEFAULT_PROJECTILE_DURABILITY),
+                hit_box.to_hit_box(sounds),
+                animation.make_animation(loader)?,
+            )),
+            Self::SetStance { stance } => AnimationEvent::SetStance(
+                *names
+                    .stances
+                    .get(stance.as_str())
+                    .ok_or_else(|| format!("Could not find stance '{stance}'"))?,
+            ),
+            Self::ApplyBuff { buff } => AnimationEvent::ApplyBuff(
+                *names
+                    .buffs
+                    .get(buff.as_str())
+                    .ok_or_else(|| format!("Could not find buff '{buff}'"))?,
+            ),
+        })
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(tag = "type")]
 enum EndBehaviorJson {
@@ -301,6 +1400,16 @@ enum EndBehaviorJson {
 }
 
 impl EndBehaviorJson {
+    /// Move it ends into, if any
+    fn target(&self) -> Option<&str> {
+        match self {
+            Self::Endless => None,
+            Self::OnFrameXToStateY { y, .. }
+            | Self::OnGroundedToStateY { y }
+            | Self::OnStunEndToStateY { y } => Some(y),
+        }
+    }
+
     fn to_end_behavior(&self, map: &HashMap<&str, usize>) -> Result<EndBehavior, String> {
         Ok(match self {
             EndBehaviorJson::Endless => EndBehavior::Endless,
@@ -318,6 +1427,37 @@ impl EndBehaviorJson {
     }
 }
 
+/// Moves a state can cancel into during its cancel window, split by how its attack went
+#[derive(Deserialize)]
+struct CancelOptionsJson {
+    #[serde(default)]
+    on_hit: Vec<String>,
+    #[serde(default)]
+    on_block: Vec<String>,
+    #[serde(default)]
+    on_whiff: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ChainJson {
+    input: InputJson,
+    into: String,
+    window: CancelWindowJson,
+}
+
+impl ChainJson {
+    fn to_chain(&self, map: &HashMap<&str, usize>) -> Result<Chain, String> {
+        let into = *map
+            .get(self.into.as_str())
+            .ok_or_else(|| self.into.clone())?;
+        Ok(Chain::new(
+            self.window.to_range(),
+            self.input.to_move_input(),
+            into,
+        ))
+    }
+}
+
 #[derive(Deserialize, Clone, Copy)]
 struct CancelWindowJson {
     start: Option<usize>,
@@ -346,6 +1486,13 @@ enum RelativeDirectionJson {
 }
 
 impl RelativeDirectionJson {
+    fn complexity(self) -> u32 {
+        match self {
+            Self::Any => 0,
+            _ => 1,
+        }
+    }
+
     fn to_relative_direction(self) -> RelativeDirection {
         match self {
             RelativeDirectionJson::Any => RelativeDirection::None,
@@ -375,6 +1522,14 @@ enum RelativeMotionJson {
 }
 
 impl RelativeMotionJson {
+    fn complexity(self) -> u32 {
+        match self {
+            Self::DpForward | Self::DpBack => 3,
+            Self::QcForward | Self::QcBack => 2,
+            Self::DownDown | Self::ForwardForward | Self::BackBack => 1,
+        }
+    }
+
     fn to_relative_motion(self) -> RelativeMotion {
         match self {
             RelativeMotionJson::DownDown => RelativeMotion::DownDown,
@@ -395,6 +1550,10 @@ enum ButtonJson {
     L,
     M,
     H,
+    LM,
+    MH,
+    #[allow(clippy::upper_case_acronyms)]
+    LMH,
 }
 
 impl ButtonJson {
@@ -404,6 +1563,9 @@ impl ButtonJson {
             ButtonJson::L => ButtonFlag::L,
             ButtonJson::None => ButtonFlag::NONE,
             ButtonJson::M => ButtonFlag::M,
+            ButtonJson::LM => ButtonFlag::L | ButtonFlag::M,
+            ButtonJson::MH => ButtonFlag::M | ButtonFlag::H,
+            ButtonJson::LMH => ButtonFlag::L | ButtonFlag::M | ButtonFlag::H,
         }
     }
 }
@@ -421,6 +1583,16 @@ enum InputJson {
 }
 
 impl InputJson {
+    /// Button presses beat plain movement, then the harder motion, the more buttons and a held direction over any
+    fn complexity(self) -> u32 {
+        let (motion, dir, button) = match self {
+            Self::Direction { dir, button } => (0, dir.complexity(), button),
+            Self::Motion { motion, button } => (motion.complexity(), 0, button),
+        };
+        let buttons = button.to_button_flag().bits().count_ones();
+        (buttons > 0) as u32 * 64 + motion * 16 + buttons * 2 + dir
+    }
+
     fn to_move_input(self) -> MoveInput {
         match self {
             Self::Direction { dir, button } => MoveInput::new(
@@ -438,9 +1610,21 @@ impl InputJson {
 }
 
 #[derive(Deserialize)]
-struct RunLenJson<T> {
+struct HurtRunLenJson {
     frame: usize,
-    boxes: Vec<T>,
+    boxes: Vec<HurtBoxJson>,
+    /// Hit box only, read so lint can point out it does nothing here
+    #[serde(default)]
+    rearm: bool,
+}
+
+#[derive(Deserialize)]
+struct HitRunLenJson {
+    frame: usize,
+    boxes: Vec<HitBoxJson>,
+    /// Lets the move connect again even if an earlier group already hit
+    #[serde(default)]
+    rearm: bool,
 }
 
 #[derive(Deserialize, Clone, Copy)]
@@ -462,6 +1646,24 @@ impl BlockTypeJson {
 }
 
 #[derive(Deserialize, Clone, Copy)]
+#[serde(tag = "type")]
+enum HitStrengthJson {
+    Light,
+    Medium,
+    Heavy,
+}
+
+impl HitStrengthJson {
+    fn to_hit_strength(self) -> HitStrength {
+        match self {
+            Self::Light => HitStrength::Light,
+            Self::Medium => HitStrength::Medium,
+            Self::Heavy => HitStrength::Heavy,
+        }
+    }
+}
+
+#[derive(Deserialize)]
 struct HitBoxJson {
     rect: RectJson,
     dmg: usize,
@@ -469,10 +1671,54 @@ struct HitBoxJson {
     hit_stun: Option<u32>,
     cancel_window: usize,
     block_type: BlockTypeJson,
+    juggle_points: Option<u32>,
+    /// Combo scaling taken off when this hit starts a combo
+    initial_proration: Option<f32>,
+    /// Combo scaling taken off when this hit lands mid combo
+    forced_proration: Option<f32>,
+    /// Damage floor as a share of dmg, lets supers stay worth it at the end of long combos
+    min_dmg_scaling: Option<f32>,
+    /// Knockback when the hit launches, the launch state's start behavior is used without it
+    launch: Option<LaunchJson>,
+    hit_stop: Option<usize>,
+    /// Dizzy gauge filled on hit, the damage if missing
+    dizzy: Option<f32>,
+    /// Which ground hit reaction plays, medium if missing
+    strength: Option<HitStrengthJson>,
+    block_push_back: Option<f32>,
+    hit_sound: Option<String>,
+    /// The hit sound plays on block too if missing
+    block_sound: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct LaunchJson {
+    /// Away from the attacker
+    x: f32,
+    y: f32,
+    /// Frames before the victim can air recover, never if missing
+    untech: Option<usize>,
+}
+
+impl LaunchJson {
+    fn to_launch(self) -> Launch {
+        Launch::new(FPoint::new(self.x, self.y), self.untech)
+    }
 }
 
 impl HitBoxJson {
-    fn to_hit_box(self) -> HitBox {
+    fn to_hit_box(&self, sounds: &mut SoundTable) -> HitBox {
+        let hit_sound = sounds.add(&self.hit_sound);
+        let block_sound = sounds.add(&self.block_sound).or(hit_sound);
+        let default_proration = Proration::default();
+        let proration = Proration::new(
+            self.initial_proration
+                .unwrap_or(default_proration.initial()),
+            self.forced_proration.unwrap_or(default_proration.forced()),
+            self.min_dmg_scaling
+                .unwrap_or(default_proration.min_dmg_scaling()),
+        );
+
         HitBox::new(
             self.rect.to_frect(),
             self.dmg as f32,
@@ -480,7 +1726,18 @@ impl HitBoxJson {
             self.hit_stun.unwrap_or(u32::MAX),
             self.cancel_window,
             self.block_type.to_block_type(),
+            self.juggle_points.unwrap_or(DEFAULT_JUGGLE_POINTS),
+        )
+        .with_proration(proration)
+        .with_launch(self.launch.map(LaunchJson::to_launch))
+        .with_hit_stop(self.hit_stop.unwrap_or(DEFAULT_HIT_STOP))
+        .with_dizzy(self.dizzy.unwrap_or(self.dmg as f32))
+        .with_strength(
+            self.strength
+                .map_or(HitStrength::Medium, HitStrengthJson::to_hit_strength),
         )
+        .with_block_push_back(self.block_push_back.unwrap_or(DEFAULT_BLOCK_PUSH_BACK))
+        .with_sounds(hit_sound, block_sound)
     }
 }
 
@@ -495,6 +1752,31 @@ impl HurtBoxJson {
     }
 }
 
+#[derive(Deserialize, Clone, Copy)]
+struct ThrowJson {
+    rect: RectJson,
+    dmg: usize,
+    start: usize,
+    end: usize,
+    tech_window: usize,
+    /// Thrown player's position relative to the thrower afterwards, x points the way the thrower faces
+    release_offset: FPointJson,
+}
+
+impl ThrowJson {
+    fn to_throw_data(self) -> ThrowData {
+        ThrowData::new(
+            self.start..self.end,
+            ThrowBox::new(
+                self.rect.to_frect(),
+                self.dmg as f32,
+                self.tech_window,
+                self.release_offset.to_fpoint(),
+            ),
+        )
+    }
+}
+
 #[derive(Deserialize, Clone, Copy)]
 struct CollisionBoxJson {
     rect: RectJson,
@@ -505,3 +1787,100 @@ impl CollisionBoxJson {
         CollisionBox::new(self.rect.to_frect())
     }
 }
+
+#[cfg(test)]
+fn test_character() -> CharacterJson {
+    parse_character(
+        &crate::game::assets::FileSource,
+        "./resources/character1/config.json",
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_zero_frame_cycle_is_rejected() {
+    let mut character_json = test_character();
+    assert!(zero_frame_cycles(&character_json).is_empty());
+
+    let names = [4, 5].map(|i| character_json.moves[i].name.clone());
+    character_json.moves[4].end_behavior = EndBehaviorJson::OnFrameXToStateY {
+        x: 0,
+        y: names[1].clone(),
+    };
+    character_json.moves[5].end_behavior = EndBehaviorJson::OnFrameXToStateY {
+        x: 0,
+        y: names[0].clone(),
+    };
+
+    let problems = zero_frame_cycles(&character_json);
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("moves[4]"));
+    assert!(validate(&character_json).is_err());
+}
+
+#[test]
+fn test_exitless_move_is_linted() {
+    let mut character_json = test_character();
+    let no_way_out = |warnings: &[String]| {
+        warnings
+            .iter()
+            .any(|warning| warning.starts_with("moves[4]") && warning.contains("no way out"))
+    };
+    assert!(!no_way_out(&lint(&character_json)));
+
+    let mov = &mut character_json.moves[4];
+    mov.end_behavior = EndBehaviorJson::Endless;
+    mov.cancel_options = CancelOptionsJson {
+        on_hit: Vec::new(),
+        on_block: Vec::new(),
+        on_whiff: Vec::new(),
+    };
+    mov.chains.clear();
+
+    assert!(no_way_out(&lint(&character_json)));
+}
+
+#[test]
+fn test_cancel_priority_order() {
+    let mut character_json = test_character();
+    let moves = &mut character_json.moves;
+    for mov in moves.iter_mut().take(4) {
+        mov.priority = 0;
+        mov.max_distance = None;
+        mov.input = InputJson::Direction {
+            dir: RelativeDirectionJson::Any,
+            button: ButtonJson::L,
+        };
+    }
+    let sorted = |moves: &[MoveJson]| {
+        let priorities: Vec<_> = moves.iter().map(MoveJson::cancel_priority).collect();
+        let mut cancel_options = vec![0, 1, 2, 3];
+        sort_by_priority(&mut cancel_options, &priorities);
+        cancel_options
+    };
+    // Ties keep the JSON order
+    assert_eq!(sorted(moves), [0, 1, 2, 3]);
+
+    moves[3].max_distance = Some(100.0);
+    assert_eq!(sorted(moves), [3, 0, 1, 2]);
+
+    moves[2].input = InputJson::Motion {
+        motion: RelativeMotionJson::DpForward,
+        button: ButtonJson::L,
+    };
+    assert_eq!(sorted(moves), [2, 3, 0, 1]);
+
+    moves[1].priority = 1;
+    assert_eq!(sorted(moves), [1, 2, 3, 0]);
+}
+
+#[test]
+fn test_hurt_box_rearm_is_linted() {
+    let mut character_json = test_character();
+    let rearm = |warnings: &[String]| warnings.iter().any(|warning| warning.contains(".rearm"));
+    assert!(!rearm(&lint(&character_json)));
+
+    character_json.moves[0].hurt_boxes[0].rearm = true;
+
+    assert!(rearm(&lint(&character_json)));
+}
@@ -1,97 +1,279 @@
-use std::error::Error;
+use std::{collections::HashMap, error::Error, rc::Rc};
 
 use sdl3::{
-    EventPump,
-    render::{Canvas, TextureCreator},
+    EventPump, GamepadSubsystem,
+    render::{Canvas, FPoint, FRect, TextureCreator},
     video::{Window, WindowContext},
 };
 use serde::Deserialize;
 
 use crate::game::{
-    Game, GameContext, GameState, PlayerInputs,
-    deserialize::{AnimationJson, FPointJson, SideJson, TextureJson, character},
-    input::{self, PLAYER1_BUTTONS, PLAYER1_DIRECTIONS, PLAYER2_BUTTONS, PLAYER2_DIRECTIONS},
-    render::Camera,
-    scene::Scenes,
-    stage::Stage,
+    Game, GameContext, GameState, PlayerInputs, SETTINGS_PATH, Side,
+    assets::AssetSource,
+    audio::Audio,
+    deserialize::{
+        AnimationJson, FPointJson, RectJson, SideJson, TextureJson, character,
+        migrate::GAME_MIGRATIONS, pack, parse_versioned_json, stage,
+    },
+    gamepad::Gamepads,
+    input::{self, PLAYER1_BINDINGS, PLAYER2_BINDINGS},
+    locale::{self, Strings},
+    perf::PerfOverlay,
+    render::{Camera, SdlTextureLoader, TextureLoader},
+    scene::{
+        Scenes, UiSkin,
+        gameplay::{HudLayout, WinIcons},
+    },
+    settings::{Controls, Settings},
+    tuning::Tuning,
+    watcher::AssetWatcher,
 };
 
+const DEFAULT_LADDER_DIR: &str = "./ai/ladder";
+
 pub fn deserialize<'a>(
     texture_creator: &'a TextureCreator<WindowContext>,
     canvas: Canvas<Window>,
     events: EventPump,
+    gamepads: GamepadSubsystem,
     screen_dim: (u32, u32),
     config: &str,
+    assets: Rc<dyn AssetSource>,
 ) -> Result<Game<'a>, Box<dyn Error>> {
-    let src = std::fs::read_to_string(config)
-        .map_err(|err| format!("Failed to open: '{config}': {err}"))?;
-    let game_json: GameJson =
-        serde_json::from_str(&src).map_err(|err| format!("Failed to parse: '{config}': {err}"))?;
+    let mut loader = SdlTextureLoader::new(texture_creator, assets.clone());
+    let (context, state, inputs) = deserialize_simulation(&mut loader, screen_dim, config)?;
+
+    let mut game = Game {
+        context,
+        previous_state: state.clone(),
+        state,
+        scene: Scenes::new(),
+        inputs,
+        texture_loader: loader,
+        canvas,
+        events,
+        config: config.to_string(),
+        watcher: None,
+        tuning: Tuning::default(),
+        perf: PerfOverlay::default(),
+        audio: Audio::new(assets),
+        slow_motion: 0,
+        frozen: false,
+        frame_steps: 0,
+        confirming: None,
+        escaped_to: None,
+        gamepads: Gamepads::new(gamepads),
+        transition: None,
+    };
+    // Character configs hot reload in every build, the rest only in debug builds
+    game.watcher = AssetWatcher::new(config, &game.context.character_configs)
+        .inspect_err(|err| {
+            if cfg!(feature = "debug") {
+                println!("[WARNING] Hot reloading is off: {err}");
+            }
+        })
+        .ok();
+    let settings = Settings::load(SETTINGS_PATH);
+    game.apply_display(settings.display)?;
+    game.apply_settings(settings);
+    // The roster or stages may have shrunk since, the config's defaults stay in that case
+    if let Err(err) = game.pick_last_played()
+        && cfg!(feature = "debug")
+    {
+        println!("[WARNING] Ignoring last played: {err}");
+    }
+    Ok(game)
+}
 
-    let mut global_textures = Vec::new();
+/// Builds everything needed to step the game, textures only go as far as the loader takes them
+pub fn deserialize_simulation(
+    loader: &mut dyn TextureLoader,
+    screen_dim: (u32, u32),
+    config: &str,
+) -> Result<(GameContext, GameState, PlayerInputs), Box<dyn Error>> {
+    let src = loader
+        .assets()
+        .read_to_string(config)
+        .map_err(|err| format!("Failed to open: '{config}': {err}"))?;
+    let game_json: GameJson = parse_versioned_json(config, &src, GAME_MIGRATIONS)?;
 
+    let players = &game_json.scene_data.gameplay.players;
+    let start_slots = [players.player1.start(), players.player2.start()];
+    // The default characters are often a mirror match, so player2 starts on the next palette
+    let character_palettes = [
+        0,
+        (players.player1.config == players.player2.config) as usize,
+    ];
     let (player1_context, player1_state) = character::deserialize(
-        texture_creator,
-        &mut global_textures,
-        &game_json.scene_data.gameplay.players.player1,
+        loader,
+        &players.player1.config,
+        start_slots[0].0,
+        start_slots[0].1,
+        character_palettes[0],
+        None,
     )?;
-    let (player1_input_history, player1_inputs) =
-        input::new_inputs(PLAYER1_BUTTONS, PLAYER1_DIRECTIONS);
+    let (player1_input_history, player1_inputs) = input::new_inputs(PLAYER1_BINDINGS);
 
     let (player2_context, player2_state) = character::deserialize(
-        texture_creator,
-        &mut global_textures,
-        &game_json.scene_data.gameplay.players.player2,
+        loader,
+        &players.player2.config,
+        start_slots[1].0,
+        start_slots[1].1,
+        character_palettes[1],
+        (players.player1.config == players.player2.config).then_some(character_palettes[0]),
     )?;
-    let (player2_input_history, player2_inputs) =
-        input::new_inputs(PLAYER2_BUTTONS, PLAYER2_DIRECTIONS);
-
-    Ok(Game {
-        context: GameContext {
-            should_quit: false,
-            matchmaking_server: game_json.scene_data.gameplay.matchmaking_server,
-            left_agent_filepath: game_json.ai.left_agent_path,
-            right_agent_filepath: game_json.ai.right_agent_path,
-            main_menu_texture: game_json
-                .scene_data
-                .main_menu
-                .background
-                .make_texture(texture_creator, &mut global_textures)?,
-            round_start_animation: game_json
+    let (player2_input_history, player2_inputs) = input::new_inputs(PLAYER2_BINDINGS);
+
+    let packs = pack::scan();
+    let mut roster = game_json
+        .scene_data
+        .gameplay
+        .roster
+        .clone()
+        .unwrap_or_else(|| {
+            let mut configs = vec![players.player1.config.clone()];
+            if players.player2.config != players.player1.config {
+                configs.push(players.player2.config.clone());
+            }
+            configs
+        });
+
+    roster.extend(packs.characters);
+
+    let stage_configs: Vec<_> = game_json
+        .scene_data
+        .gameplay
+        .stages
+        .iter()
+        .chain(&packs.stages)
+        .collect();
+    if stage_configs.is_empty() {
+        return Err(format!("No stages in: '{config}'").into());
+    }
+    let stages = stage_configs
+        .iter()
+        .map(|stage_config| stage::deserialize(loader, stage_config))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let matchmaking = &game_json.scene_data.gameplay.matchmaking;
+    let matchmaking_servers: HashMap<String, String> = matchmaking
+        .environments
+        .iter()
+        .map(|(environment, endpoint)| (environment.clone(), endpoint.address()))
+        .collect();
+    let matchmaking_server = matchmaking_servers
+        .get(&matchmaking.environment)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "Invalid: '{config}': scene_data.gameplay.matchmaking.environment: no environment named '{}'",
+                matchmaking.environment
+            )
+        })?;
+
+    let context = GameContext {
+        should_quit: false,
+        matchmaking_server,
+        matchmaking_servers,
+        left_agent_filepath: game_json.ai.left_agent_path,
+        right_agent_filepath: game_json.ai.right_agent_path,
+        character_agent_filepaths: game_json.ai.character_models,
+        agents_picked: [false; 2],
+        distilled_agent_filepath: game_json.ai.distilled_agent_path,
+        picked_agents: HashMap::new(),
+        agent_action_repeat: game_json.ai.action_repeat.unwrap_or(1),
+        ladder_dirpath: game_json
+            .ai
+            .ladder_dir
+            .unwrap_or_else(|| DEFAULT_LADDER_DIR.to_string()),
+        roster,
+        start_slots,
+        pending_characters: None,
+        character_configs: [
+            players.player1.config.clone(),
+            players.player2.config.clone(),
+        ],
+        character_palettes,
+        settings: Settings::default(),
+        pending_settings: None,
+        strings: Strings::default(),
+        languages: locale::languages(loader.assets()),
+        show_boxes: cfg!(feature = "debug"),
+        show_move_list: false,
+        main_menu_texture: game_json
+            .scene_data
+            .main_menu
+            .background
+            .make_texture(loader)?,
+        menu_music: game_json.scene_data.main_menu.music,
+        skin: game_json
+            .scene_data
+            .skin
+            .map(|skin| skin.make_skin(loader))
+            .transpose()?
+            .unwrap_or_default(),
+        round_start_animation: game_json
+            .scene_data
+            .gameplay
+            .round_start_animation
+            .make_animation(loader)?,
+        timer_animation: game_json
+            .scene_data
+            .gameplay
+            .timer_animation
+            .as_ref()
+            .map(|animation| animation.make_animation(loader))
+            .transpose()?,
+        hud: game_json
+            .scene_data
+            .gameplay
+            .hud
+            .map(HudJson::to_hud_layout)
+            .unwrap_or_default(),
+        win_icons: WinIcons {
+            won: game_json
                 .scene_data
                 .gameplay
-                .round_start_animation
-                .make_animation(texture_creator, &mut global_textures)?,
-            stage: Stage::init(texture_creator, &mut global_textures)?,
-            timer_animation: game_json
+                .win_icons
+                .won
+                .make_texture(loader)?,
+            empty: game_json
                 .scene_data
                 .gameplay
-                .timer_animation
-                .make_animation(texture_creator, &mut global_textures)?,
-            player1: player1_context,
-            player2: player2_context,
-            camera: Camera::new(screen_dim),
-        },
-        state: GameState {
-            player1_inputs,
-            player2_inputs,
-            player1: player1_state,
-            player2: player2_state,
+                .win_icons
+                .empty
+                .make_texture(loader)?,
         },
-        scene: Scenes::new(),
-        inputs: PlayerInputs {
-            player1: player1_input_history,
-            player2: player2_input_history,
-        },
-        global_textures,
-        canvas,
-        events,
-        _texture_creator: texture_creator,
-    })
+        stages,
+        stage: 0,
+        player1: player1_context,
+        player2: player2_context,
+        camera: Camera::new(screen_dim),
+    };
+    let state = GameState {
+        player1_inputs,
+        player2_inputs,
+        player1: player1_state,
+        player2: player2_state,
+        clash_sparks: Vec::new(),
+        damage_numbers: Vec::new(),
+        sounds: Vec::new(),
+    };
+    let inputs = PlayerInputs {
+        player1: player1_input_history,
+        player2: player2_input_history,
+        controls: Controls::default(),
+    };
+
+    Ok((context, state, inputs))
 }
 
 #[derive(Deserialize)]
 struct GameJson {
+    /// Read by the migrations before the rest is parsed, missing on configs from before it was added
+    #[allow(dead_code)]
+    #[serde(default)]
+    schema_version: usize,
     scene_data: SceneDataJson,
     ai: AiDataJson,
 }
@@ -100,27 +282,128 @@ struct GameJson {
 struct SceneDataJson {
     main_menu: MainMenuDataJson,
     gameplay: GameplayDataJson,
+    /// Defaults to the plain shapes when left out
+    skin: Option<SkinJson>,
+}
+
+#[derive(Deserialize)]
+struct SkinJson {
+    menu_cursor: Option<TextureJson>,
+    health_bar_frame: Option<TextureJson>,
+    timer: Option<TextureJson>,
+}
+
+impl SkinJson {
+    fn make_skin(&self, loader: &mut dyn TextureLoader) -> Result<UiSkin, String> {
+        let mut make_texture = |texture: &Option<TextureJson>| {
+            texture
+                .as_ref()
+                .map(|texture| texture.make_texture(loader))
+                .transpose()
+        };
+        Ok(UiSkin {
+            menu_cursor: make_texture(&self.menu_cursor)?,
+            health_bar_frame: make_texture(&self.health_bar_frame)?,
+            timer: make_texture(&self.timer)?,
+        })
+    }
 }
 
 #[derive(Deserialize)]
 struct MainMenuDataJson {
     background: TextureJson,
+    /// Loops through every menu
+    music: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct AiDataJson {
     left_agent_path: String,
     right_agent_path: String,
+    /// Character name -> model path, overrides the left/right paths for that character
+    #[serde(default)]
+    character_models: HashMap<String, String>,
+    distilled_agent_path: Option<String>,
+    /// Frames each agent action is held for, defaults to picking every frame
+    action_repeat: Option<usize>,
+    /// Where training drops its ladder checkpoints
+    ladder_dir: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct GameplayDataJson {
-    matchmaking_server: String,
+    matchmaking: MatchmakingJson,
     round_start_animation: AnimationJson,
-    timer_animation: AnimationJson,
+    /// One frame per second of the round, drawn in place of the text digits when given
+    timer_animation: Option<AnimationJson>,
+    /// Defaults to the built in layout when left out
+    hud: Option<HudJson>,
+    win_icons: WinIconsJson,
+    /// Stage configs selectable in stage select, the first is used when there's no pick
+    stages: Vec<String>,
+    /// Character configs selectable in character select, defaults to the configured players
+    roster: Option<Vec<String>>,
     players: PlayersDataJson,
 }
 
+/// Matchmaking servers by environment name, like "dev", "staging" and "production"
+#[derive(Deserialize)]
+struct MatchmakingJson {
+    /// Environment played on unless the command line picks another
+    environment: String,
+    environments: HashMap<String, EndpointJson>,
+}
+
+#[derive(Deserialize)]
+struct EndpointJson {
+    host: String,
+    port: u16,
+}
+
+impl EndpointJson {
+    fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// Positions and sizes are shares of the screen, player2's side mirrors player1's
+#[derive(Deserialize, Clone, Copy)]
+struct HudJson {
+    /// Player1's health bar from its top left corner
+    health_bar: RectJson,
+    /// The timer is centered on `x`, `size` is its font pixel size
+    timer: HudItemJson,
+    /// Player1's win icons end at `x`, `size` is an icon's side
+    score: HudItemJson,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct HudItemJson {
+    x: f32,
+    y: f32,
+    /// Share of the screen height
+    size: f32,
+}
+
+impl HudJson {
+    fn to_hud_layout(self) -> HudLayout {
+        let bar = self.health_bar;
+        HudLayout {
+            health_bar: FRect::new(bar.x, bar.y, bar.w, bar.h),
+            timer: FPoint::new(self.timer.x, self.timer.y),
+            timer_size: self.timer.size,
+            score: FPoint::new(self.score.x, self.score.y),
+            score_size: self.score.size,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct WinIconsJson {
+    won: TextureJson,
+    empty: TextureJson,
+}
+
 #[derive(Deserialize)]
 struct PlayersDataJson {
     player1: PlayerJson,
@@ -128,8 +411,14 @@ struct PlayersDataJson {
 }
 
 #[derive(Deserialize)]
-pub struct PlayerJson {
-    pub config: String,
-    pub start_pos: FPointJson,
-    pub start_side: SideJson,
+struct PlayerJson {
+    config: String,
+    start_pos: FPointJson,
+    start_side: SideJson,
+}
+
+impl PlayerJson {
+    fn start(&self) -> (Side, FPoint) {
+        (self.start_side.to_side(), self.start_pos.to_fpoint())
+    }
 }
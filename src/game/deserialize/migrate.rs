@@ -0,0 +1,178 @@
+use serde_json::{Map, Value, json};
+
+/// Upgrades a config from the version at its index to the next
+pub type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// Configs from before `schema_version` was added count as version 0
+pub const CHARACTER_MIGRATIONS: &[Migration] = &[split_cancel_options];
+pub const GAME_MIGRATIONS: &[Migration] = &[add_stages_and_win_icons, split_matchmaking_server];
+
+/// Stage older game configs played on, it was built in before stages moved to their own configs
+const LEGACY_STAGE: &str = "./resources/stage1/forest.json";
+const LEGACY_WIN_ICON: &str = "./resources/scenes/win_icon.png";
+const LEGACY_WIN_ICON_EMPTY: &str = "./resources/scenes/win_icon_empty.png";
+/// Environment the single matchmaking server of older game configs becomes
+const LEGACY_MATCHMAKING_ENVIRONMENT: &str = "production";
+
+/// Brings `json` up to the latest version, which is the number of migrations, Ok with the version it was on
+pub fn migrate(json: &mut Value, migrations: &[Migration]) -> Result<usize, String> {
+    let object = json
+        .as_object_mut()
+        .ok_or_else(|| String::from("expected an object at the top"))?;
+    let version = match object.get("schema_version") {
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| format!("schema_version: expected a number, found {version}"))?
+            as usize,
+        None => 0,
+    };
+    if version > migrations.len() {
+        return Err(format!(
+            "schema_version {version} is newer than this build reads, {} at most",
+            migrations.len()
+        ));
+    }
+    for migration in &migrations[version..] {
+        migration(object)?;
+    }
+    object.insert(String::from("schema_version"), migrations.len().into());
+    Ok(version)
+}
+
+/// Cancel options used to be one list taken whatever the attack did
+fn split_cancel_options(character: &mut Map<String, Value>) -> Result<(), String> {
+    let Some(Value::Array(moves)) = character.get_mut("moves") else {
+        return Ok(());
+    };
+    for mov in moves.iter_mut().filter_map(Value::as_object_mut) {
+        if let Some(Value::Array(options)) = mov.get("cancel_options") {
+            let options = options.clone();
+            mov.insert(
+                String::from("cancel_options"),
+                json!({
+                    "on_hit": options,
+                    "on_block": options,
+                    "on_whiff": options,
+                }),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Stages and win icons became required once they moved out of the code
+fn add_stages_and_win_icons(game: &mut Map<String, Value>) -> Result<(), String> {
+    let gameplay = game
+        .get_mut("scene_data")
+        .and_then(|scene_data| scene_data.get_mut("gameplay"))
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| String::from("scene_data.gameplay: missing"))?;
+    gameplay
+        .entry("stages")
+        .or_insert_with(|| json!([LEGACY_STAGE]));
+    gameplay.entry("win_icons").or_insert_with(|| {
+        json!({
+            "won": { "texture_path": LEGACY_WIN_ICON },
+            "empty": { "texture_path": LEGACY_WIN_ICON_EMPTY },
+        })
+    });
+    Ok(())
+}
+
+/// The one matchmaking server became one per environment, with the host and port apart
+fn split_matchmaking_server(game: &mut Map<String, Value>) -> Result<(), String> {
+    let gameplay = game
+        .get_mut("scene_data")
+        .and_then(|scene_data| scene_data.get_mut("gameplay"))
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| String::from("scene_data.gameplay: missing"))?;
+    let Some(server) = gameplay.remove("matchmaking_server") else {
+        return Ok(());
+    };
+    let (host, port) = server
+        .as_str()
+        .and_then(|server| server.rsplit_once(':'))
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+        .ok_or_else(|| {
+            format!(
+                "scene_data.gameplay.matchmaking_server: expected \"host:port\", found {server}"
+            )
+        })?;
+    let matchmaking = json!({
+        "environment": LEGACY_MATCHMAKING_ENVIRONMENT,
+        "environments": {
+            LEGACY_MATCHMAKING_ENVIRONMENT: { "host": host, "port": port },
+        },
+    });
+    gameplay.insert(String::from("matchmaking"), matchmaking);
+    Ok(())
+}
+
+#[test]
+fn test_migrate_splits_cancel_options() {
+    let mut character = json!({ "moves": [{ "name": "jab", "cancel_options": ["kick"] }] });
+
+    assert_eq!(migrate(&mut character, CHARACTER_MIGRATIONS), Ok(0));
+    assert_eq!(
+        character["schema_version"],
+        json!(CHARACTER_MIGRATIONS.len())
+    );
+    assert_eq!(
+        character["moves"][0]["cancel_options"],
+        json!({ "on_hit": ["kick"], "on_block": ["kick"], "on_whiff": ["kick"] })
+    );
+}
+
+#[test]
+fn test_migrate_game_from_first_version() {
+    let mut game = json!({
+        "scene_data": { "gameplay": { "matchmaking_server": "127.0.0.1:8000" } }
+    });
+
+    assert_eq!(migrate(&mut game, GAME_MIGRATIONS), Ok(0));
+    let gameplay = &game["scene_data"]["gameplay"];
+    assert_eq!(gameplay["stages"], json!([LEGACY_STAGE]));
+    assert_eq!(
+        gameplay["win_icons"]["won"]["texture_path"],
+        json!(LEGACY_WIN_ICON)
+    );
+    assert_eq!(gameplay.get("matchmaking_server"), None);
+    assert_eq!(
+        gameplay["matchmaking"],
+        json!({
+            "environment": LEGACY_MATCHMAKING_ENVIRONMENT,
+            "environments": {
+                LEGACY_MATCHMAKING_ENVIRONMENT: { "host": "127.0.0.1", "port": 8000 },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_migrate_only_runs_newer_migrations() {
+    // Version 1 already has its stages, only the matchmaking server is split
+    let mut game = json!({
+        "schema_version": 1,
+        "scene_data": { "gameplay": { "matchmaking_server": "example.com:9000" } }
+    });
+
+    assert_eq!(migrate(&mut game, GAME_MIGRATIONS), Ok(1));
+    let gameplay = &game["scene_data"]["gameplay"];
+    assert_eq!(gameplay.get("stages"), None);
+    assert_eq!(
+        gameplay["matchmaking"]["environments"][LEGACY_MATCHMAKING_ENVIRONMENT]["port"],
+        json!(9000)
+    );
+}
+
+#[test]
+fn test_migrate_rejects_bad_configs() {
+    let mut newer = json!({ "schema_version": GAME_MIGRATIONS.len() + 1 });
+    assert!(migrate(&mut newer, GAME_MIGRATIONS).is_err());
+
+    let mut portless = json!({
+        "schema_version": 1,
+        "scene_data": { "gameplay": { "matchmaking_server": "localhost" } }
+    });
+    assert!(migrate(&mut portless, GAME_MIGRATIONS).is_err());
+}
@@ -0,0 +1,158 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::game::{
+    assets::AssetSource,
+    deserialize::parse_json,
+    render::{TextureLoader, TextureRegion, Tint, animation::AnimationLayout},
+};
+
+/// Directory scanned at startup for addon packs, one subdirectory per pack
+const MODS_DIR: &str = "./mods";
+/// Lists a pack's configs, sits at the top of its directory
+const MANIFEST: &str = "pack.json";
+
+#[derive(Default)]
+pub struct Packs {
+    pub characters: Vec<String>,
+    pub stages: Vec<String>,
+}
+
+/// Character and stage configs of every pack in `MODS_DIR`, broken packs are skipped
+pub fn scan() -> Packs {
+    let mut packs = Packs::default();
+    // Most installs have no mods at all
+    let Ok(entries) = std::fs::read_dir(MODS_DIR) else {
+        return packs;
+    };
+    let mut dirs: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    // Keeps the roster in the same order between runs
+    dirs.sort();
+    for dir in dirs {
+        match read_pack(&dir) {
+            Ok(pack) => {
+                packs.characters.extend(pack.characters);
+                packs.stages.extend(pack.stages);
+            }
+            Err(err) => {
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] Skipping pack '{}': {err}", dir.display());
+                }
+            }
+        }
+    }
+    packs
+}
+
+fn read_pack(dir: &Path) -> Result<Packs, String> {
+    let manifest = dir.join(MANIFEST);
+    let manifest = manifest.to_string_lossy();
+    let src = std::fs::read_to_string(manifest.as_ref())
+        .map_err(|err| format!("Failed to open: '{manifest}': {err}"))?;
+    let pack_json: PackJson = parse_json(&manifest, &src)?;
+    let paths = PackPaths {
+        root: Some(dir.to_path_buf()),
+    };
+    let resolve = |configs: &[String]| {
+        configs
+            .iter()
+            .map(|config| paths.resolve(config))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    Ok(Packs {
+        characters: resolve(&pack_json.characters)?,
+        stages: resolve(&pack_json.stages)?,
+    })
+}
+
+#[derive(Deserialize)]
+struct PackJson {
+    /// Relative to the pack like every path in its configs
+    #[serde(default)]
+    characters: Vec<String>,
+    #[serde(default)]
+    stages: Vec<String>,
+}
+
+/// Paths in a pack's configs are relative to the pack and can't leave it, so packs can't collide
+///
+/// Configs outside `MODS_DIR` keep their paths as written
+#[derive(Clone, Default)]
+pub struct PackPaths {
+    root: Option<PathBuf>,
+}
+
+impl PackPaths {
+    /// The pack `config` belongs to, if any
+    pub fn of(config: &str) -> Self {
+        let root = Path::new(config)
+            .strip_prefix(MODS_DIR)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .map(|pack| Path::new(MODS_DIR).join(pack));
+        Self { root }
+    }
+
+    pub fn resolve(&self, path: &str) -> Result<String, String> {
+        let Some(root) = &self.root else {
+            return Ok(path.to_string());
+        };
+        let inside = Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !inside {
+            return Err(format!(
+                "'{path}' reaches outside its pack '{}'",
+                root.display()
+            ));
+        }
+        Ok(root.join(path).to_string_lossy().into_owned())
+    }
+}
+
+/// Resolves the paths it loads inside the config's pack first
+pub struct PackLoader<'a> {
+    loader: &'a mut dyn TextureLoader,
+    paths: &'a PackPaths,
+}
+
+impl<'a> PackLoader<'a> {
+    pub fn new(loader: &'a mut dyn TextureLoader, paths: &'a PackPaths) -> Self {
+        Self { loader, paths }
+    }
+}
+
+impl TextureLoader for PackLoader<'_> {
+    fn assets(&self) -> &dyn AssetSource {
+        self.loader.assets()
+    }
+
+    fn set_tint(&mut self, tint: Option<Tint>) {
+        self.loader.set_tint(tint);
+    }
+
+    fn load_texture(&mut self, file_path: &str) -> Result<TextureRegion, String> {
+        self.loader.load_texture(&self.paths.resolve(file_path)?)
+    }
+
+    fn load_animation(
+        &mut self,
+        file_path: &str,
+        width: u32,
+        height: u32,
+        frames: u32,
+        layout: AnimationLayout,
+    ) -> Result<Vec<TextureRegion>, String> {
+        self.loader.load_animation(
+            &self.paths.resolve(file_path)?,
+            width,
+            height,
+            frames,
+            layout,
+        )
+    }
+}
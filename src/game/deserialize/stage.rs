@@ -0,0 +1,74 @@
+use serde::Deserialize;
+
+use crate::game::{
+    deserialize::{
+        TextureJson,
+        pack::{PackLoader, PackPaths},
+        parse_json,
+    },
+    render::TextureLoader,
+    stage::{DEFAULT_CORNER_DISTANCE, Layer, Stage},
+};
+
+pub fn deserialize(loader: &mut dyn TextureLoader, config: &str) -> Result<Stage, String> {
+    let src = loader
+        .assets()
+        .read_to_string(config)
+        .map_err(|err| format!("Failed to open: '{config}': {err}"))?;
+    let stage_json: StageJson = parse_json(config, &src)?;
+    let pack = PackPaths::of(config);
+    let loader = &mut PackLoader::new(loader, &pack);
+
+    let layers = stage_json
+        .layers
+        .iter()
+        .map(|layer| {
+            Ok(Layer {
+                region: layer.texture.make_texture(loader)?,
+                parallax: layer.parallax,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Stage::new(
+        stage_json.name,
+        layers,
+        stage_json.width,
+        stage_json.height,
+        stage_json.floor_height,
+        stage_json
+            .music
+            .map(|music| pack.resolve(&music))
+            .transpose()?,
+    )
+    .with_corner_distance(
+        stage_json
+            .corner_distance
+            .unwrap_or(DEFAULT_CORNER_DISTANCE),
+    ))
+}
+
+#[derive(Deserialize)]
+struct StageJson {
+    name: String,
+    /// Back to front
+    layers: Vec<LayerJson>,
+    /// Distance from the center to either wall
+    width: f32,
+    height: f32,
+    #[serde(default)]
+    floor_height: f32,
+    /// Track played during rounds on this stage
+    music: Option<String>,
+    /// Distance from a wall that counts as cornered, block push back goes to the attacker there
+    corner_distance: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct LayerJson {
+    #[serde(flatten)]
+    texture: TextureJson,
+    /// Share of the image panned across wall to wall, 0 keeps the layer still
+    #[serde(default)]
+    parallax: f32,
+}
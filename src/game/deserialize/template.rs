@@ -0,0 +1,203 @@
+use std::path::Path;
+
+use image::{Rgba, RgbaImage};
+use serde_json::{Value, json};
+
+use crate::game::deserialize::{character, migrate::CHARACTER_MIGRATIONS};
+
+/// New characters get a directory of their own in here
+const CHARACTERS_DIR: &str = "./resources";
+/// Width and height of the placeholder sprite, and of every animation frame using it
+const SPRITE_SIZE: u32 = 200;
+
+/// Writes a starter character, a config with the basic moves and a placeholder sprite, to
+/// `./resources/<name>/`, returns the config's path
+///
+/// The config is loaded back before returning, so what it hands back is known to work
+pub fn create(name: &str) -> Result<String, String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "'{name}' can only use letters, digits, '_' and '-'"
+        ));
+    }
+    let dir = Path::new(CHARACTERS_DIR).join(name);
+    if dir.exists() {
+        return Err(format!("'{}' already exists", dir.display()));
+    }
+    std::fs::create_dir_all(&dir)
+        .map_err(|err| format!("Failed to create '{}': {err}", dir.display()))?;
+
+    let sprite = format!("{CHARACTERS_DIR}/{name}/placeholder.png");
+    placeholder_sprite()
+        .save(&sprite)
+        .map_err(|err| format!("Failed to write '{sprite}': {err}"))?;
+
+    let config = format!("{CHARACTERS_DIR}/{name}/config.json");
+    let src = serde_json::to_string_pretty(&template(name, &sprite))
+        .map_err(|err| format!("Failed to write '{config}': {err}"))?;
+    std::fs::write(&config, src).map_err(|err| format!("Failed to write '{config}': {err}"))?;
+
+    let warnings = character::check(&config)?;
+    if !warnings.is_empty() {
+        return Err(format!("'{config}' has warnings:\n{}", warnings.join("\n")));
+    }
+    Ok(config)
+}
+
+/// A grey block standing in the middle of the frame
+fn placeholder_sprite() -> RgbaImage {
+    let body = SPRITE_SIZE / 3..SPRITE_SIZE * 2 / 3;
+    let height = SPRITE_SIZE / 4..SPRITE_SIZE;
+    RgbaImage::from_fn(SPRITE_SIZE, SPRITE_SIZE, |x, y| {
+        if body.contains(&x) && height.contains(&y) {
+            Rgba([160, 160, 160, 255])
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+fn template(name: &str, sprite: &str) -> Value {
+    let animation = json!({
+        "texture_path": sprite,
+        "layout": { "type": "Horz" },
+        "frames": 1,
+        "w": SPRITE_SIZE,
+        "h": SPRITE_SIZE
+    });
+    let body = json!([{ "frame": 0, "boxes": [{ "rect": { "x": 0, "y": 0, "w": 40, "h": 40 } }] }]);
+    let collision_box = json!({ "rect": { "x": 0, "y": 0, "w": 40, "h": 40 } });
+    let neutral_input =
+        json!({ "Direction": { "dir": { "type": "Neutral" }, "button": { "type": "None" } } });
+    let no_cancels = json!({ "start": null, "end": null });
+    let state = |name: &str, start_behavior: Value, flags: Value, end_behavior: Value| {
+        json!({
+            "name": name,
+            "input": neutral_input,
+            "hit_boxes": [],
+            "hurt_boxes": body,
+            "collision_box": collision_box,
+            "start_behavior": start_behavior,
+            "flags": flags,
+            "end_behavior": end_behavior,
+            "cancel_window": no_cancels,
+            "cancel_options": {},
+            "animation": animation
+        })
+    };
+    let movement = |name: &str, dir: &str, start_behavior: Value, cancels: &[&str]| {
+        json!({
+            "name": name,
+            "input": { "Direction": { "dir": { "type": dir }, "button": { "type": "None" } } },
+            "hit_boxes": [],
+            "hurt_boxes": body,
+            "collision_box": collision_box,
+            "start_behavior": start_behavior,
+            "flags": [],
+            "end_behavior": { "type": "Endless" },
+            "cancel_window": { "start": 0, "end": null },
+            "cancel_options": { "on_whiff": cancels },
+            "animation": animation
+        })
+    };
+
+    let neutral = movement(
+        "neutral",
+        "Neutral",
+        json!({ "type": "SetVel", "x": 0, "y": 0 }),
+        &["light_attack", "jump", "back_walk", "forward_walk"],
+    );
+    let forward_walk = movement(
+        "forward_walk",
+        "Forward",
+        json!({ "type": "Walk" }),
+        &["light_attack", "jump", "back_walk", "neutral"],
+    );
+    let back_walk = movement(
+        "back_walk",
+        "Back",
+        json!({ "type": "BackWalk" }),
+        &["light_attack", "jump", "forward_walk", "neutral"],
+    );
+    let mut jump = state(
+        "jump",
+        json!({ "type": "Jump" }),
+        json!([{ "type": "Airborne" }, { "type": "LockSide" }]),
+        json!({ "type": "OnGroundedToStateY", "y": "neutral" }),
+    );
+    jump["input"] =
+        json!({ "Direction": { "dir": { "type": "Up" }, "button": { "type": "None" } } });
+    let light_attack = json!({
+        "name": "light_attack",
+        "input": { "Direction": { "dir": { "type": "Any" }, "button": { "type": "L" } } },
+        "hit_boxes": [
+            { "frame": 0, "boxes": [] },
+            { "frame": 6, "boxes": [{
+                "rect": { "x": 50, "y": 20, "w": 80, "h": 40 },
+                "dmg": 30,
+                "block_stun": 8,
+                "hit_stun": 18,
+                "cancel_window": 10,
+                "block_type": { "type": "Mid" },
+                "strength": { "type": "Light" }
+            }] },
+            { "frame": 9, "boxes": [] }
+        ],
+        "hurt_boxes": body,
+        "collision_box": collision_box,
+        "start_behavior": { "type": "SetVel", "x": 0, "y": 0 },
+        "flags": [{ "type": "LockSide" }],
+        "end_behavior": { "type": "OnFrameXToStateY", "x": 16, "y": "neutral" },
+        "cancel_window": { "start": 6, "end": 12 },
+        "cancel_options": { "on_hit": ["light_attack"], "on_block": ["light_attack"] },
+        "animation": animation
+    });
+    let hit_stun = state(
+        "hit_stun",
+        json!({ "type": "AddFrictionVel", "x": -3, "y": 0 }),
+        json!([]),
+        json!({ "type": "OnStunEndToStateY", "y": "neutral" }),
+    );
+    let block_stun = state(
+        "block_stun",
+        json!({ "type": "AddFrictionVel", "x": -1, "y": 0 }),
+        json!([{ "type": "HighBlock" }]),
+        json!({ "type": "OnStunEndToStateY", "y": "neutral" }),
+    );
+    let launched = state(
+        "launched",
+        json!({ "type": "SetVel", "x": -2, "y": 10 }),
+        json!([{ "type": "Airborne" }]),
+        json!({ "type": "OnGroundedToStateY", "y": "recovery" }),
+    );
+    let recovery = state(
+        "recovery",
+        json!({ "type": "SetVel", "x": 0, "y": 0 }),
+        json!([{ "type": "Airborne" }]),
+        json!({ "type": "OnFrameXToStateY", "x": 30, "y": "neutral" }),
+    );
+
+    json!({
+        "schema_version": CHARACTER_MIGRATIONS.len(),
+        "name": name,
+        "hp": 1000,
+        "block_stun_state": "block_stun",
+        "ground_hit_state": "hit_stun",
+        "launch_hit_state": "launched",
+        "moves": [
+            neutral,
+            forward_walk,
+            back_walk,
+            jump,
+            light_attack,
+            hit_stun,
+            block_stun,
+            launched,
+            recovery
+        ]
+    })
+}
@@ -0,0 +1,111 @@
+use sdl3::{render::ScaleMode, video::Window};
+use serde::{Deserialize, Serialize};
+
+use crate::{DEFAULT_SCREEN_HEIGHT, DEFAULT_SCREEN_WIDTH};
+
+/// Window sizes offered by the display settings, all the default aspect ratio
+pub const RESOLUTIONS: [(u32, u32); 4] = [
+    (DEFAULT_SCREEN_WIDTH, DEFAULT_SCREEN_HEIGHT),
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+];
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum DisplayMode {
+    Windowed,
+    /// Window without decorations, sized like a windowed one
+    Borderless,
+    /// Covers the whole display, the resolution is only used once back in a window
+    Fullscreen,
+}
+
+pub const DISPLAY_MODES: [DisplayMode; 3] = [
+    DisplayMode::Windowed,
+    DisplayMode::Borderless,
+    DisplayMode::Fullscreen,
+];
+
+impl DisplayMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Windowed => "Windowed",
+            Self::Borderless => "Borderless",
+            Self::Fullscreen => "Fullscreen",
+        }
+    }
+}
+
+/// How textures are sampled when drawn bigger or smaller than they are
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum ScalingFilter {
+    /// Blocky, keeps pixel art sharp
+    Nearest,
+    #[default]
+    Linear,
+}
+
+pub const SCALING_FILTERS: [ScalingFilter; 2] = [ScalingFilter::Nearest, ScalingFilter::Linear];
+
+impl ScalingFilter {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Nearest => "Nearest",
+            Self::Linear => "Linear",
+        }
+    }
+
+    pub fn scale_mode(self) -> ScaleMode {
+        match self {
+            Self::Nearest => ScaleMode::Nearest,
+            Self::Linear => ScaleMode::Linear,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    pub mode: DisplayMode,
+    pub resolution: (u32, u32),
+    /// Paces the main loop on presenting instead of sleeping
+    #[serde(default)]
+    pub vsync: bool,
+    #[serde(default)]
+    pub filter: ScalingFilter,
+    /// Only scales the game by whole numbers, the spare window is letterboxed
+    #[serde(default)]
+    pub pixel_perfect: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::Windowed,
+            resolution: RESOLUTIONS[0],
+            vsync: false,
+            filter: ScalingFilter::default(),
+            pixel_perfect: false,
+        }
+    }
+}
+
+impl DisplaySettings {
+    /// Reconfigures the window, the camera still has to be resized to the new window size
+    pub fn apply(&self, window: &mut Window) -> Result<(), String> {
+        let (w, h) = self.resolution;
+        match self.mode {
+            DisplayMode::Windowed | DisplayMode::Borderless => {
+                window
+                    .set_fullscreen(false)
+                    .map_err(|err| err.to_string())?;
+                window.set_bordered(self.mode == DisplayMode::Windowed);
+                window.set_size(w, h).map_err(|err| err.to_string())?;
+            }
+            DisplayMode::Fullscreen => {
+                window.set_size(w, h).map_err(|err| err.to_string())?;
+                window.set_fullscreen(true).map_err(|err| err.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
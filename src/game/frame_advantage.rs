@@ -0,0 +1,78 @@
+use crate::game::{GameContext, GameState};
+
+/// Measures who gets to act first once a hit or block is over, positive means player1 is plus
+#[derive(Default)]
+pub struct FrameAdvantage {
+    last_time: Option<usize>,
+    /// Round frame each player became actionable on, `None` outside of an interaction
+    pending: Option<[Option<usize>; 2]>,
+    advantage: Option<i32>,
+}
+
+impl FrameAdvantage {
+    /// `time` is the round clock, frames where it doesn't move (hit freeze) are skipped
+    pub fn update(&mut self, context: &GameContext, state: &GameState, time: usize) {
+        if self.last_time == Some(time) {
+            return;
+        }
+        self.last_time = Some(time);
+
+        let players = [
+            (
+                state.player1.actionable(&context.player1),
+                state.player1.stunned(&context.player1),
+            ),
+            (
+                state.player2.actionable(&context.player2),
+                state.player2.stunned(&context.player2),
+            ),
+        ];
+        self.record(players, time);
+    }
+
+    /// `players` is whether each player is actionable and whether they are stunned this frame
+    fn record(&mut self, players: [(bool, bool); 2], time: usize) {
+        if self.pending.is_none() && players.iter().any(|(_, stunned)| *stunned) {
+            self.pending = Some([None; 2]);
+        }
+        let Some(pending) = &mut self.pending else {
+            return;
+        };
+
+        for (player, (actionable, _)) in players.into_iter().enumerate() {
+            let other_recovered = pending[1 - player].is_some();
+            match (actionable, pending[player]) {
+                (true, None) => pending[player] = Some(time),
+                // Acting again before the other side recovered restarts the count
+                (false, Some(_)) if !other_recovered => pending[player] = None,
+                _ => {}
+            }
+        }
+
+        if let [Some(player1), Some(player2)] = *pending {
+            self.advantage = Some(player2 as i32 - player1 as i32);
+            self.pending = None;
+        }
+    }
+
+    /// Advantage of the last finished interaction
+    pub fn advantage(&self) -> Option<i32> {
+        self.advantage
+    }
+}
+
+#[test]
+fn test_light_attack_advantage() {
+    // character1's light_attack hits on frame 7 and recovers on frame 17, with 20 hit stun and 8 block stun
+    let advantage = |stun: usize| {
+        let mut frame_advantage = FrameAdvantage::default();
+        for time in 0..40 {
+            let attacker = (time >= 17, false);
+            let stunned = (7..7 + stun).contains(&time);
+            frame_advantage.record([attacker, (!stunned, stunned)], time);
+        }
+        frame_advantage.advantage()
+    };
+    assert_eq!(advantage(20), Some(10));
+    assert_eq!(advantage(8), Some(-2));
+}
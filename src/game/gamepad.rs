@@ -0,0 +1,129 @@
+use sdl3::{
+    GamepadSubsystem,
+    gamepad::{Axis, Button, Gamepad},
+    keyboard::Keycode,
+};
+
+use crate::game::input::{ButtonFlag, DirectionFlag, KeyBindings};
+
+/// Stick travel past which it counts as held in that direction
+const STICK_DEADZONE: i16 = 16_000;
+
+/// Pads press the keys their player has bound, so rebinding and online mapping carry over
+pub struct Gamepads {
+    subsystem: GamepadSubsystem,
+    /// Instance id and handle of the pad playing for player1 and player2
+    slots: [Option<(u32, Gamepad)>; 2],
+    /// Players whose pad was unplugged, the next pad plugged in goes to them
+    disconnected: [bool; 2],
+    /// Directions each player's left stick is held in
+    sticks: [DirectionFlag; 2],
+}
+
+impl Gamepads {
+    pub fn new(subsystem: GamepadSubsystem) -> Self {
+        Self {
+            subsystem,
+            slots: [None, None],
+            disconnected: [false; 2],
+            sticks: [DirectionFlag::Neutral; 2],
+        }
+    }
+
+    /// Opens a plugged in pad for a player who lost theirs, or else the first without one
+    pub fn connect(&mut self, id: u32) -> Option<usize> {
+        if self.player(id).is_some() {
+            return None;
+        }
+        let player = (0..2)
+            .find(|player| self.disconnected[*player])
+            .or_else(|| (0..2).find(|player| self.slots[*player].is_none()))?;
+        match self.subsystem.open(id) {
+            Ok(pad) => {
+                self.slots[player] = Some((id, pad));
+                self.disconnected[player] = false;
+                Some(player)
+            }
+            Err(err) => {
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] Failed to open gamepad {id}: {err}");
+                }
+                None
+            }
+        }
+    }
+
+    /// Frees the unplugged pad's player, they wait on it coming back
+    pub fn disconnect(&mut self, id: u32) -> Option<usize> {
+        let player = self.player(id)?;
+        self.slots[player] = None;
+        self.disconnected[player] = true;
+        self.sticks[player] = DirectionFlag::Neutral;
+        Some(player)
+    }
+
+    /// First player waiting on their pad to come back
+    pub fn disconnected(&self) -> Option<usize> {
+        (0..2).find(|player| self.disconnected[*player])
+    }
+
+    /// Stops waiting on unplugged pads, the players carry on with their keys
+    pub fn forget_disconnected(&mut self) {
+        self.disconnected = [false; 2];
+    }
+
+    /// Player the pad plays for
+    pub fn player(&self, id: u32) -> Option<usize> {
+        (0..2).find(|player| matches!(self.slots[*player], Some((slot_id, _)) if slot_id == id))
+    }
+
+    /// The bound key a pad button stands in for
+    pub fn button_key(&self, button: Button, bindings: &KeyBindings) -> Option<Keycode> {
+        match button {
+            Button::DPadUp => bindings.direction_key(DirectionFlag::Up),
+            Button::DPadDown => bindings.direction_key(DirectionFlag::Down),
+            Button::DPadLeft => bindings.direction_key(DirectionFlag::Left),
+            Button::DPadRight => bindings.direction_key(DirectionFlag::Right),
+            Button::West => bindings.button_key(ButtonFlag::L),
+            Button::North => bindings.button_key(ButtonFlag::M),
+            Button::East => bindings.button_key(ButtonFlag::H),
+            _ => None,
+        }
+    }
+
+    /// Bound direction keys the left stick moved onto, true, or off of, false
+    pub fn stick_keys(
+        &mut self,
+        player: usize,
+        axis: Axis,
+        value: i16,
+        bindings: &KeyBindings,
+    ) -> Vec<(Keycode, bool)> {
+        let (negative, positive) = match axis {
+            Axis::LeftX => (DirectionFlag::Left, DirectionFlag::Right),
+            Axis::LeftY => (DirectionFlag::Up, DirectionFlag::Down),
+            _ => return Vec::new(),
+        };
+        let held = if value <= -STICK_DEADZONE {
+            negative
+        } else if value >= STICK_DEADZONE {
+            positive
+        } else {
+            DirectionFlag::Neutral
+        };
+
+        let stick = &mut self.sticks[player];
+        let mut keys = Vec::new();
+        for dir in [negative, positive] {
+            let was_held = stick.contains(dir);
+            let is_held = held == dir;
+            if was_held != is_held
+                && let Some(key) = bindings.direction_key(dir)
+            {
+                keys.push((key, is_held));
+            }
+        }
+        *stick = (*stick & !(negative | positive)) | held;
+        keys
+    }
+}
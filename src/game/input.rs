@@ -1,6 +1,7 @@
 use bincode::{BorrowDecode, Encode};
 use bitflags::bitflags;
 use sdl3::keyboard::Keycode;
+use serde::{Deserialize, Serialize};
 
 use crate::game::MAX_ROLLBACK_FRAMES;
 
@@ -61,12 +62,9 @@ pub const PLAYER2_DIRECTIONS: KeyToDirections = [
 type MoveBuffer = [(Motion, ButtonFlag); MOTION_BUF_SIZE];
 
 // Returns an input history and state component for a players input
-pub fn new_inputs(
-    key_to_button: KeyToButtons,
-    key_to_direction: KeyToDirections,
-) -> (InputHistory, Inputs) {
+pub fn new_inputs(bindings: KeyBindings) -> (InputHistory, Inputs) {
     let inputs = Inputs::new();
-    let input_history = InputHistory::new(key_to_button, key_to_direction, 0);
+    let input_history = InputHistory::new(bindings.buttons, bindings.directions, 0);
     (input_history, inputs)
 }
 
@@ -108,6 +106,32 @@ impl Inputs {
         self.buf
     }
 
+    /// Plain bytes of every field, for saving to replays
+    pub fn to_raw(&self) -> RawInputs {
+        RawInputs {
+            dir: self.dir.into(),
+            buttons: self.buttons.bits(),
+            just_pressed_buttons: self.just_pressed_buttons.bits(),
+            buf: self
+                .buf
+                .map(|(motion, buttons)| (motion.bits(), buttons.bits())),
+        }
+    }
+
+    pub fn from_raw(raw: &RawInputs) -> Self {
+        Self {
+            dir: Direction::from(raw.dir),
+            buttons: ButtonFlag::from_bits_retain(raw.buttons),
+            just_pressed_buttons: ButtonFlag::from_bits_retain(raw.just_pressed_buttons),
+            buf: raw.buf.map(|(motion, buttons)| {
+                (
+                    Motion::from_bits_retain(motion),
+                    ButtonFlag::from_bits_retain(buttons),
+                )
+            }),
+        }
+    }
+
     pub fn update(
         &mut self,
         held_buttons: ButtonFlag,
@@ -125,8 +149,120 @@ impl Inputs {
     }
 }
 
+/// `Inputs` as plain bytes
+#[derive(Clone, Copy, Debug, BorrowDecode, Encode)]
+pub struct RawInputs {
+    dir: u8,
+    buttons: u8,
+    just_pressed_buttons: u8,
+    buf: [(u32, u8); MOTION_BUF_SIZE],
+}
+
 type KeyToButtons = [(Keycode, ButtonFlag); BUTTON_COUNT * INPUT_VARIANTS];
 type KeyToDirections = [(Keycode, DirectionFlag); DIRECTION_COUNT * INPUT_VARIANTS];
+
+/// A player's keys, saved in the settings as key names with the directions first
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(into = "Vec<String>", try_from = "Vec<String>")]
+pub struct KeyBindings {
+    pub buttons: KeyToButtons,
+    pub directions: KeyToDirections,
+}
+
+pub const PLAYER1_BINDINGS: KeyBindings = KeyBindings {
+    buttons: PLAYER1_BUTTONS,
+    directions: PLAYER1_DIRECTIONS,
+};
+pub const PLAYER2_BINDINGS: KeyBindings = KeyBindings {
+    buttons: PLAYER2_BUTTONS,
+    directions: PLAYER2_DIRECTIONS,
+};
+
+impl KeyBindings {
+    /// Bound keys in `labels` order
+    pub const SLOTS: usize = DIRECTION_COUNT * INPUT_VARIANTS + BUTTON_COUNT * INPUT_VARIANTS;
+
+    pub fn keys(&self) -> impl Iterator<Item = Keycode> {
+        self.directions
+            .iter()
+            .map(|(key, _)| *key)
+            .chain(self.buttons.iter().map(|(key, _)| *key))
+    }
+
+    fn key_mut(&mut self, slot: usize) -> &mut Keycode {
+        let directions = self.directions.len();
+        if slot < directions {
+            &mut self.directions[slot].0
+        } else {
+            &mut self.buttons[slot - directions].0
+        }
+    }
+
+    pub fn button_key(&self, button: ButtonFlag) -> Option<Keycode> {
+        self.buttons
+            .iter()
+            .find_map(|(key, bound)| (*bound == button).then_some(*key))
+    }
+
+    pub fn direction_key(&self, dir: DirectionFlag) -> Option<Keycode> {
+        self.directions
+            .iter()
+            .find_map(|(key, bound)| (*bound == dir).then_some(*key))
+    }
+
+    /// "Up: W" style labels, directions first
+    pub fn labels(&self) -> Vec<String> {
+        let directions = self
+            .directions
+            .iter()
+            .map(|(key, dir)| (key, dir.iter_names().next().map(|(name, _)| name)));
+        let buttons = self
+            .buttons
+            .iter()
+            .map(|(key, button)| (key, button.iter_names().next().map(|(name, _)| name)));
+        directions
+            .chain(buttons)
+            .map(|(key, name)| format!("{}: {}", name.unwrap_or("?"), key.name()))
+            .collect()
+    }
+
+    /// Binds `key` to the slot, a slot already on that key takes the old one so none are shared
+    pub fn rebind(&mut self, slot: usize, key: Keycode) {
+        let old = *self.key_mut(slot);
+        if let Some(taken) = self.keys().position(|bound| bound == key) {
+            *self.key_mut(taken) = old;
+        }
+        *self.key_mut(slot) = key;
+    }
+}
+
+impl From<KeyBindings> for Vec<String> {
+    fn from(bindings: KeyBindings) -> Self {
+        bindings.keys().map(|key| key.name()).collect()
+    }
+}
+
+impl TryFrom<Vec<String>> for KeyBindings {
+    type Error = String;
+
+    fn try_from(names: Vec<String>) -> Result<Self, Self::Error> {
+        if names.len() != Self::SLOTS {
+            return Err(format!(
+                "Expected {} keys, found {}",
+                Self::SLOTS,
+                names.len()
+            ));
+        }
+        // Only the keys come from the names, the order of what they're bound to is fixed
+        let mut bindings = PLAYER1_BINDINGS;
+        for (slot, name) in names.iter().enumerate() {
+            *bindings.key_mut(slot) =
+                Keycode::from_name(name).ok_or_else(|| format!("Unknown key: '{name}'"))?;
+        }
+        Ok(bindings)
+    }
+}
+
 struct InputState {
     active_dir: DirectionFlag,
     release_next_dir: DirectionFlag,
@@ -253,9 +389,9 @@ impl InputHistory {
         self.current_index = 0;
     }
 
-    pub fn set_mappings(&mut self, key_to_button: KeyToButtons, key_to_direction: KeyToDirections) {
-        self.input.key_to_button = key_to_button;
-        self.input.key_to_direction = key_to_direction;
+    pub fn set_mappings(&mut self, bindings: KeyBindings) {
+        self.input.key_to_button = bindings.buttons;
+        self.input.key_to_direction = bindings.directions;
     }
 
     pub fn set_delay(&mut self, delay: usize) {
@@ -578,6 +714,22 @@ pub enum RelativeDirection {
 }
 
 impl RelativeDirection {
+    /// Numpad digit facing right, any direction has none
+    pub fn numpad(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Neutral => "5",
+            Self::Up => "8",
+            Self::Down => "2",
+            Self::Back => "4",
+            Self::Forward => "6",
+            Self::UpBack => "7",
+            Self::DownBack => "1",
+            Self::UpForward => "9",
+            Self::DownForward => "3",
+        }
+    }
+
     /// Returns true if self and other match, or if self is none
     pub fn matches_or_is_none(self, other: Self) -> bool {
         self == other || self == Self::None
@@ -616,6 +768,25 @@ bitflags! {
     }
 }
 
+impl RelativeMotion {
+    /// Numpad digits facing right
+    pub fn numpad(self) -> &'static str {
+        const DIGITS: [(RelativeMotion, &str); 7] = [
+            (RelativeMotion::DownDown, "22"),
+            (RelativeMotion::ForwardForward, "66"),
+            (RelativeMotion::BackBack, "44"),
+            (RelativeMotion::QcForward, "236"),
+            (RelativeMotion::QcBack, "214"),
+            (RelativeMotion::DpForward, "623"),
+            (RelativeMotion::DpBack, "421"),
+        ];
+        DIGITS
+            .iter()
+            .find(|(motion, _)| *motion == self)
+            .map_or("", |(_, digits)| digits)
+    }
+}
+
 impl Motion {
     pub fn on_left_side(self) -> RelativeMotion {
         RelativeMotion::from_bits_retain(self.bits())
@@ -651,3 +822,16 @@ fn test_insert_input() {
         history.buf[1..3]
     );
 }
+
+#[test]
+fn test_rebind_swaps_taken_key() {
+    let mut bindings = PLAYER1_BINDINGS;
+    let keys: Vec<Keycode> = bindings.keys().collect();
+
+    // Slot 1's key moves to slot 0, which hands its old key over
+    bindings.rebind(0, keys[1]);
+
+    let rebound: Vec<Keycode> = bindings.keys().collect();
+    assert_eq!([rebound[0], rebound[1]], [keys[1], keys[0]]);
+    assert_eq!(rebound[2..], keys[2..]);
+}
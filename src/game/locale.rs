@@ -0,0 +1,91 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::game::assets::AssetSource;
+
+/// One `<language>.json` string table per language
+const LANGUAGE_DIR: &str = "./resources/lang";
+
+/// UI text in the picked language, looked up by its English text so untranslated text stays English
+///
+/// A table is a flat JSON object of English text to its translation, "{}" marks where a
+/// number or name goes and must be kept in the translation. The font only has ASCII glyphs
+#[derive(Clone, Default)]
+pub struct Strings {
+    /// Empty for the built in English
+    language: String,
+    table: HashMap<String, String>,
+}
+
+impl Strings {
+    /// Missing or broken tables fall back to English
+    pub fn load(assets: &dyn AssetSource, language: &str) -> Self {
+        if language.is_empty() {
+            return Self::default();
+        }
+        let path = format!("{LANGUAGE_DIR}/{language}.json");
+        let table = assets
+            .read_to_string(&path)
+            .and_then(|src| serde_json::from_str(&src).map_err(|err| err.to_string()))
+            .unwrap_or_else(|err| {
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] Ignoring strings '{path}': {err}");
+                }
+                HashMap::new()
+            });
+        Self {
+            language: language.to_string(),
+            table,
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The translation of `text`, or `text` itself
+    pub fn get<'a>(&'a self, text: &'a str) -> &'a str {
+        self.table.get(text).map_or(text, String::as_str)
+    }
+
+    /// The translation of `text` with each "{}" filled by the next of `args`
+    pub fn fill(&self, text: &str, args: &[&dyn Display]) -> String {
+        let mut args = args.iter();
+        let mut parts = self.get(text).split("{}");
+        let mut filled = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            if let Some(arg) = args.next() {
+                filled += &arg.to_string();
+            }
+            filled += part;
+        }
+        filled
+    }
+}
+
+/// Languages with a string table, the built in English first as ""
+pub fn languages(assets: &dyn AssetSource) -> Vec<String> {
+    let mut languages = vec![String::new()];
+    let mut tables: Vec<String> = assets
+        .list(LANGUAGE_DIR)
+        .into_iter()
+        .filter_map(|name| name.strip_suffix(".json").map(str::to_string))
+        .collect();
+    tables.sort();
+    languages.extend(tables);
+    languages
+}
+
+#[test]
+fn test_fill_falls_back_to_english() {
+    let strings = Strings {
+        language: String::from("test"),
+        table: HashMap::from([(String::from("Round {}"), String::from("Ronda {}"))]),
+    };
+
+    assert_eq!(strings.fill("Round {}", &[&2]), "Ronda 2");
+    assert_eq!(strings.fill("{} wins", &[&"P1"]), "P1 wins");
+    assert_eq!(Strings::default().fill("Round {}", &[&2]), "Round 2");
+    // Missing args leave the spot empty, extra ones are dropped
+    assert_eq!(strings.fill("{} vs {}", &[&"P1"]), "P1 vs ");
+    assert_eq!(strings.fill("Round {}", &[&2, &3]), "Ronda 2");
+}
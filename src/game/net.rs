@@ -7,12 +7,77 @@ use std::net::{SocketAddr, UdpSocket};
 
 use bincode::{BorrowDecode, Encode, config};
 
-use crate::game::{FRAME_RATE, GAME_VERSION};
+use crate::game::{FRAME_RATE, GAME_VERSION, GameContext};
 
 const BUFFER_LEN: usize = 1024;
 const PEER_TIME_OUT: usize = FRAME_RATE * 30;
 const GAME_START_DELAY: usize = FRAME_RATE;
 
+/// The local pick and the lists the peer's picks are looked up in
+///
+/// Picks are traded by character config path and stage name, peers with other packs installed
+/// order their rosters differently
+#[derive(Clone)]
+pub struct Lineup {
+    character: usize,
+    roster: Vec<String>,
+    stages: Vec<String>,
+}
+
+impl Lineup {
+    /// The host fights on its first stage
+    pub fn new(context: &GameContext, character: usize) -> Self {
+        Self {
+            character,
+            roster: context.roster.clone(),
+            stages: context
+                .stages()
+                .iter()
+                .map(|stage| stage.name().to_string())
+                .collect(),
+        }
+    }
+
+    pub fn character(&self) -> usize {
+        self.character
+    }
+
+    fn character_config(&self) -> &str {
+        &self.roster[self.character]
+    }
+
+    fn stage_name(&self) -> &str {
+        &self.stages[0]
+    }
+
+    fn find_character(&self, config: &str) -> std::io::Result<usize> {
+        find(&self.roster, config).ok_or_else(|| mismatch(format!("character '{config}'")))
+    }
+
+    fn find_stage(&self, name: &str) -> std::io::Result<usize> {
+        find(&self.stages, name).ok_or_else(|| mismatch(format!("stage '{name}'")))
+    }
+}
+
+fn find(names: &[String], name: &str) -> Option<usize> {
+    names.iter().position(|other| other == name)
+}
+
+fn mismatch(what: String) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Peer picked {what}, which isn't installed here"),
+    )
+}
+
+/// The peer sent `Mismatch`
+fn peer_mismatch() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "Peer doesn't have the character or stage picked here",
+    )
+}
+
 #[derive(Debug, Encode, BorrowDecode)]
 struct GameMessage<'a> {
     version: &'a [u8],
@@ -34,8 +99,10 @@ impl<'a> GameMessage<'a> {
 enum MessageContent<'a> {
     Syn,
     SynAck,
-    Connect,
-    StartAt(usize),
+    Connect((&'a str, u32, u32)), // Client's character config, input delay, max rollback
+    StartAt((usize, &'a str, &'a str, u32, u32)), // Start frame, host's config, stage, delay, rollback
+    /// The sender doesn't have a pick the other peer made, both give up on the match
+    Mismatch,
     HeartBeat,
     Inputs((u32, &'a [u8])), // Start seq_num, (frame_num as u32, Direction, ButtonFlags) as bytes
     InputsAck(u32),
@@ -1,7 +1,11 @@
 use std::net::{SocketAddr, UdpSocket};
 
-use crate::game::net::{
-    BUFFER_LEN, GameMessage, MessageContent, PEER_TIME_OUT, recv_msg, send_msg, stream::UdpStream,
+use crate::game::{
+    net::{
+        BUFFER_LEN, GameMessage, Lineup, MessageContent, PEER_TIME_OUT, peer_mismatch, recv_msg,
+        send_msg, stream::UdpStream,
+    },
+    settings::NetplaySettings,
 };
 
 enum UdpClientState {
@@ -17,19 +21,55 @@ pub struct UdpClient {
     state: UdpClientState,
     recv_buf: [u8; BUFFER_LEN],
     send_buf: [u8; BUFFER_LEN],
+    lineup: Lineup,
+    peer_character: usize,
+    /// The host's stage, looked up in the local stages
+    stage: usize,
+    netplay: NetplaySettings,
+    /// The host's preferences, sent along with the start frame
+    peer_netplay: NetplaySettings,
 }
 
 impl UdpClient {
-    pub fn new(connection: UdpSocket, peer_addr: SocketAddr) -> Self {
+    pub fn new(connection: UdpSocket, peer_addr: SocketAddr, lineup: Lineup) -> Self {
         Self {
             socket: connection,
             target_addr: peer_addr,
             state: UdpClientState::Syncing,
             recv_buf: [0; BUFFER_LEN],
             send_buf: [0; BUFFER_LEN],
+            lineup,
+            peer_character: 0,
+            stage: 0,
+            netplay: NetplaySettings::default(),
+            peer_netplay: NetplaySettings::default(),
         }
     }
 
+    /// Preferences sent to the host with the roster pick
+    pub fn with_netplay(mut self, netplay: NetplaySettings) -> Self {
+        self.netplay = netplay;
+        self
+    }
+
+    /// What both peers settled on
+    pub fn netplay(&self) -> NetplaySettings {
+        self.netplay.agree(self.peer_netplay)
+    }
+
+    pub fn local_character(&self) -> usize {
+        self.lineup.character()
+    }
+
+    /// Roster pick the host sent along with the start frame
+    pub fn peer_character(&self) -> usize {
+        self.peer_character
+    }
+
+    pub fn stage(&self) -> usize {
+        self.stage
+    }
+
     pub fn abort(&mut self, current_frame: usize) -> std::io::Result<()> {
         self.send_msg(current_frame, MessageContent::Abort)?;
         Ok(())
@@ -63,7 +103,18 @@ impl UdpClient {
 
         while let Some(msg) = self.recv_msg() {
             if let MessageContent::SynAck = msg.content {
-                self.send_msg(current_frame, MessageContent::Connect)?;
+                let connect = MessageContent::Connect((
+                    self.lineup.character_config(),
+                    self.netplay.input_delay as u32,
+                    self.netplay.max_rollback as u32,
+                ));
+                send_msg(
+                    &self.socket,
+                    &mut self.send_buf,
+                    self.target_addr,
+                    current_frame,
+                    connect,
+                )?;
                 let time_out = current_frame + PEER_TIME_OUT;
                 return Ok(Some(UdpClientState::Connecting(time_out)));
             }
@@ -79,10 +130,37 @@ impl UdpClient {
     ) -> std::io::Result<Option<UdpClientState>> {
         while let Some(msg) = self.recv_msg() {
             match msg.content {
-                MessageContent::StartAt(start_timer) => {
+                MessageContent::StartAt((
+                    start_timer,
+                    peer_character,
+                    stage,
+                    input_delay,
+                    max_rollback,
+                )) => {
+                    let (peer_character, stage) = (peer_character.to_string(), stage.to_string());
+                    self.peer_netplay = NetplaySettings {
+                        input_delay: input_delay as usize,
+                        max_rollback: max_rollback as usize,
+                    };
+                    let picks = self
+                        .lineup
+                        .find_character(&peer_character)
+                        .and_then(|character| Ok((character, self.lineup.find_stage(&stage)?)));
+                    let (peer_character, stage) = match picks {
+                        Ok(picks) => picks,
+                        Err(err) => {
+                            self.send_msg(current_frame, MessageContent::Mismatch)?;
+                            return Err(err);
+                        }
+                    };
+                    self.peer_character = peer_character;
+                    self.stage = stage;
                     return Ok(Some(UdpClientState::WaitingToStart(start_timer)));
                 }
                 MessageContent::Abort => return Ok(Some(UdpClientState::Syncing)),
+                MessageContent::Mismatch => {
+                    return Err(peer_mismatch());
+                }
                 _ => {}
             }
         }
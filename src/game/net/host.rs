@@ -1,8 +1,11 @@
 use std::net::{SocketAddr, UdpSocket};
 
-use crate::game::net::{
-    BUFFER_LEN, GAME_START_DELAY, GameMessage, MessageContent, PEER_TIME_OUT, recv_msg, send_msg,
-    stream::UdpStream,
+use crate::game::{
+    net::{
+        BUFFER_LEN, GAME_START_DELAY, GameMessage, Lineup, MessageContent, PEER_TIME_OUT,
+        peer_mismatch, recv_msg, send_msg, stream::UdpStream,
+    },
+    settings::NetplaySettings,
 };
 
 enum UdpHostState {
@@ -18,19 +21,53 @@ pub struct UdpHost {
     state: UdpHostState,
     recv_buf: [u8; BUFFER_LEN],
     send_buf: [u8; BUFFER_LEN],
+    lineup: Lineup,
+    peer_character: usize,
+    netplay: NetplaySettings,
+    /// The client's preferences, sent along with its roster pick
+    peer_netplay: NetplaySettings,
 }
 
 impl UdpHost {
-    pub fn new(connection: UdpSocket, peer_addr: SocketAddr) -> Self {
+    pub fn new(connection: UdpSocket, peer_addr: SocketAddr, lineup: Lineup) -> Self {
         Self {
             socket: connection,
             client_addr: peer_addr,
             state: UdpHostState::Listening,
             recv_buf: [0; BUFFER_LEN],
             send_buf: [0; BUFFER_LEN],
+            lineup,
+            peer_character: 0,
+            netplay: NetplaySettings::default(),
+            peer_netplay: NetplaySettings::default(),
         }
     }
 
+    /// Preferences sent to the client with the start frame
+    pub fn with_netplay(mut self, netplay: NetplaySettings) -> Self {
+        self.netplay = netplay;
+        self
+    }
+
+    /// What both peers settled on
+    pub fn netplay(&self) -> NetplaySettings {
+        self.netplay.agree(self.peer_netplay)
+    }
+
+    pub fn local_character(&self) -> usize {
+        self.lineup.character()
+    }
+
+    /// Roster pick the client sent while connecting
+    pub fn peer_character(&self) -> usize {
+        self.peer_character
+    }
+
+    /// The host picks the stage, the client looks it up by name
+    pub fn stage(&self) -> usize {
+        0
+    }
+
     pub fn abort(&mut self, current_frame: usize) -> std::io::Result<()> {
         match self.state {
             UdpHostState::Connected | UdpHostState::Connecting(_) | UdpHostState::Syncing(_) => {
@@ -88,11 +125,35 @@ impl UdpHost {
     ) -> std::io::Result<Option<UdpHostState>> {
         while let Some(msg) = self.recv_msg() {
             match msg.content {
-                MessageContent::Connect => {
+                MessageContent::Connect((peer_character, input_delay, max_rollback)) => {
+                    let peer_character = peer_character.to_string();
+                    self.peer_character = match self.lineup.find_character(&peer_character) {
+                        Ok(peer_character) => peer_character,
+                        Err(err) => {
+                            self.send_msg(current_frame, MessageContent::Mismatch)?;
+                            return Err(err);
+                        }
+                    };
+                    self.peer_netplay = NetplaySettings {
+                        input_delay: input_delay as usize,
+                        max_rollback: max_rollback as usize,
+                    };
                     let peer_start =
                         (current_frame - local_offset) + peer_offset + GAME_START_DELAY;
                     let start_timer = current_frame + GAME_START_DELAY;
-                    self.send_msg(current_frame, MessageContent::StartAt(peer_start))?;
+                    send_msg(
+                        &self.socket,
+                        &mut self.send_buf,
+                        self.client_addr,
+                        current_frame,
+                        MessageContent::StartAt((
+                            peer_start,
+                            self.lineup.character_config(),
+                            self.lineup.stage_name(),
+                            self.netplay.input_delay as u32,
+                            self.netplay.max_rollback as u32,
+                        )),
+                    )?;
                     return Ok(Some(UdpHostState::Connecting(start_timer)));
                 }
                 MessageContent::Abort => return Ok(Some(UdpHostState::Listening)),
@@ -113,8 +174,12 @@ impl UdpHost {
         start_frame: usize,
     ) -> std::io::Result<Option<UdpHostState>> {
         while let Some(msg) = self.recv_msg() {
-            if let MessageContent::Abort = msg.content {
-                return Ok(Some(UdpHostState::Listening));
+            match msg.content {
+                MessageContent::Abort => return Ok(Some(UdpHostState::Listening)),
+                MessageContent::Mismatch => {
+                    return Err(peer_mismatch());
+                }
+                _ => {}
             }
         }
 
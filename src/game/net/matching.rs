@@ -5,8 +5,8 @@ use bincode::{BorrowDecode, config};
 use crate::game::{
     GAME_VERSION,
     net::{
-        BUFFER_LEN, GameMessage, MessageContent, PEER_TIME_OUT, client::UdpClient, host::UdpHost,
-        recv_msg, send_msg,
+        BUFFER_LEN, GameMessage, Lineup, MessageContent, PEER_TIME_OUT, client::UdpClient,
+        host::UdpHost, recv_msg, send_msg,
     },
 };
 
@@ -36,10 +36,12 @@ pub struct MatchingSocket {
     state: MatchingState,
     recv_buf: [u8; BUFFER_LEN],
     send_buf: [u8; BUFFER_LEN],
+    /// Handed to the host/client once a peer is found
+    lineup: Lineup,
 }
 
 impl MatchingSocket {
-    pub fn bind<A>(local_addr: A, server_addr: A) -> std::io::Result<Self>
+    pub fn bind<A>(local_addr: A, server_addr: A, lineup: Lineup) -> std::io::Result<Self>
     where
         A: ToSocketAddrs,
     {
@@ -55,6 +57,7 @@ impl MatchingSocket {
             state: MatchingState::RequestPeer,
             recv_buf: [0; BUFFER_LEN],
             send_buf: [0; BUFFER_LEN],
+            lineup,
         })
     }
 
@@ -62,6 +65,7 @@ impl MatchingSocket {
         Ok(PeerConnectionType::Hosting(UdpHost::new(
             self.socket.try_clone()?,
             client_addr,
+            self.lineup.clone(),
         )))
     }
 
@@ -69,6 +73,7 @@ impl MatchingSocket {
         Ok(PeerConnectionType::Joining(UdpClient::new(
             self.socket.try_clone()?,
             host_addr,
+            self.lineup.clone(),
         )))
     }
 
@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use sdl3::{
+    keyboard::Keycode,
+    pixels::Color,
+    render::{Canvas, FRect},
+    video::Window,
+};
+
+use crate::game::render::text::{draw_text, text_height, text_width};
+
+const TOGGLE_KEY: Keycode = Keycode::F10;
+const SAMPLE_PERIOD: Duration = Duration::from_secs(1);
+
+/// Numbers for one pass of `Game::run`
+pub struct FrameMetrics {
+    /// Time left over after the ticks ran, in nanoseconds
+    pub lag: i128,
+    pub update_time: Duration,
+    pub render_time: Duration,
+    /// Frames the scene has re-simulated for rollbacks so far
+    pub rolled_back: usize,
+    pub texture_count: usize,
+}
+
+/// Counts frames and rollbacks over each second, timings are from the latest frame
+#[derive(Default)]
+pub struct PerfOverlay {
+    open: bool,
+    sample_start: Option<Instant>,
+    sample_frames: usize,
+    sample_rolled_back: usize,
+    last_rolled_back: usize,
+    fps: usize,
+    rollbacks_per_sec: usize,
+    latest: Option<FrameMetrics>,
+}
+
+impl PerfOverlay {
+    pub fn handle_keypress(&mut self, keycode: Keycode) {
+        if keycode == TOGGLE_KEY {
+            self.open = !self.open;
+        }
+    }
+
+    pub fn record_frame(&mut self, metrics: FrameMetrics) {
+        let now = Instant::now();
+        let sample_start = *self.sample_start.get_or_insert(now);
+
+        self.sample_frames += 1;
+        // The count restarts with each scene
+        self.sample_rolled_back += if metrics.rolled_back >= self.last_rolled_back {
+            metrics.rolled_back - self.last_rolled_back
+        } else {
+            metrics.rolled_back
+        };
+        self.last_rolled_back = metrics.rolled_back;
+        self.latest = Some(metrics);
+
+        if now.duration_since(sample_start) >= SAMPLE_PERIOD {
+            self.fps = self.sample_frames;
+            self.rollbacks_per_sec = self.sample_rolled_back;
+            self.sample_start = Some(now);
+            self.sample_frames = 0;
+            self.sample_rolled_back = 0;
+        }
+    }
+
+    /// Panel in the top left under the health bar
+    pub fn render(&self, canvas: &mut Canvas<Window>) -> Result<(), sdl3::Error> {
+        let (true, Some(latest)) = (self.open, &self.latest) else {
+            return Ok(());
+        };
+
+        let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let lines = [
+            format!("FPS {}", self.fps),
+            format!("LAG {:.2}MS", latest.lag as f64 / 1_000_000.0),
+            format!("ROLLBACK {}/S", self.rollbacks_per_sec),
+            format!("TEXTURES {}", latest.texture_count),
+            format!("UPDATE {:.2}MS", millis(latest.update_time)),
+            format!("RENDER {:.2}MS", millis(latest.render_time)),
+        ];
+
+        let (_, screen_h) = canvas.window().size();
+        let scale = screen_h as f32 / 300.0;
+        let line_h = text_height(scale) * 1.5;
+        let pad = scale * 2.0;
+        let panel_w = lines
+            .iter()
+            .map(|line| text_width(line, scale))
+            .fold(0.0, f32::max);
+        let x = pad * 2.0;
+        let y = screen_h as f32 * 0.2;
+
+        canvas.set_draw_color(Color::BLACK);
+        canvas.fill_rect(FRect::new(
+            x - pad,
+            y - pad,
+            panel_w + pad * 2.0,
+            line_h * lines.len() as f32 + pad,
+        ))?;
+        for (row, line) in lines.iter().enumerate() {
+            draw_text(
+                canvas,
+                line,
+                x,
+                y + row as f32 * line_h,
+                scale,
+                Color::WHITE,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -4,31 +4,31 @@ use sdl3::render::{FPoint, FRect};
 
 use crate::game::{
     Side,
-    boxes::{CollisionBox, HitBox, HurtBox},
+    boxes::{CollisionBox, HitBox, HurtBox, ThrowBox},
     stage::Stage,
 };
 
-const GRAVITY_CONSTANT: f32 = 0.4;
-const FRICTION_COEFFICIENT: f32 = 0.6;
+pub const DEFAULT_GRAVITY: f32 = 0.4;
+pub const DEFAULT_FRICTION: f32 = 0.6;
 
 pub fn velocity_system(pos: FPoint, vel: FPoint) -> FPoint {
     FPoint::new(pos.x + vel.x, pos.y + vel.y)
 }
 
-pub fn friction_system(vel: FPoint) -> FPoint {
-    FPoint::new(vel.x * FRICTION_COEFFICIENT, vel.y * FRICTION_COEFFICIENT)
+pub fn friction_system(vel: FPoint, friction: f32) -> FPoint {
+    FPoint::new(vel.x * friction, vel.y * friction)
 }
 
 /// Returns true if grounded
 /// Returns (pos, vel, grounded)
-pub fn gravity_system(pos: FPoint, vel: FPoint, gravity_mult: f32) -> (FPoint, FPoint, bool) {
+pub fn gravity_system(pos: FPoint, vel: FPoint, gravity: f32) -> (FPoint, FPoint, bool) {
     if pos.y <= 0.0 {
         let new_pos = FPoint::new(pos.x, 0.0);
         let new_vel = FPoint::new(vel.x, 0.0);
         (new_pos, new_vel, true)
     } else {
         let new_pos = FPoint::new(pos.x, pos.y);
-        let new_vel = FPoint::new(vel.x, vel.y - GRAVITY_CONSTANT * gravity_mult);
+        let new_vel = FPoint::new(vel.x, vel.y - gravity);
         (new_pos, new_vel, false)
     }
 }
@@ -62,6 +62,45 @@ pub fn check_hit_collisions(
     None
 }
 
+/// Returns the center of the overlap if the two hit boxes touch
+pub fn check_hit_box_clash(
+    side1: Side,
+    offset1: FPoint,
+    hit_box1: &HitBox,
+    side2: Side,
+    offset2: FPoint,
+    hit_box2: &HitBox,
+) -> Option<FPoint> {
+    let rect1 = hit_box1.on_side(side1, offset1);
+    let rect2 = hit_box2.on_side(side2, offset2);
+    if !aabb_collision(rect1, rect2) {
+        return None;
+    }
+
+    let left = rect1.x.max(rect2.x);
+    let right = (rect1.x + rect1.w).min(rect2.x + rect2.w);
+    let top = rect1.y.min(rect2.y);
+    let bottom = (rect1.y - rect1.h).max(rect2.y - rect2.h);
+    Some(FPoint::new((left + right) / 2.0, (top + bottom) / 2.0))
+}
+
+pub fn check_throw_collision(
+    throw_side: Side,
+    throw_box_offset: FPoint,
+    throw_box: &ThrowBox,
+    hurt_side: Side,
+    hurt_box_offset: FPoint,
+    hurt_boxes: &[HurtBox],
+) -> bool {
+    let throw_box_with_offset = throw_box.on_side(throw_side, throw_box_offset);
+    hurt_boxes.iter().any(|hurt_box| {
+        aabb_collision(
+            throw_box_with_offset,
+            hurt_box.on_side(hurt_side, hurt_box_offset),
+        )
+    })
+}
+
 // Returns (player1_pos, player2_pos)
 pub fn movement_system(
     pos1_side: Side,
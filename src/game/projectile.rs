@@ -1,10 +1,201 @@
-use sdl3::render::FRect;
+use sdl3::render::FPoint;
 
-use crate::game::boxes::HitBox;
+use crate::game::{
+    Side,
+    boxes::{HitBox, HurtBox},
+    physics::{check_hit_collisions, velocity_system},
+    render::animation::Animation,
+};
 
-#[allow(dead_code)]
+/// Frames a clash spark stays on screen
+pub const CLASH_SPARK_FRAMES: usize = 12;
+
+/// What a move's `SpawnProjectile` event fires, offset and velocity point the way the owner faces
+pub struct ProjectileData {
+    offset: FPoint,
+    vel: FPoint,
+    lifetime: usize,
+    /// Clashing with another projectile takes off the smaller durability from both
+    durability: u32,
+    hit_box: HitBox,
+    animation: Animation,
+}
+
+impl ProjectileData {
+    pub fn new(
+        offset: FPoint,
+        vel: FPoint,
+        lifetime: usize,
+        durability: u32,
+        hit_box: HitBox,
+        animation: Animation,
+    ) -> Self {
+        Self {
+            offset,
+            vel,
+            lifetime,
+            durability,
+            hit_box,
+            animation,
+        }
+    }
+
+    pub fn hit_box(&self) -> &HitBox {
+        &self.hit_box
+    }
+
+    pub fn animation(&self) -> &Animation {
+        &self.animation
+    }
+}
+
+/// Something sent out by a character that moves on its own in a straight line, projectiles and assists
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Flight {
+    pos: FPoint,
+    side: Side,
+    frame: usize,
+}
+
+impl Flight {
+    /// Starts `offset` away from the owner, offset and later velocities point the way `side` faces
+    pub fn launch(owner_pos: FPoint, offset: FPoint, side: Side) -> Self {
+        Self {
+            pos: velocity_system(owner_pos, facing(offset, side)),
+            side,
+            frame: 0,
+        }
+    }
+
+    /// Moves one frame along `vel`, returns false once it has been out for `frames`
+    pub fn step(&mut self, vel: FPoint, frames: usize) -> bool {
+        self.pos = velocity_system(self.pos, facing(vel, self.side));
+        self.frame += 1;
+        self.frame < frames
+    }
+
+    /// `hit_box` placed where this is, if it touches any of the hurt boxes
+    pub fn hits(
+        &self,
+        hit_box: &HitBox,
+        hurt_side: Side,
+        hurt_pos: FPoint,
+        hurt_boxes: &[HurtBox],
+    ) -> Option<HitBox> {
+        check_hit_collisions(
+            self.side,
+            self.pos,
+            std::slice::from_ref(hit_box),
+            hurt_side,
+            hurt_pos,
+            hurt_boxes,
+        )
+    }
+
+    pub fn pos(&self) -> FPoint {
+        self.pos
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+}
+
+fn facing(point: FPoint, side: Side) -> FPoint {
+    match side {
+        Side::Left => point,
+        Side::Right => FPoint::new(-point.x, point.y),
+    }
+}
+
+/// A live projectile, it only keeps the index of the event that spawned it so rollbacks stay cheap
+#[derive(Clone, PartialEq, Debug)]
 pub struct Projectile {
-    pos: FRect,
-    hitbox: HitBox,
-    animation: usize,
+    source: (usize, usize),
+    flight: Flight,
+    durability: u32,
+}
+
+impl Projectile {
+    pub fn spawn(
+        data: &ProjectileData,
+        source: (usize, usize),
+        owner_pos: FPoint,
+        side: Side,
+    ) -> Self {
+        Self {
+            source,
+            flight: Flight::launch(owner_pos, data.offset, side),
+            durability: data.durability,
+        }
+    }
+
+    /// Returns false once the projectile has outlived its lifetime
+    pub fn update(&mut self, data: &ProjectileData) -> bool {
+        self.flight.step(data.vel, data.lifetime)
+    }
+
+    /// (state index, event index) of the event that spawned this projectile
+    pub fn source(&self) -> (usize, usize) {
+        self.source
+    }
+
+    pub fn flight(&self) -> &Flight {
+        &self.flight
+    }
+
+    pub fn pos(&self) -> FPoint {
+        self.flight.pos()
+    }
+
+    pub fn side(&self) -> Side {
+        self.flight.side()
+    }
+
+    pub fn frame(&self) -> usize {
+        self.flight.frame()
+    }
+
+    pub fn durability(&self) -> u32 {
+        self.durability
+    }
+
+    /// Both projectiles lose the weaker one's durability, returns false if either was already used up
+    pub fn clash(&mut self, other: &mut Projectile) -> bool {
+        let amount = self.durability.min(other.durability);
+        self.durability -= amount;
+        other.durability -= amount;
+        amount > 0
+    }
+}
+
+/// Drawn where two projectiles cancel out, lives in the game state so rollbacks replay it
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClashSpark {
+    pos: FPoint,
+    frame: usize,
+}
+
+impl ClashSpark {
+    pub fn new(pos: FPoint) -> Self {
+        Self { pos, frame: 0 }
+    }
+
+    /// Returns false once the spark has faded
+    pub fn update(&mut self) -> bool {
+        self.frame += 1;
+        self.frame < CLASH_SPARK_FRAMES
+    }
+
+    pub fn pos(&self) -> FPoint {
+        self.pos
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
 }
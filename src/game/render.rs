@@ -1,8 +1,10 @@
-use image::DynamicImage;
+use std::{collections::HashMap, io::Cursor, path::Path, rc::Rc};
+
+use image::{DynamicImage, RgbaImage};
 use sdl3::{
-    pixels::{FColor, PixelFormat},
+    pixels::{Color, FColor, PixelFormat},
     rect::Rect,
-    render::{Canvas, FPoint, FRect, Texture, TextureCreator},
+    render::{Canvas, FPoint, FRect, ScaleMode, Texture, TextureCreator},
     sys::pixels::SDL_PIXELFORMAT_ABGR8888,
     video::{Window, WindowContext},
 };
@@ -11,19 +13,54 @@ use crate::{
     DEFAULT_SCREEN_HEIGHT, DEFAULT_SCREEN_WIDTH,
     game::{
         Side,
-        boxes::{CollisionBox, HitBox, HurtBox},
+        assets::{AssetSource, FileSource},
+        boxes::{CollisionBox, HitBox, HurtBox, ThrowBox},
+        damage_number::{DAMAGE_NUMBER_FRAMES, DamageNumber},
+        projectile::{CLASH_SPARK_FRAMES, ClashSpark},
         render::animation::{Animation, AnimationLayout},
+        stage::Stage,
     },
 };
 
 pub mod animation;
+pub mod text;
+
+/// Game units across a clash spark when it first appears
+const CLASH_SPARK_SIZE: f32 = 60.0;
+/// Font pixel size of damage numbers in game units
+const DAMAGE_NUMBER_SCALE: f32 = 5.0;
+const MAX_ZOOM: f32 = 1.4;
+/// Zoom on the winner while a knockout plays out
+const CLOSE_UP_ZOOM: f32 = 1.8;
+/// Game units kept on screen past each player
+const FRAMING_MARGIN: f32 = 150.0;
+/// Share of the way to the target framing covered each frame
+const FRAMING_EASE: f32 = 0.1;
+/// Shadow width against the collision box it sits under
+const SHADOW_WIDTH_SCALE: f32 = 1.6;
+/// Game units tall at full size
+const SHADOW_HEIGHT: f32 = 12.0;
+/// Height above the ground where the shadow is at its smallest
+const SHADOW_SHRINK_HEIGHT: f32 = 400.0;
+const SHADOW_MIN_SCALE: f32 = 0.4;
+const SHADOW_ALPHA: f32 = 0.4;
+/// Strips the shadow ellipse is built from
+const SHADOW_ROWS: usize = 6;
+/// Side of the square textures animation frames and small images are packed into
+const ATLAS_SIZE: u32 = 2048;
+/// Images with a side past this get their own texture, they would leave most of a page unused
+const ATLAS_MAX_REGION: u32 = ATLAS_SIZE / 2;
+/// Edge pixels repeated around each region so filtering doesn't pull in its neighbors
+const ATLAS_PADDING: u32 = 1;
 
 pub struct Camera {
-    screen_w: u32,
-    screen_h: u32,
+    /// Largest part of the window with the default aspect ratio, the rest is letterboxed
+    viewport: FRect,
     game_center: FPoint,
     game_to_screen_ratio: FPoint,
     offset: FPoint,
+    /// Scales by whole numbers only when the window is at least the default size
+    pixel_perfect: bool,
 }
 
 impl Camera {
@@ -32,21 +69,58 @@ impl Camera {
 
     pub fn new(screen_dim: (u32, u32)) -> Self {
         let (w, h) = screen_dim;
+        let viewport = Self::calc_viewport(w, h, false);
         Self {
-            screen_w: w,
-            screen_h: h,
-            game_center: Self::calc_game_center(w, h),
+            viewport,
+            game_center: Self::calc_game_center(viewport),
             offset: FPoint { x: 0.0, y: 0.0 },
-            game_to_screen_ratio: Self::calc_screen_ratio(w, h),
+            game_to_screen_ratio: Self::calc_screen_ratio(viewport),
+            pixel_perfect: false,
         }
     }
 
+    pub fn set_pixel_perfect(&mut self, pixel_perfect: bool) {
+        self.pixel_perfect = pixel_perfect;
+    }
+
     pub fn resize(&mut self, screen_dim: (u32, u32)) {
         let (w, h) = screen_dim;
-        self.screen_w = w;
-        self.screen_h = h;
-        self.game_center = Self::calc_game_center(w, h);
-        self.game_to_screen_ratio = Self::calc_screen_ratio(w, h);
+        self.viewport = Self::calc_viewport(w, h, self.pixel_perfect);
+        self.game_center = Self::calc_game_center(self.viewport);
+        self.game_to_screen_ratio = Self::calc_screen_ratio(self.viewport);
+    }
+
+    pub fn viewport(&self) -> FRect {
+        self.viewport
+    }
+
+    /// Black bars over whatever was drawn outside the viewport
+    pub fn render_letterbox(&self, canvas: &mut Canvas<Window>) -> Result<(), sdl3::Error> {
+        let (screen_w, screen_h) = canvas.window().size();
+        let (screen_w, screen_h) = (screen_w as f32, screen_h as f32);
+        let viewport = self.viewport;
+        let bars = [
+            FRect::new(0.0, 0.0, viewport.x, screen_h),
+            FRect::new(
+                viewport.x + viewport.w,
+                0.0,
+                screen_w - viewport.x - viewport.w,
+                screen_h,
+            ),
+            FRect::new(0.0, 0.0, screen_w, viewport.y),
+            FRect::new(
+                0.0,
+                viewport.y + viewport.h,
+                screen_w,
+                screen_h - viewport.y - viewport.h,
+            ),
+        ];
+
+        canvas.set_draw_color(Color::BLACK);
+        for bar in bars.into_iter().filter(|bar| bar.w > 0.0 && bar.h > 0.0) {
+            canvas.fill_rect(bar)?;
+        }
+        Ok(())
     }
 
     pub fn render_animation(
@@ -57,7 +131,8 @@ impl Camera {
         animation: &Animation,
         frame: usize,
     ) -> Result<(), sdl3::Error> {
-        let screen_pos = self.to_screen_pos(pos);
+        let offset = animation.get_frame_offset(frame);
+        let screen_pos = self.to_screen_pos(FPoint::new(pos.x + offset.x, pos.y + offset.y));
 
         let (texture, src) = animation.get_frame(frame, global_textures);
         // Animation is rendered with the pos in the center
@@ -81,7 +156,12 @@ impl Camera {
         frame: usize,
         side: Side,
     ) -> Result<(), sdl3::Error> {
-        let screen_pos = self.to_screen_pos(pos);
+        let offset = animation.get_frame_cycle_offset(frame);
+        let offset_x = match side {
+            Side::Left => offset.x,
+            Side::Right => -offset.x,
+        };
+        let screen_pos = self.to_screen_pos(FPoint::new(pos.x + offset_x, pos.y + offset.y));
         let flip_horz = match side {
             Side::Left => false,
             Side::Right => true,
@@ -100,6 +180,24 @@ impl Camera {
         canvas.copy_ex(texture, src, dst, 0.0, None, flip_horz, false)
     }
 
+    /// Copy of the camera looking at `framing` with the ground raised by `floor_height`, the screen size stays the same
+    pub fn framed(&self, framing: &Framing, floor_height: f32) -> Camera {
+        Camera {
+            offset: FPoint::new(framing.center_x, floor_height),
+            game_to_screen_ratio: FPoint::new(
+                self.game_to_screen_ratio.x * framing.zoom,
+                self.game_to_screen_ratio.y * framing.zoom,
+            ),
+            ..*self
+        }
+    }
+
+    /// How far across the viewport `pos` is drawn, -1 at the left edge to 1 at the right
+    pub fn pan(&self, pos: FPoint) -> f32 {
+        let half_w = self.viewport.w / 2.0;
+        ((self.to_screen_pos(pos).x - self.game_center.x) / half_w).clamp(-1.0, 1.0)
+    }
+
     fn to_screen_pos(&self, pos: FPoint) -> FPoint {
         FPoint::new(
             self.game_center.x + (pos.x - self.offset.x) * self.game_to_screen_ratio.x,
@@ -116,15 +214,90 @@ impl Camera {
         )
     }
 
-    fn calc_screen_ratio(screen_w: u32, screen_h: u32) -> FPoint {
+    /// Scales the default screen uniformly to fit the window, centered with bars on the spare sides
+    fn calc_viewport(screen_w: u32, screen_h: u32, pixel_perfect: bool) -> FRect {
+        let (screen_w, screen_h) = (screen_w as f32, screen_h as f32);
+        let scale = (screen_w / Self::SCREEN_WIDTH_RATIO_1 as f32)
+            .min(screen_h / Self::SCREEN_HEIGHT_RATIO_1 as f32);
+        // Windows smaller than the default can't fit a whole scale, so they keep the fitted one
+        let scale = if pixel_perfect && scale >= 1.0 {
+            scale.floor()
+        } else {
+            scale
+        };
+        let w = Self::SCREEN_WIDTH_RATIO_1 as f32 * scale;
+        let h = Self::SCREEN_HEIGHT_RATIO_1 as f32 * scale;
+        FRect::new((screen_w - w) / 2.0, (screen_h - h) / 2.0, w, h)
+    }
+
+    fn calc_screen_ratio(viewport: FRect) -> FPoint {
         FPoint::new(
-            screen_w as f32 / Self::SCREEN_WIDTH_RATIO_1 as f32,
-            screen_h as f32 / Self::SCREEN_HEIGHT_RATIO_1 as f32,
+            viewport.w / Self::SCREEN_WIDTH_RATIO_1 as f32,
+            viewport.h / Self::SCREEN_HEIGHT_RATIO_1 as f32,
         )
     }
 
-    fn calc_game_center(screen_w: u32, screen_h: u32) -> FPoint {
-        FPoint::new(screen_w as f32 / 2.0, screen_h as f32 * 0.9)
+    fn calc_game_center(viewport: FRect) -> FPoint {
+        FPoint::new(viewport.x + viewport.w / 2.0, viewport.y + viewport.h * 0.9)
+    }
+}
+
+/// Where the gameplay camera looks, it is only a function of the players so it replays with rollbacks
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Framing {
+    center_x: f32,
+    zoom: f32,
+}
+
+impl Framing {
+    /// How far the camera has panned towards a wall, -1 on the left one to 1 on the right
+    pub fn pan(&self, stage: &Stage) -> f32 {
+        if stage.width() <= 0.0 {
+            return 0.0;
+        }
+        (self.center_x / stage.width()).clamp(-1.0, 1.0)
+    }
+
+    /// Centered on the players, zoomed in as far as keeps both with a margin on screen
+    pub fn on_players(stage: &Stage, pos1: FPoint, pos2: FPoint) -> Self {
+        let half_view_w = Camera::SCREEN_WIDTH_RATIO_1 as f32 / 2.0;
+        let needed_half_w = (pos1.x - pos2.x).abs() / 2.0 + FRAMING_MARGIN;
+        let zoom = (half_view_w / needed_half_w).clamp(1.0, MAX_ZOOM);
+
+        // Never show past the stage edges once zoomed in
+        let edge = (stage.width() - half_view_w / zoom).max(0.0);
+        let center_x = ((pos1.x + pos2.x) / 2.0).clamp(-edge, edge);
+        Self { center_x, zoom }
+    }
+
+    /// Centered on `pos` zoomed past what gameplay uses, still kept inside the stage
+    pub fn close_up(stage: &Stage, pos: FPoint) -> Self {
+        let half_view_w = Camera::SCREEN_WIDTH_RATIO_1 as f32 / 2.0;
+        let edge = (stage.width() - half_view_w / CLOSE_UP_ZOOM).max(0.0);
+        Self {
+            center_x: pos.x.clamp(-edge, edge),
+            zoom: CLOSE_UP_ZOOM,
+        }
+    }
+
+    /// Eases towards framing the players
+    pub fn track(&mut self, stage: &Stage, pos1: FPoint, pos2: FPoint) {
+        self.ease_to(Self::on_players(stage, pos1, pos2));
+    }
+
+    pub fn ease_to(&mut self, target: Self) {
+        self.center_x += (target.center_x - self.center_x) * FRAMING_EASE;
+        self.zoom += (target.zoom - self.zoom) * FRAMING_EASE;
+    }
+}
+
+/// The whole screen, as the camera was before it tracked the players
+impl Default for Framing {
+    fn default() -> Self {
+        Self {
+            center_x: 0.0,
+            zoom: 1.0,
+        }
     }
 }
 
@@ -174,11 +347,130 @@ pub fn draw_collision_box_system(
     Ok(())
 }
 
-fn open_img(file_path: &str) -> Result<DynamicImage, String> {
-    let file =
-        std::fs::File::open(file_path).map_err(|err| format!("File: '{file_path}': {err}"))?;
-    let reader = std::io::BufReader::new(file);
-    let img = image::ImageReader::new(reader)
+pub fn draw_throw_box_system(
+    canvas: &mut Canvas<Window>,
+    camera: &Camera,
+    side: Side,
+    offset: FPoint,
+    throw_box: &ThrowBox,
+) -> Result<(), sdl3::Error> {
+    canvas.set_draw_color(FColor::RGBA(1.0, 0.0, 1.0, 0.5));
+    let on_side_hitbox = throw_box.on_side(side, offset);
+    let on_screen_rect = camera.to_screen_rect(on_side_hitbox);
+    canvas.fill_rect(on_screen_rect)?;
+    Ok(())
+}
+
+/// Ellipse on the ground under `ground_box`, the collision box as it would be standing, shrinking
+/// and fading as `height` goes up
+pub fn draw_shadow_system(
+    canvas: &mut Canvas<Window>,
+    camera: &Camera,
+    ground_box: FRect,
+    height: f32,
+) -> Result<(), sdl3::Error> {
+    let scale = (1.0 - height / SHADOW_SHRINK_HEIGHT).clamp(SHADOW_MIN_SCALE, 1.0);
+    let w = ground_box.w * SHADOW_WIDTH_SCALE * scale;
+    let h = SHADOW_HEIGHT * scale;
+    let center_x = ground_box.x + ground_box.w / 2.0;
+    let ground_y = ground_box.y - ground_box.h;
+    let row_h = h / SHADOW_ROWS as f32;
+
+    canvas.set_blend_mode(sdl3::render::BlendMode::Blend);
+    canvas.set_draw_color(FColor::RGBA(0.0, 0.0, 0.0, SHADOW_ALPHA * scale));
+    for row in 0..SHADOW_ROWS {
+        // Middle of the row from -1 at the top to 1 at the bottom
+        let row_y = (row as f32 + 0.5) / SHADOW_ROWS as f32 * 2.0 - 1.0;
+        let row_w = w * (1.0 - row_y * row_y).sqrt();
+        let rect = FRect::new(
+            center_x - row_w / 2.0,
+            ground_y + h / 2.0 - row as f32 * row_h,
+            row_w,
+            row_h,
+        );
+        canvas.fill_rect(camera.to_screen_rect(rect))?;
+    }
+    canvas.set_blend_mode(sdl3::render::BlendMode::None);
+    Ok(())
+}
+
+/// White overlay on the hurt boxes that fades out with `intensity`
+pub fn draw_hit_flash_system(
+    canvas: &mut Canvas<Window>,
+    camera: &Camera,
+    side: Side,
+    offset: FPoint,
+    hurtboxes: &[HurtBox],
+    intensity: f32,
+) -> Result<(), sdl3::Error> {
+    canvas.set_blend_mode(sdl3::render::BlendMode::Add);
+    canvas.set_draw_color(FColor::RGBA(1.0, 1.0, 1.0, 0.6 * intensity));
+    for hurtbox in hurtboxes {
+        let on_side_hitbox = hurtbox.on_side(side, offset);
+        let on_screen_rect = camera.to_screen_rect(on_side_hitbox);
+        canvas.fill_rect(on_screen_rect)?;
+    }
+    canvas.set_blend_mode(sdl3::render::BlendMode::None);
+    Ok(())
+}
+
+pub fn draw_clash_spark_system(
+    canvas: &mut Canvas<Window>,
+    camera: &Camera,
+    clash_spark: &ClashSpark,
+) -> Result<(), sdl3::Error> {
+    let fade = 1.0 - clash_spark.frame() as f32 / CLASH_SPARK_FRAMES as f32;
+    let size = CLASH_SPARK_SIZE * (0.5 + clash_spark.frame() as f32 / CLASH_SPARK_FRAMES as f32);
+    let pos = clash_spark.pos();
+    // One flat and one tall bar make a cross that grows as it fades
+    let bars = [
+        FRect::new(pos.x - size / 2.0, pos.y + size / 8.0, size, size / 4.0),
+        FRect::new(pos.x - size / 8.0, pos.y + size / 2.0, size / 4.0, size),
+    ];
+
+    canvas.set_blend_mode(sdl3::render::BlendMode::Add);
+    canvas.set_draw_color(FColor::RGBA(1.0, 0.85, 0.3, fade));
+    for bar in bars {
+        canvas.fill_rect(camera.to_screen_rect(bar))?;
+    }
+    canvas.set_blend_mode(sdl3::render::BlendMode::None);
+    Ok(())
+}
+
+pub fn draw_damage_number_system(
+    canvas: &mut Canvas<Window>,
+    camera: &Camera,
+    damage_number: &DamageNumber,
+) -> Result<(), sdl3::Error> {
+    // Solid for the first half, then fades out
+    let life = damage_number.frame() as f32 / DAMAGE_NUMBER_FRAMES as f32;
+    let alpha = (((1.0 - life) * 2.0).min(1.0) * 255.0) as u8;
+    let color = if damage_number.chip() {
+        Color::RGBA(180, 180, 180, alpha)
+    } else {
+        Color::RGBA(255, 230, 50, alpha)
+    };
+    let pos = camera.to_screen_pos(damage_number.pos());
+    let scale = DAMAGE_NUMBER_SCALE * camera.game_to_screen_ratio.y;
+
+    canvas.set_blend_mode(sdl3::render::BlendMode::Blend);
+    text::draw_text_centered(
+        canvas,
+        &damage_number.label(),
+        pos.x,
+        pos.y - text::text_height(scale),
+        scale,
+        color,
+    )?;
+    canvas.set_blend_mode(sdl3::render::BlendMode::None);
+    Ok(())
+}
+
+fn open_img(assets: &dyn AssetSource, file_path: &str) -> Result<DynamicImage, String> {
+    let bytes = assets
+        .read(file_path)
+        .map_err(|err| format!("File: '{file_path}': {err}"))?;
+    let img = image::ImageReader::new(Cursor::new(bytes))
         .with_guessed_format()
         .expect("Failed to guess img file format")
         .decode()
@@ -191,84 +483,388 @@ fn open_img(file_path: &str) -> Result<DynamicImage, String> {
     Ok(img)
 }
 
-pub fn load_texture<'a>(
-    texture_creator: &'a TextureCreator<WindowContext>,
-    global_textures: &mut Vec<Texture<'a>>,
-    file_path: &str,
-) -> Result<usize, String> {
-    let img = open_img(file_path)?;
-
-    let mut texture = texture_creator
-        .create_texture_streaming(
-            unsafe { PixelFormat::from_ll(SDL_PIXELFORMAT_ABGR8888) },
-            img.width(),
-            img.height(),
+/// Top left corner of an animation frame in its sprite sheet
+fn frame_origin(layout: AnimationLayout, frame: u32, width: u32, height: u32) -> (u32, u32) {
+    match layout {
+        AnimationLayout::Vertical => (0, frame * height),
+        AnimationLayout::Horizontal => (frame * width, 0),
+    }
+}
+
+/// Whether two paths lead to the same file, however they are spelled
+fn same_file(file_path: &str, path: &Path) -> bool {
+    match (
+        std::fs::canonicalize(file_path),
+        std::fs::canonicalize(path),
+    ) {
+        (Ok(file_path), Ok(path)) => file_path == path,
+        _ => false,
+    }
+}
+
+/// Copy of `img` with its outer pixels repeated `ATLAS_PADDING` times on every side
+fn extrude(img: &RgbaImage) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    RgbaImage::from_fn(w + ATLAS_PADDING * 2, h + ATLAS_PADDING * 2, |x, y| {
+        *img.get_pixel(
+            x.saturating_sub(ATLAS_PADDING).min(w - 1),
+            y.saturating_sub(ATLAS_PADDING).min(h - 1),
         )
-        .map_err(|err| format!("File: '{file_path}': {err}"))?;
+    })
+}
 
-    texture
-        .update(None, &img.to_rgba8(), 4 * img.width() as usize)
-        .map_err(|err| format!("File: '{file_path}': {err}"))?;
+/// Part of a global texture an image or animation frame was loaded into
+#[derive(Clone, Copy, Debug)]
+pub struct TextureRegion {
+    pub texture: usize,
+    pub src: FRect,
+}
+
+/// Shelf packer over one atlas texture, regions fill a row left to right and the next row starts
+/// under the tallest one
+struct AtlasPage {
+    texture: usize,
+    tint: Option<Tint>,
+    x: u32,
+    y: u32,
+    row_h: u32,
+}
+
+impl AtlasPage {
+    fn new(texture: usize, tint: Option<Tint>) -> Self {
+        Self {
+            texture,
+            tint,
+            x: 0,
+            y: 0,
+            row_h: 0,
+        }
+    }
+
+    /// Space for a `w` by `h` region, `None` once the page is full
+    fn allocate(&mut self, w: u32, h: u32) -> Option<Rect> {
+        if self.x + w > ATLAS_SIZE {
+            self.x = 0;
+            self.y += self.row_h;
+            self.row_h = 0;
+        }
+        if self.y + h > ATLAS_SIZE {
+            return None;
+        }
 
-    global_textures.push(texture);
+        let rect = Rect::new(self.x as i32, self.y as i32, w, h);
+        self.x += w;
+        self.row_h = self.row_h.max(h);
+        Some(rect)
+    }
+}
 
-    Ok(global_textures.len() - 1)
+/// Turns image files into regions of the global texture list
+pub trait TextureLoader {
+    /// Where the images, and the configs naming them, are read from
+    fn assets(&self) -> &dyn AssetSource;
+    /// Color modulation for everything loaded until it is set back to `None`, used for palettes
+    fn set_tint(&mut self, _tint: Option<Tint>) {}
+    fn load_texture(&mut self, file_path: &str) -> Result<TextureRegion, String>;
+    /// One region per frame
+    fn load_animation(
+        &mut self,
+        file_path: &str,
+        width: u32,
+        height: u32,
+        frames: u32,
+        layout: AnimationLayout,
+    ) -> Result<Vec<TextureRegion>, String>;
 }
 
-pub fn load_animation<'a>(
+/// (r, g, b) the texture colors are multiplied by
+pub type Tint = (u8, u8, u8);
+
+/// (file path, frame width, frame height, frames, layout, tint), plain textures have no frames
+type LoadKey = (String, u32, u32, u32, Option<AnimationLayout>, Option<Tint>);
+
+/// Owns the global texture list, images already loaded are handed out again instead of reloaded
+///
+/// Animation frames and small images are packed into shared atlas pages, one set of pages per tint
+pub struct SdlTextureLoader<'a> {
     texture_creator: &'a TextureCreator<WindowContext>,
-    global_textures: &mut Vec<Texture<'a>>,
-    file_path: &str,
-    width: u32,
-    height: u32,
-    frames: u32,
-    layout: AnimationLayout,
-) -> Result<usize, String> {
-    let img = open_img(file_path)?;
-
-    let mut texture = texture_creator
-        .create_texture_streaming(
-            unsafe { PixelFormat::from_ll(SDL_PIXELFORMAT_ABGR8888) },
-            width,
-            height * frames,
-        )
-        .map_err(|err| format!("File: '{file_path}': {err}"))?;
+    assets: Rc<dyn AssetSource>,
+    textures: Vec<Texture<'a>>,
+    pages: Vec<AtlasPage>,
+    loaded: HashMap<LoadKey, Vec<TextureRegion>>,
+    tint: Option<Tint>,
+    scale_mode: ScaleMode,
+}
 
-    match layout {
-        AnimationLayout::Vertical => {
-            let frames_rect = Rect::new(0, 0, width, height * frames);
-            let frames = img.crop_imm(
-                frames_rect.x as u32,
-                frames_rect.y as u32,
-                frames_rect.width(),
-                frames_rect.height(),
-            );
-            texture
-                .update(frames_rect, &frames.to_rgba8(), 4 * frames.width() as usize)
+impl<'a> SdlTextureLoader<'a> {
+    pub fn new(
+        texture_creator: &'a TextureCreator<WindowContext>,
+        assets: Rc<dyn AssetSource>,
+    ) -> Self {
+        Self {
+            texture_creator,
+            assets,
+            textures: Vec::new(),
+            pages: Vec::new(),
+            loaded: HashMap::new(),
+            tint: None,
+            scale_mode: ScaleMode::Linear,
+        }
+    }
+
+    pub fn textures(&self) -> &[Texture<'a>] {
+        &self.textures
+    }
+
+    /// Applies to the textures already loaded as well as later ones
+    pub fn set_scale_mode(&mut self, scale_mode: ScaleMode) {
+        self.scale_mode = scale_mode;
+        for texture in &mut self.textures {
+            texture.set_scale_mode(scale_mode);
+        }
+    }
+
+    /// Tinted copies are separate textures, so both players can share an image in different colors
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        file_path: &str,
+    ) -> Result<usize, String> {
+        let mut texture = self
+            .texture_creator
+            .create_texture_streaming(
+                unsafe { PixelFormat::from_ll(SDL_PIXELFORMAT_ABGR8888) },
+                width,
+                height,
+            )
+            .map_err(|err| format!("File: '{file_path}': {err}"))?;
+        if let Some((r, g, b)) = self.tint {
+            texture.set_color_mod(r, g, b);
+        }
+        texture.set_scale_mode(self.scale_mode);
+        self.textures.push(texture);
+        Ok(self.textures.len() - 1)
+    }
+
+    /// Packs `img` into an atlas page with room, images too big to share a page get their own texture
+    fn place(&mut self, img: &RgbaImage, file_path: &str) -> Result<TextureRegion, String> {
+        let (w, h) = img.dimensions();
+        if w > ATLAS_MAX_REGION || h > ATLAS_MAX_REGION {
+            let texture = self.create_texture(w, h, file_path)?;
+            self.textures[texture]
+                .update(None, img, 4 * w as usize)
                 .map_err(|err| format!("File: '{file_path}': {err}"))?;
+            return Ok(TextureRegion {
+                texture,
+                src: FRect::new(0.0, 0.0, w as f32, h as f32),
+            });
         }
-        AnimationLayout::Horizontal => {
-            for frame in 0..frames {
-                let frame_rect = Rect::new((frame * width) as i32, 0, width, height);
-                let frame = img.crop_imm(
-                    frame_rect.x as u32,
-                    frame_rect.y as u32,
-                    frame_rect.width(),
-                    frame_rect.height(),
-                );
-                let texture_frame = Rect::new(
-                    frame_rect.y,
-                    frame_rect.x,
-                    frame_rect.width(),
-                    frame_rect.height(),
-                );
-                texture
-                    .update(texture_frame, &frame.to_rgba8(), 4 * frame.width() as usize)
-                    .map_err(|err| format!("File: '{file_path}': {err}"))?;
+
+        let padded = extrude(img);
+        let (padded_w, padded_h) = padded.dimensions();
+        let tint = self.tint;
+        let free_space = self
+            .pages
+            .iter_mut()
+            .filter(|page| page.tint == tint)
+            .find_map(|page| Some((page.texture, page.allocate(padded_w, padded_h)?)));
+        let (texture, rect) = match free_space {
+            Some(free_space) => free_space,
+            None => {
+                let texture = self.create_texture(ATLAS_SIZE, ATLAS_SIZE, file_path)?;
+                let mut page = AtlasPage::new(texture, tint);
+                let rect = page
+                    .allocate(padded_w, padded_h)
+                    .expect("Region fits an empty atlas page");
+                self.pages.push(page);
+                if cfg!(feature = "debug") {
+                    println!("Created atlas page {} (tint: {tint:?})", self.pages.len());
+                }
+                (texture, rect)
             }
+        };
+
+        self.textures[texture]
+            .update(rect, &padded, 4 * padded_w as usize)
+            .map_err(|err| format!("File: '{file_path}': {err}"))?;
+        Ok(TextureRegion {
+            texture,
+            src: FRect::new(
+                (rect.x as u32 + ATLAS_PADDING) as f32,
+                (rect.y as u32 + ATLAS_PADDING) as f32,
+                w as f32,
+                h as f32,
+            ),
+        })
+    }
+}
+
+impl SdlTextureLoader<'_> {
+    /// Redraws every region loaded from `path` with the image now on disk, false if none were
+    ///
+    /// Regions keep their place, so an image that changed size needs a restart to show up right
+    pub fn reload(&mut self, path: &Path) -> Result<bool, String> {
+        let loads: Vec<_> = self
+            .loaded
+            .iter()
+            .filter(|((file_path, ..), _)| same_file(file_path, path))
+            .map(|(key, regions)| (key.clone(), regions.clone()))
+            .collect();
+        for ((file_path, width, height, _, layout, _), regions) in &loads {
+            let img = open_img(self.assets.as_ref(), file_path)?;
+            for (frame, region) in regions.iter().enumerate() {
+                let img = match layout {
+                    Some(layout) => {
+                        let (x, y) = frame_origin(*layout, frame as u32, *width, *height);
+                        img.crop_imm(x, y, *width, *height).to_rgba8()
+                    }
+                    None => img.to_rgba8(),
+                };
+                self.redraw(*region, &img, file_path)?;
+            }
+        }
+        Ok(!loads.is_empty())
+    }
+
+    /// Writes `img` over a region handed out before, extruding it again on atlas pages
+    fn redraw(
+        &mut self,
+        region: TextureRegion,
+        img: &RgbaImage,
+        file_path: &str,
+    ) -> Result<(), String> {
+        let (w, h) = img.dimensions();
+        if (w as f32, h as f32) != (region.src.w, region.src.h) {
+            return Err(format!(
+                "File: '{file_path}': size changed from {}x{}, restart to load it",
+                region.src.w, region.src.h
+            ));
+        }
+        let on_page = self.pages.iter().any(|page| page.texture == region.texture);
+        let (img, padding) = if on_page {
+            (extrude(img), ATLAS_PADDING)
+        } else {
+            (img.clone(), 0)
+        };
+        let (padded_w, padded_h) = img.dimensions();
+        let rect = Rect::new(
+            region.src.x as i32 - padding as i32,
+            region.src.y as i32 - padding as i32,
+            padded_w,
+            padded_h,
+        );
+        self.textures[region.texture]
+            .update(rect, &img, 4 * padded_w as usize)
+            .map_err(|err| format!("File: '{file_path}': {err}"))
+    }
+}
+
+impl TextureLoader for SdlTextureLoader<'_> {
+    fn assets(&self) -> &dyn AssetSource {
+        self.assets.as_ref()
+    }
+
+    fn set_tint(&mut self, tint: Option<Tint>) {
+        self.tint = tint;
+    }
+
+    fn load_texture(&mut self, file_path: &str) -> Result<TextureRegion, String> {
+        let key = (file_path.to_string(), 0, 0, 0, None, self.tint);
+        if let Some(regions) = self.loaded.get(&key) {
+            return Ok(regions[0]);
+        }
+        let img = open_img(self.assets.as_ref(), file_path)?.to_rgba8();
+        let region = self.place(&img, file_path)?;
+        self.loaded.insert(key, vec![region]);
+        Ok(region)
+    }
+
+    fn load_animation(
+        &mut self,
+        file_path: &str,
+        width: u32,
+        height: u32,
+        frames: u32,
+        layout: AnimationLayout,
+    ) -> Result<Vec<TextureRegion>, String> {
+        let key = (
+            file_path.to_string(),
+            width,
+            height,
+            frames,
+            Some(layout),
+            self.tint,
+        );
+        if let Some(regions) = self.loaded.get(&key) {
+            return Ok(regions.clone());
+        }
+
+        let img = open_img(self.assets.as_ref(), file_path)?;
+        let regions = (0..frames)
+            .map(|frame| {
+                let (x, y) = frame_origin(layout, frame, width, height);
+                let frame = img.crop_imm(x, y, width, height).to_rgba8();
+                self.place(&frame, file_path)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.loaded.insert(key, regions.clone());
+        Ok(regions)
+    }
+}
+
+/// Hands out texture indices without decoding anything, used when there is no window
+pub struct HeadlessTextureLoader {
+    assets: Rc<dyn AssetSource>,
+    count: usize,
+}
+
+impl Default for HeadlessTextureLoader {
+    fn default() -> Self {
+        Self::new(Rc::new(FileSource))
+    }
+}
+
+impl HeadlessTextureLoader {
+    pub fn new(assets: Rc<dyn AssetSource>) -> Self {
+        Self { assets, count: 0 }
+    }
+
+    fn next_region(
+        &mut self,
+        file_path: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<TextureRegion, String> {
+        if !self.assets.exists(file_path) {
+            return Err(format!("File: '{file_path}': not found"));
         }
+        self.count += 1;
+        Ok(TextureRegion {
+            texture: self.count - 1,
+            src: FRect::new(0.0, 0.0, width as f32, height as f32),
+        })
     }
-    global_textures.push(texture);
+}
 
-    Ok(global_textures.len() - 1)
+impl TextureLoader for HeadlessTextureLoader {
+    fn assets(&self) -> &dyn AssetSource {
+        self.assets.as_ref()
+    }
+
+    fn load_texture(&mut self, file_path: &str) -> Result<TextureRegion, String> {
+        self.next_region(file_path, 0, 0)
+    }
+
+    fn load_animation(
+        &mut self,
+        file_path: &str,
+        width: u32,
+        height: u32,
+        frames: u32,
+        _layout: AnimationLayout,
+    ) -> Result<Vec<TextureRegion>, String> {
+        let region = self.next_region(file_path, width, height)?;
+        Ok(vec![region; frames as usize])
+    }
 }
@@ -1,77 +1,67 @@
-use sdl3::{
-    render::{FRect, Texture, TextureCreator},
-    video::WindowContext,
-};
+use sdl3::render::{FPoint, FRect, Texture};
 
-use crate::game::render::load_animation;
+use crate::game::render::{TextureLoader, TextureRegion};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AnimationLayout {
     Horizontal,
     Vertical,
 }
 
-/// Animation frames are stored vertically
+/// Frames can sit anywhere on any texture, each one keeps its own source rect
 pub struct Animation {
-    texture_index: usize,
-    frames: usize,
-    frame_w: f32,
-    frame_h: f32,
+    frames: Vec<TextureRegion>,
+    /// Game units each frame is drawn away from the position, x points the way the sprite faces
+    offsets: Vec<FPoint>,
+    /// Game frames each sprite frame is shown for, without them every sprite lasts one
+    durations: Vec<usize>,
 }
 
 impl Animation {
-    pub fn new(texture_index: usize, frames: usize, frame_w: f32, frame_h: f32) -> Animation {
+    pub fn new(frames: Vec<TextureRegion>) -> Animation {
         Self {
-            texture_index,
             frames,
-            frame_w,
-            frame_h,
+            offsets: Vec::new(),
+            durations: Vec::new(),
         }
     }
 
-    pub fn load<'a>(
-        texture_creator: &'a TextureCreator<WindowContext>,
-        global_textures: &mut Vec<Texture<'a>>,
+    /// One offset per frame, sprites without them are centered on the position
+    pub fn with_offsets(mut self, offsets: Vec<FPoint>) -> Self {
+        self.offsets = offsets;
+        self
+    }
+
+    pub fn load(
+        loader: &mut dyn TextureLoader,
         file_path: &str,
         width: u32,
         height: u32,
         frames: u32,
         layout: AnimationLayout,
     ) -> Result<Self, String> {
-        let texture_index = load_animation(
-            texture_creator,
-            global_textures,
-            file_path,
-            width,
-            height,
-            frames,
-            layout,
-        )?;
-
-        Ok(Self::new(
-            texture_index,
-            frames as usize,
-            width as f32,
-            height as f32,
-        ))
+        let frames = loader.load_animation(file_path, width, height, frames, layout)?;
+        Ok(Self::new(frames))
     }
 
-    // pub fn width(&self) -> f32 {
-    //     self.frame_w
-    // }
-
-    // pub fn height(&self) -> f32 {
-    //     self.frame_h
-    // }
+    /// One duration per frame, each at least 1
+    pub fn with_durations(mut self, durations: Vec<usize>) -> Self {
+        self.durations = durations;
+        self
+    }
 
+    /// Game frames the animation lasts
     pub fn get_frame_count(&self) -> usize {
-        self.frames
+        if self.durations.is_empty() {
+            self.frames.len()
+        } else {
+            self.durations.iter().sum()
+        }
     }
 
     pub fn get_frame<'r>(&self, frame: usize, textures: &'r [Texture]) -> (&'r Texture<'r>, FRect) {
-        let frame = frame.min(self.frames - 1);
-        let src_rect = FRect::new(0.0, frame as f32 * self.frame_h, self.frame_w, self.frame_h);
-        (&textures[self.texture_index], src_rect)
+        let region = self.frames[self.held_index(frame)];
+        (&textures[region.texture], region.src)
     }
 
     pub fn get_frame_cycle<'r>(
@@ -79,8 +69,48 @@ impl Animation {
         frame: usize,
         textures: &'r [Texture],
     ) -> (&'r Texture<'r>, FRect) {
-        let frame = frame % self.frames;
-        let src_rect = FRect::new(0.0, frame as f32 * self.frame_h, self.frame_w, self.frame_h);
-        (&textures[self.texture_index], src_rect)
+        let region = self.frames[self.cycle_index(frame)];
+        (&textures[region.texture], region.src)
+    }
+
+    pub fn get_frame_offset(&self, frame: usize) -> FPoint {
+        self.offset(self.held_index(frame))
+    }
+
+    pub fn get_frame_cycle_offset(&self, frame: usize) -> FPoint {
+        self.offset(self.cycle_index(frame))
+    }
+
+    /// Sprite frame shown on game frame `frame`, the last one holds once the animation ends
+    fn held_index(&self, frame: usize) -> usize {
+        self.sprite_at(frame.min(self.get_frame_count() - 1))
+    }
+
+    /// Sprite frame shown on game frame `frame`, looping back to the start
+    fn cycle_index(&self, frame: usize) -> usize {
+        self.sprite_at(frame % self.get_frame_count())
+    }
+
+    /// `frame` has to be within the animation
+    fn sprite_at(&self, frame: usize) -> usize {
+        if self.durations.is_empty() {
+            return frame;
+        }
+
+        let mut end = 0;
+        for (index, duration) in self.durations.iter().enumerate() {
+            end += duration;
+            if frame < end {
+                return index;
+            }
+        }
+        self.frames.len() - 1
+    }
+
+    fn offset(&self, index: usize) -> FPoint {
+        self.offsets
+            .get(index)
+            .copied()
+            .unwrap_or(FPoint::new(0.0, 0.0))
     }
 }
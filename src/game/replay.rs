@@ -0,0 +1,178 @@
+use std::{path::PathBuf, time::SystemTime};
+
+use bincode::{BorrowDecode, Encode, config};
+
+use crate::game::{
+    CharacterPicks, GAME_VERSION, GameContext, GameState, Side,
+    input::{Inputs, RawInputs},
+};
+
+/// Finished local matches are saved here
+const REPLAY_DIR: &str = "./replays";
+const REPLAY_EXTENSION: &str = "replay";
+
+/// A local match as both players' inputs on every simulated frame, with what it was played with
+///
+/// The inputs are stored whole rather than as key presses, so playback doesn't depend on how the
+/// motions were parsed or on pauses in between
+#[derive(Encode, BorrowDecode)]
+pub struct Replay {
+    version: Vec<u8>,
+    character_configs: [String; 2],
+    palettes: [usize; 2],
+    player1_side_left: bool,
+    stage: usize,
+    frames: Vec<[RawInputs; 2]>,
+}
+
+impl Replay {
+    /// Empty recording of a match with the characters and stage currently loaded
+    pub fn new(context: &GameContext) -> Self {
+        Self {
+            version: GAME_VERSION.to_vec(),
+            character_configs: context.character_configs.clone(),
+            palettes: context.character_palettes,
+            player1_side_left: context.player1.start_side() == Side::Left,
+            stage: context.stage,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Adds the inputs the frame about to be simulated runs on
+    pub fn record(&mut self, state: &GameState) {
+        self.frames
+            .push([state.player1_inputs.to_raw(), state.player2_inputs.to_raw()]);
+    }
+
+    /// Puts the recorded inputs of `frame` into the state, false past the end of the replay
+    pub fn play_frame(&self, frame: usize, state: &mut GameState) -> bool {
+        let Some([player1, player2]) = self.frames.get(frame) else {
+            return false;
+        };
+        state.player1_inputs = Inputs::from_raw(player1);
+        state.player2_inputs = Inputs::from_raw(player2);
+        true
+    }
+
+    pub fn frames_len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Named after the time it's saved, so the newest sorts last
+    pub fn save(&self) -> Result<(), String> {
+        std::fs::create_dir_all(REPLAY_DIR)
+            .map_err(|err| format!("Failed to create '{REPLAY_DIR}': {err}"))?;
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|err| err.to_string())?
+            .as_secs();
+        let path = format!("{REPLAY_DIR}/{secs}.{REPLAY_EXTENSION}");
+        std::fs::write(&path, self.encode()?)
+            .map_err(|err| format!("Failed to write '{path}': {err}"))
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        bincode::encode_to_vec(self, config::standard())
+            .map_err(|err| format!("Failed to encode replay: {err}"))
+    }
+
+    /// `name` is what the errors call the replay
+    fn decode(bytes: &[u8], name: &str) -> Result<Self, String> {
+        let (replay, _len): (Self, usize) =
+            bincode::borrow_decode_from_slice(bytes, config::standard())
+                .map_err(|err| format!("Failed to parse '{name}': {err}"))?;
+        if replay.version != GAME_VERSION {
+            return Err(format!(
+                "'{name}' was recorded on another version of the game"
+            ));
+        }
+        Ok(replay)
+    }
+
+    /// Whether any replay has been saved, without loading one
+    pub fn any_saved() -> bool {
+        std::fs::read_dir(REPLAY_DIR).is_ok_and(|mut entries| {
+            entries.any(|entry| {
+                entry.is_ok_and(|entry| {
+                    entry
+                        .path()
+                        .extension()
+                        .is_some_and(|ext| ext == REPLAY_EXTENSION)
+                })
+            })
+        })
+    }
+
+    /// The most recently saved replay
+    pub fn load_latest() -> Result<Self, String> {
+        let path = std::fs::read_dir(REPLAY_DIR)
+            .map_err(|err| format!("Failed to open '{REPLAY_DIR}': {err}"))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == REPLAY_EXTENSION))
+            .max()
+            .ok_or_else(|| format!("No replays in '{REPLAY_DIR}'"))?;
+        Self::load(path)
+    }
+
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let bytes = std::fs::read(&path)
+            .map_err(|err| format!("Failed to open '{}': {err}", path.display()))?;
+        Self::decode(&bytes, &path.display().to_string())
+    }
+
+    /// Queues the recorded characters and stage, they load before the next scene starts
+    pub fn pick(&self, context: &mut GameContext) -> Result<(), String> {
+        let roster_index = |config: &String| {
+            context
+                .roster
+                .iter()
+                .position(|path| path == config)
+                .ok_or_else(|| format!("Replay character '{config}' isn't in the roster"))
+        };
+        let picks = CharacterPicks {
+            player1: roster_index(&self.character_configs[0])?,
+            player2: roster_index(&self.character_configs[1])?,
+            player1_palette: self.palettes[0],
+            player2_palette: self.palettes[1],
+            player1_side: if self.player1_side_left {
+                Side::Left
+            } else {
+                Side::Right
+            },
+        };
+        context.pick_characters(picks)?;
+        context.pick_stage(self.stage)
+    }
+}
+
+#[test]
+fn test_replay_round_trip() {
+    use crate::game::input::{ButtonFlag, Direction, Motion, PLAYER1_BINDINGS, new_inputs};
+
+    let (_, mut inputs) = new_inputs(PLAYER1_BINDINGS);
+    inputs.update(
+        ButtonFlag::L,
+        (Direction::Down, Motion::NONE, ButtonFlag::L),
+    );
+    let replay = Replay {
+        version: GAME_VERSION.to_vec(),
+        character_configs: [String::from("a.json"), String::from("b.json")],
+        palettes: [0, 1],
+        player1_side_left: false,
+        stage: 1,
+        frames: vec![[inputs.to_raw(), inputs.to_raw()]],
+    };
+
+    let decoded = Replay::decode(&replay.encode().unwrap(), "test").unwrap();
+
+    assert_eq!(decoded.character_configs, replay.character_configs);
+    assert_eq!(decoded.palettes, replay.palettes);
+    assert!(!decoded.player1_side_left);
+    assert_eq!(decoded.stage, replay.stage);
+    assert_eq!(decoded.frames_len(), 1);
+    assert_eq!(Inputs::from_raw(&decoded.frames[0][1]), inputs);
+
+    let mut old = replay;
+    old.version = Vec::new();
+    assert!(Replay::decode(&old.encode().unwrap(), "test").is_err());
+}
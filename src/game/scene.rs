@@ -1,25 +1,64 @@
+use std::path::PathBuf;
+
 use sdl3::{
+    keyboard::Keycode,
     render::{Canvas, Texture},
     video::Window,
 };
 
 use crate::game::{
     GameContext, GameState, PlayerInputs,
+    render::TextureRegion,
     scene::{
-        connecting::Connecting, hosting::Hosting, local_play::LocalPlay, main_menu::MainMenu,
-        matching::Matching, online_play::OnlinePlay, spectate_ai::SpectateAi, verses_ai::VersesAi,
+        arcade::Arcade,
+        character_select::{AfterSelect, CharacterSelect},
+        connecting::Connecting,
+        hosting::Hosting,
+        local_play::LocalPlay,
+        main_menu::MainMenu,
+        matching::Matching,
+        model_select::ModelSelect,
+        online_play::OnlinePlay,
+        replay_viewer::ReplayViewer,
+        settings_menu::SettingsMenu,
+        spectate_ai::SpectateAi,
+        stage_select::StageSelect,
+        tutorial::Tutorial,
+        verses_ai::VersesAi,
+        victory::Victory,
+        winner_stays::WinnerStays,
     },
 };
 
+mod arcade;
+mod character_select;
 mod connecting;
 pub mod gameplay;
 mod hosting;
 mod local_play;
 mod main_menu;
 mod matching;
+mod model_select;
 mod online_play;
+mod replay_viewer;
+mod settings_menu;
 mod spectate_ai;
+mod stage_select;
+mod tutorial;
 mod verses_ai;
+mod victory;
+mod winner_stays;
+
+/// Textures drawn over or in place of the plain UI shapes, anything left out keeps the shape
+#[derive(Clone, Copy, Default)]
+pub struct UiSkin {
+    /// Replaces the `>` beside the picked main menu option
+    pub menu_cursor: Option<TextureRegion>,
+    /// Stretched over player1's health bar, mirrored for player2
+    pub health_bar_frame: Option<TextureRegion>,
+    /// Drawn behind the timer digits
+    pub timer: Option<TextureRegion>,
+}
 
 pub trait Scene {
     fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState);
@@ -29,7 +68,11 @@ pub trait Scene {
         inputs: &mut PlayerInputs,
         state: &mut GameState,
     ) -> Result<(), String>;
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String>;
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String>;
     fn render(
         &self,
         canvas: &mut Canvas<Window>,
@@ -40,6 +83,16 @@ pub trait Scene {
     fn exit(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState);
 }
 
+/// What Escape does in a scene
+pub enum EscapeAction {
+    /// Opens or closes the scene's pause menu
+    Pause,
+    /// Leaves for the previous menu straight away
+    Back(Scenes),
+    /// Asks before leaving for the main menu, or before quitting from it
+    Confirm(&'static str),
+}
+
 pub enum Scenes {
     MainMenu(MainMenu),
     LocalPlay(LocalPlay),
@@ -49,9 +102,16 @@ pub enum Scenes {
     Matching(Matching),
     VersesAi(VersesAi),
     SpectateAi(SpectateAi),
+    ModelSelect(ModelSelect),
+    Arcade(Arcade),
+    CharacterSelect(CharacterSelect),
+    Settings(SettingsMenu),
+    StageSelect(StageSelect),
+    ReplayViewer(ReplayViewer),
+    Tutorial(Tutorial),
+    Victory(Victory),
+    WinnerStays(WinnerStays),
     //RoundEnd,
-    //WinScreen,
-    //Settings,
 }
 
 impl Scene for Scenes {
@@ -65,6 +125,17 @@ impl Scene for Scenes {
             Self::Matching(matching) => matching.enter(context, inputs, state),
             Self::VersesAi(verses_ai) => verses_ai.enter(context, inputs, state),
             Self::SpectateAi(spectate_ai) => spectate_ai.enter(context, inputs, state),
+            Self::ModelSelect(model_select) => model_select.enter(context, inputs, state),
+            Self::Arcade(arcade) => arcade.enter(context, inputs, state),
+            Self::CharacterSelect(character_select) => {
+                character_select.enter(context, inputs, state)
+            }
+            Self::Settings(settings_menu) => settings_menu.enter(context, inputs, state),
+            Self::StageSelect(stage_select) => stage_select.enter(context, inputs, state),
+            Self::ReplayViewer(replay_viewer) => replay_viewer.enter(context, inputs, state),
+            Self::Tutorial(tutorial) => tutorial.enter(context, inputs, state),
+            Self::Victory(victory) => victory.enter(context, inputs, state),
+            Self::WinnerStays(winner_stays) => winner_stays.enter(context, inputs, state),
         }
     }
 
@@ -84,10 +155,25 @@ impl Scene for Scenes {
             Self::Matching(matching) => matching.handle_input(context, inputs, state),
             Self::VersesAi(verses_ai) => verses_ai.handle_input(context, inputs, state),
             Self::SpectateAi(spectate_ai) => spectate_ai.handle_input(context, inputs, state),
+            Self::ModelSelect(model_select) => model_select.handle_input(context, inputs, state),
+            Self::Arcade(arcade) => arcade.handle_input(context, inputs, state),
+            Self::CharacterSelect(character_select) => {
+                character_select.handle_input(context, inputs, state)
+            }
+            Self::Settings(settings_menu) => settings_menu.handle_input(context, inputs, state),
+            Self::StageSelect(stage_select) => stage_select.handle_input(context, inputs, state),
+            Self::ReplayViewer(replay_viewer) => replay_viewer.handle_input(context, inputs, state),
+            Self::Tutorial(tutorial) => tutorial.handle_input(context, inputs, state),
+            Self::Victory(victory) => victory.handle_input(context, inputs, state),
+            Self::WinnerStays(winner_stays) => winner_stays.handle_input(context, inputs, state),
         }
     }
 
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
         match self {
             Self::MainMenu(main_menu) => main_menu.update(context, state),
             Self::LocalPlay(local_play) => local_play.update(context, state),
@@ -97,6 +183,15 @@ impl Scene for Scenes {
             Self::Matching(matching) => matching.update(context, state),
             Self::VersesAi(verses_ai) => verses_ai.update(context, state),
             Self::SpectateAi(spectate_ai) => spectate_ai.update(context, state),
+            Self::ModelSelect(model_select) => model_select.update(context, state),
+            Self::Arcade(arcade) => arcade.update(context, state),
+            Self::CharacterSelect(character_select) => character_select.update(context, state),
+            Self::Settings(settings_menu) => settings_menu.update(context, state),
+            Self::StageSelect(stage_select) => stage_select.update(context, state),
+            Self::ReplayViewer(replay_viewer) => replay_viewer.update(context, state),
+            Self::Tutorial(tutorial) => tutorial.update(context, state),
+            Self::Victory(victory) => victory.update(context, state),
+            Self::WinnerStays(winner_stays) => winner_stays.update(context, state),
         }
     }
 
@@ -124,6 +219,27 @@ impl Scene for Scenes {
             Self::SpectateAi(spectate_ai) => {
                 spectate_ai.render(canvas, global_textures, context, state)
             }
+            Self::ModelSelect(model_select) => {
+                model_select.render(canvas, global_textures, context, state)
+            }
+            Self::Arcade(arcade) => arcade.render(canvas, global_textures, context, state),
+            Self::CharacterSelect(character_select) => {
+                character_select.render(canvas, global_textures, context, state)
+            }
+            Self::Settings(settings_menu) => {
+                settings_menu.render(canvas, global_textures, context, state)
+            }
+            Self::StageSelect(stage_select) => {
+                stage_select.render(canvas, global_textures, context, state)
+            }
+            Self::ReplayViewer(replay_viewer) => {
+                replay_viewer.render(canvas, global_textures, context, state)
+            }
+            Self::Tutorial(tutorial) => tutorial.render(canvas, global_textures, context, state),
+            Self::Victory(victory) => victory.render(canvas, global_textures, context, state),
+            Self::WinnerStays(winner_stays) => {
+                winner_stays.render(canvas, global_textures, context, state)
+            }
         }
     }
 
@@ -137,6 +253,17 @@ impl Scene for Scenes {
             Self::Matching(matching) => matching.exit(context, inputs, state),
             Self::VersesAi(verses_ai) => verses_ai.exit(context, inputs, state),
             Self::SpectateAi(spectate_ai) => spectate_ai.exit(context, inputs, state),
+            Self::ModelSelect(model_select) => model_select.exit(context, inputs, state),
+            Self::Arcade(arcade) => arcade.exit(context, inputs, state),
+            Self::CharacterSelect(character_select) => {
+                character_select.exit(context, inputs, state)
+            }
+            Self::Settings(settings_menu) => settings_menu.exit(context, inputs, state),
+            Self::StageSelect(stage_select) => stage_select.exit(context, inputs, state),
+            Self::ReplayViewer(replay_viewer) => replay_viewer.exit(context, inputs, state),
+            Self::Tutorial(tutorial) => tutorial.exit(context, inputs, state),
+            Self::Victory(victory) => victory.exit(context, inputs, state),
+            Self::WinnerStays(winner_stays) => winner_stays.exit(context, inputs, state),
         }
     }
 }
@@ -146,9 +273,139 @@ impl Scenes {
         Self::MainMenu(MainMenu::new())
     }
 
+    /// Character select that heads to matchmaking once the pick is in
+    pub fn online_select() -> Self {
+        Self::CharacterSelect(CharacterSelect::new(AfterSelect::Online))
+    }
+
+    /// Plays back the replay saved at `path`
+    pub fn replay(context: &mut GameContext, path: PathBuf) -> Result<Self, String> {
+        Ok(Self::ReplayViewer(ReplayViewer::open(context, path)?))
+    }
+
     pub fn reset(context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) -> Self {
         let mut scene = Scenes::new();
         scene.enter(context, inputs, state);
         scene
     }
+
+    /// Track looped while the scene is up, matches play their stage's
+    pub fn music<'a>(&self, context: &'a GameContext) -> Option<&'a str> {
+        match self {
+            Self::LocalPlay(_)
+            | Self::OnlinePlay(_)
+            | Self::VersesAi(_)
+            | Self::SpectateAi(_)
+            | Self::Arcade(_)
+            | Self::ReplayViewer(_)
+            | Self::Tutorial(_)
+            | Self::Victory(_)
+            | Self::WinnerStays(_) => context.stage().music(),
+            _ => context.menu_music.as_deref(),
+        }
+    }
+
+    /// Music is turned down while a super flash or knockout plays out
+    pub fn ducks_music(&self, state: &GameState) -> bool {
+        match self {
+            Self::LocalPlay(local_play) => local_play.cinematic(state),
+            Self::OnlinePlay(online_play) => online_play.cinematic(state),
+            Self::VersesAi(verses_ai) => verses_ai.cinematic(state),
+            Self::SpectateAi(spectate_ai) => spectate_ai.cinematic(state),
+            Self::Arcade(arcade) => arcade.cinematic(state),
+            Self::ReplayViewer(replay_viewer) => replay_viewer.cinematic(state),
+            Self::Tutorial(tutorial) => tutorial.cinematic(state),
+            Self::WinnerStays(winner_stays) => winner_stays.cinematic(state),
+            _ => false,
+        }
+    }
+
+    /// Pauses or unpauses the scene, false if it can't be paused
+    pub fn toggle_pause(&mut self) -> bool {
+        match self {
+            Self::LocalPlay(local_play) => {
+                local_play.toggle_pause();
+                true
+            }
+            Self::WinnerStays(winner_stays) => {
+                winner_stays.toggle_pause();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Gameplay pauses or asks before leaving, menus go back a step
+    pub fn escape(&self) -> EscapeAction {
+        match self {
+            Self::MainMenu(_) => EscapeAction::Confirm("Quit the game?"),
+            Self::LocalPlay(_) | Self::WinnerStays(_) => EscapeAction::Pause,
+            Self::OnlinePlay(_) => {
+                EscapeAction::Confirm("Leave the match? Your opponent is dropped")
+            }
+            Self::VersesAi(_) | Self::Arcade(_) | Self::Tutorial(_) => {
+                EscapeAction::Confirm("Leave the match?")
+            }
+            Self::StageSelect(_) => EscapeAction::Back(Self::CharacterSelect(
+                CharacterSelect::new(AfterSelect::LocalPlay),
+            )),
+            Self::Hosting(_)
+            | Self::Connecting(_)
+            | Self::Matching(_)
+            | Self::SpectateAi(_)
+            | Self::ModelSelect(_)
+            | Self::CharacterSelect(_)
+            | Self::Settings(_)
+            | Self::ReplayViewer(_)
+            | Self::Victory(_) => EscapeAction::Back(Self::new()),
+        }
+    }
+
+    /// Scenes players are fighting in, they wait on an unplugged pad
+    pub fn in_match(&self) -> bool {
+        matches!(
+            self,
+            Self::LocalPlay(_)
+                | Self::OnlinePlay(_)
+                | Self::VersesAi(_)
+                | Self::Arcade(_)
+                | Self::Tutorial(_)
+                | Self::WinnerStays(_)
+        )
+    }
+
+    /// Online scenes keep ticking under prompts, the rest wait on the answer
+    pub fn online(&self) -> bool {
+        matches!(self, Self::OnlinePlay(_))
+    }
+
+    /// Hands a pressed key to a scene waiting on one, true if it was taken
+    pub fn capture_key(&mut self, key: Keycode) -> bool {
+        match self {
+            Self::Settings(settings_menu) => settings_menu.capture_key(key),
+            Self::SpectateAi(spectate_ai) => spectate_ai.capture_key(key),
+            _ => false,
+        }
+    }
+
+    /// Frames re-simulated by rollbacks in this scene so far
+    pub fn rolled_back_frames(&self) -> usize {
+        match self {
+            Self::OnlinePlay(online_play) => online_play.rolled_back(),
+            _ => 0,
+        }
+    }
+
+    /// Local play doubles as training mode, its speed can be slowed or frozen outside the pause menu
+    pub fn training(&self) -> bool {
+        match self {
+            Self::LocalPlay(local_play) => !local_play.is_paused(),
+            _ => false,
+        }
+    }
+
+    /// Local play doubles as training mode, so edits to the character configs show up live
+    pub fn hot_reloads_characters(&self) -> bool {
+        matches!(self, Self::LocalPlay(_))
+    }
 }
@@ -0,0 +1,109 @@
+use crate::game::{
+    GameContext, GameState, PlayerInputs,
+    scene::{Scene, Scenes, main_menu::MainMenu, verses_ai::VersesAi},
+};
+
+const LADDER_MODEL_EXTENSION: &str = "safetensors";
+
+/// Fights the training ladder checkpoints oldest first, a loss sends the player back to the menu
+pub struct Arcade {
+    rungs: Vec<String>,
+    rung: usize,
+    fight: VersesAi,
+}
+
+impl Scene for Arcade {
+    fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
+        if cfg!(feature = "debug") {
+            println!(
+                "Ladder rung {}/{}: '{}'",
+                self.rung + 1,
+                self.rungs.len(),
+                self.rungs[self.rung]
+            );
+        }
+        self.fight.enter(context, inputs, state);
+    }
+
+    fn handle_input(
+        &mut self,
+        context: &GameContext,
+        inputs: &mut PlayerInputs,
+        state: &mut GameState,
+    ) -> Result<(), String> {
+        self.fight.handle_input(context, inputs, state)
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        if self.fight.update(context, state)?.is_none() {
+            return Ok(None);
+        }
+
+        let next_rung = self.rung + 1;
+        if self.fight.player_won() && next_rung < self.rungs.len() {
+            let rungs = std::mem::take(&mut self.rungs);
+            let arcade = Self::at_rung(rungs, next_rung, context.agent_action_repeat)?;
+            Ok(Some(Scenes::Arcade(arcade)))
+        } else {
+            Ok(Some(Scenes::MainMenu(MainMenu::new())))
+        }
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        self.fight.render(canvas, global_textures, context, state)
+    }
+
+    fn exit(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
+        self.fight.exit(context, inputs, state);
+    }
+}
+
+impl Arcade {
+    /// Starts at the oldest checkpoint, falls back to the versus model if no ladder has been trained
+    pub fn new(context: &GameContext) -> Result<Self, String> {
+        let mut rungs = list_rungs(&context.ladder_dirpath);
+        if rungs.is_empty() {
+            rungs.push(context.verses_agent_filepath(&context.player2).to_string());
+        }
+
+        Self::at_rung(rungs, 0, context.agent_action_repeat)
+    }
+
+    fn at_rung(rungs: Vec<String>, rung: usize, action_repeat: usize) -> Result<Self, String> {
+        let fight = VersesAi::new(&rungs[rung], action_repeat)?;
+        Ok(Self { rungs, rung, fight })
+    }
+
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.fight.cinematic(state)
+    }
+}
+
+/// Checkpoints are named by training epoch, so sorting by name orders them weakest first
+fn list_rungs(ladder_dir: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(ladder_dir) else {
+        return Vec::new();
+    };
+
+    let mut rungs: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == LADDER_MODEL_EXTENSION)
+        })
+        .filter_map(|path| path.to_str().map(String::from))
+        .collect();
+    rungs.sort();
+    rungs
+}
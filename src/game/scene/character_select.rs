@@ -0,0 +1,287 @@
+use rand::Rng;
+use sdl3::{pixels::Color, render::FRect};
+
+use crate::game::{
+    CharacterPicks, GameContext, GameState, PlayerInputs, Side,
+    input::{ButtonFlag, Direction, Inputs},
+    net::Lineup,
+    render::text::{draw_text_centered, text_height},
+    scene::{
+        Scene, Scenes,
+        matching::Matching,
+        stage_select::StageSelect,
+        winner_stays::{Session, WinnerStays},
+    },
+};
+
+const PORTRAIT_SIZE: f32 = 1.0 / 10.0;
+const PORTRAIT_GAP: f32 = 1.0 / 40.0;
+const PLAYER_COLORS: [Color; 2] = [Color::RED, Color::BLUE];
+/// Palette slots up and down cycle through, they wrap around however many the character has
+const PALETTE_CHOICES: usize = 4;
+
+/// Where the players go once everyone has picked
+pub enum AfterSelect {
+    LocalPlay,
+    /// Only the local player picks, the peer's pick arrives during the handshake
+    Online,
+    /// Both pick, except the champion who keeps their character, and play on the same stage
+    WinnerStays(Session),
+}
+
+/// L confirms, M takes the pick back, H swaps which side player1 starts on, up and down pick a palette
+///
+/// The portrait after the roster picks a random character once confirmed
+pub struct CharacterSelect {
+    next: AfterSelect,
+    cursors: [usize; 2],
+    palettes: [usize; 2],
+    confirmed: [bool; 2],
+    last_dirs: [Direction; 2],
+    player1_side: Side,
+}
+
+impl Scene for CharacterSelect {
+    fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, _state: &mut GameState) {
+        inputs.local_key_mapping();
+        if let AfterSelect::WinnerStays(Session {
+            champion: Some(champion),
+            ..
+        }) = self.next
+        {
+            let config = &context.character_configs[champion];
+            self.cursors[champion] = context
+                .roster
+                .iter()
+                .position(|path| path == config)
+                .unwrap_or_default();
+            self.palettes[champion] = context.character_palettes[champion];
+            self.confirmed[champion] = true;
+            self.player1_side = context.player1.start_side();
+        }
+    }
+
+    fn handle_input(
+        &mut self,
+        _context: &GameContext,
+        inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) -> Result<(), String> {
+        inputs.update_player1();
+        match self.next {
+            AfterSelect::LocalPlay | AfterSelect::WinnerStays(_) => inputs.update_player2(),
+            AfterSelect::Online => inputs.skip_player2(),
+        }
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        let roster_len = context.roster_len();
+        let champion = match self.next {
+            AfterSelect::WinnerStays(session) => session.champion,
+            _ => None,
+        };
+        // One past the roster is the random pick
+        if champion != Some(0) {
+            self.update_player(0, &state.player1_inputs, roster_len + 1);
+        }
+        if !matches!(self.next, AfterSelect::Online) {
+            if champion != Some(1) {
+                self.update_player(1, &state.player2_inputs, roster_len + 1);
+            }
+            if ButtonFlag::H.intersects(state.player1_inputs.just_pressed_buttons()) {
+                self.player1_side = self.player1_side.opposite();
+            }
+        }
+
+        match self.next {
+            AfterSelect::LocalPlay if self.confirmed.iter().all(|confirmed| *confirmed) => {
+                context.pick_characters(CharacterPicks {
+                    player1: self.pick(0, roster_len),
+                    player2: self.pick(1, roster_len),
+                    player1_palette: self.palettes[0],
+                    player2_palette: self.palettes[1],
+                    player1_side: self.player1_side,
+                })?;
+                Ok(Some(Scenes::StageSelect(StageSelect::new(context))))
+            }
+            AfterSelect::WinnerStays(session)
+                if self.confirmed.iter().all(|confirmed| *confirmed) =>
+            {
+                context.pick_characters(CharacterPicks {
+                    player1: self.pick(0, roster_len),
+                    player2: self.pick(1, roster_len),
+                    player1_palette: self.palettes[0],
+                    player2_palette: self.palettes[1],
+                    player1_side: self.player1_side,
+                })?;
+                Ok(Some(Scenes::WinnerStays(WinnerStays::new(session))))
+            }
+            AfterSelect::Online if self.confirmed[0] => {
+                let lineup = Lineup::new(context, self.pick(0, roster_len));
+                Ok(Some(Scenes::Matching(Matching::new(
+                    &context.matchmaking_server,
+                    lineup,
+                )?)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        _global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        _state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        let (w, h) = canvas.window().size();
+        let w = w as f32;
+        let h = h as f32;
+
+        let size = w * PORTRAIT_SIZE;
+        let gap = w * PORTRAIT_GAP;
+        // The random pick sits at the end
+        let roster_len = context.roster_len() as f32 + 1.0;
+        let x_start = (w - roster_len * size - (roster_len - 1.0) * gap) / 2.0;
+        let y = (h - size) / 2.0;
+        let portrait =
+            |index: usize| FRect::new(x_start + index as f32 * (size + gap), y, size, size);
+
+        canvas.set_draw_color(Color::GRAY);
+        for index in 0..context.roster_len() {
+            canvas.fill_rect(portrait(index))?;
+        }
+        let random = portrait(context.roster_len());
+        canvas.set_draw_color(Color::RGB(64, 64, 64));
+        canvas.fill_rect(random)?;
+        let scale = size / 40.0;
+        draw_text_centered(
+            canvas,
+            "?",
+            random.x + size / 2.0,
+            random.y + (size - text_height(scale)) / 2.0,
+            scale,
+            Color::WHITE,
+        )?;
+
+        let players = match self.next {
+            AfterSelect::LocalPlay | AfterSelect::WinnerStays(_) => 2,
+            AfterSelect::Online => 1,
+        };
+        for (player, color) in PLAYER_COLORS.into_iter().enumerate().take(players) {
+            let rect = portrait(self.cursors[player]);
+            // Player2's outline sits inside player1's so both show on the same portrait
+            let inset = player as f32 * size / 10.0;
+            let rect = FRect::new(
+                rect.x + inset,
+                rect.y + inset,
+                rect.w - inset * 2.0,
+                rect.h - inset * 2.0,
+            );
+            canvas.set_draw_color(color);
+            if self.confirmed[player] {
+                canvas.fill_rect(rect)?;
+            } else {
+                canvas.draw_rect(rect)?;
+            }
+
+            // Pips under the picked portrait show the palette slot
+            let pip = size / 8.0;
+            let pip_y = y + size + pip * (1.0 + player as f32 * 2.0);
+            for slot in 0..PALETTE_CHOICES {
+                let pip_rect = FRect::new(
+                    portrait(self.cursors[player]).x + slot as f32 * pip * 2.0,
+                    pip_y,
+                    pip,
+                    pip,
+                );
+                if slot == self.palettes[player] {
+                    canvas.fill_rect(pip_rect)?;
+                } else {
+                    canvas.draw_rect(pip_rect)?;
+                }
+            }
+        }
+
+        if let AfterSelect::WinnerStays(session) = self.next {
+            let label = session
+                .label(&context.strings)
+                .unwrap_or_else(|| context.strings.get("WINNER STAYS").to_string());
+            let scale = h / 160.0;
+            draw_text_centered(canvas, &label, w / 2.0, h / 6.0, scale, Color::YELLOW)?;
+        }
+
+        if !matches!(self.next, AfterSelect::Online) {
+            // Bars on the screen edges show which side each player starts on
+            let bar_w = w / 40.0;
+            let (player1_x, player2_x) = match self.player1_side {
+                Side::Left => (0.0, w - bar_w),
+                Side::Right => (w - bar_w, 0.0),
+            };
+            canvas.set_draw_color(PLAYER_COLORS[0]);
+            canvas.fill_rect(FRect::new(player1_x, 0.0, bar_w, h))?;
+            canvas.set_draw_color(PLAYER_COLORS[1]);
+            canvas.fill_rect(FRect::new(player2_x, 0.0, bar_w, h))?;
+        }
+
+        Ok(())
+    }
+
+    fn exit(&mut self, _context: &GameContext, _inputs: &mut PlayerInputs, _state: &mut GameState) {
+    }
+}
+
+impl CharacterSelect {
+    pub fn new(next: AfterSelect) -> Self {
+        Self {
+            next,
+            cursors: [0; 2],
+            palettes: [0; 2],
+            confirmed: [false; 2],
+            last_dirs: [Direction::Neutral; 2],
+            player1_side: Side::Left,
+        }
+    }
+
+    /// The player's roster pick, the random portrait rolls one
+    fn pick(&self, player: usize, roster_len: usize) -> usize {
+        if self.cursors[player] < roster_len {
+            self.cursors[player]
+        } else {
+            rand::rng().random_range(0..roster_len)
+        }
+    }
+
+    /// `portraits` counts the random one
+    fn update_player(&mut self, player: usize, inputs: &Inputs, portraits: usize) {
+        let just_pressed = inputs.just_pressed_buttons();
+        if ButtonFlag::M.intersects(just_pressed) {
+            self.confirmed[player] = false;
+        }
+        if ButtonFlag::L.intersects(just_pressed) {
+            self.confirmed[player] = true;
+        }
+
+        let held_dir = inputs.dir();
+        if held_dir != self.last_dirs[player] && !self.confirmed[player] {
+            let scroll_dif = match held_dir {
+                Direction::Right => 1,
+                Direction::Left => portraits - 1,
+                _ => 0,
+            };
+            self.cursors[player] = (self.cursors[player] + scroll_dif) % portraits;
+            let palette_dif = match held_dir {
+                Direction::Up => 1,
+                Direction::Down => PALETTE_CHOICES - 1,
+                _ => 0,
+            };
+            self.palettes[player] = (self.palettes[player] + palette_dif) % PALETTE_CHOICES;
+        }
+        self.last_dirs[player] = held_dir;
+    }
+}
@@ -1,5 +1,5 @@
 use crate::game::{
-    GameContext, GameState, PlayerInputs,
+    CharacterPicks, GameContext, GameState, PlayerInputs, Side,
     net::client::UdpClient,
     scene::{Scene, Scenes, online_play::OnlinePlay},
 };
@@ -25,15 +25,29 @@ impl Scene for Connecting {
         Ok(())
     }
 
-    fn update(&mut self, _context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
         if let Some(connection) = self
             .client
             .update(self.current_frame)
             .map_err(|err| err.to_string())?
         {
+            // Client always plays on the right
+            context.pick_characters(CharacterPicks {
+                player1: self.client.peer_character(),
+                player2: self.client.local_character(),
+                player1_palette: 0,
+                player2_palette: 0,
+                player1_side: Side::Left,
+            })?;
+            context.pick_stage(self.client.stage())?;
             Ok(Some(Scenes::OnlinePlay(OnlinePlay::new(
                 connection,
-                crate::game::Side::Right,
+                Side::Right,
+                self.client.netplay(),
                 state,
             ))))
         } else {
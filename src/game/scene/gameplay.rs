@@ -3,17 +3,164 @@ mod round_start;
 
 use sdl3::{
     pixels::Color,
-    render::{Canvas, FRect, Texture},
+    render::{Canvas, FPoint, FRect, Texture},
     video::Window,
 };
 
 use crate::game::{
-    FRAME_RATE, GameContext, GameState, SCORE_TO_WIN,
-    render::animation::Animation,
+    FRAME_RATE, GameContext, GameState, SCORE_TO_WIN, Side,
+    character::MAX_METER,
+    render::{
+        Framing, TextureRegion, draw_clash_spark_system, draw_damage_number_system,
+        text::{draw_text, draw_text_centered, text_height, text_width},
+    },
     scene::gameplay::{during_round::DuringRound, round_start::RoundStart},
 };
 
 const ROUND_LEN: usize = 99;
+const RECOVERABLE_HEALTH_COLOR: Color = Color::RGB(230, 230, 230);
+const MAX_FRAME_ADVANTAGE_PIPS: u32 = 30;
+const METER_COLOR: Color = Color::RGB(40, 200, 255);
+const DIZZY_COLOR: Color = Color::RGB(255, 140, 0);
+/// Frames a newly won round's icon takes to grow in
+const WIN_POP_FRAMES: usize = 20;
+/// Laid over the stage while either player's super flash runs
+const SUPER_FLASH_SHADE: Color = Color::RGBA(0, 0, 0, 160);
+/// Dims the match behind the move list and the pause menu
+pub const MOVE_LIST_SHADE: Color = Color::RGBA(0, 0, 0, 200);
+
+/// Where the HUD sits, positions and sizes are shares of the screen and player2's side mirrors player1's
+#[derive(Clone, Copy)]
+pub struct HudLayout {
+    /// Player1's health bar
+    pub health_bar: FRect,
+    /// Middle of the timer's top edge
+    pub timer: FPoint,
+    /// Timer font pixel size as a share of the screen height
+    pub timer_size: f32,
+    /// Top right corner of player1's win icons
+    pub score: FPoint,
+    /// Win icon side as a share of the screen height
+    pub score_size: f32,
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self {
+            health_bar: FRect::new(0.0, 0.0, 0.4, 0.05),
+            timer: FPoint::new(0.5, 1.0 / 90.0),
+            timer_size: 1.0 / 90.0,
+            score: FPoint::new(0.4, 1.0 / 9.0),
+            score_size: 0.03,
+        }
+    }
+}
+
+impl HudLayout {
+    /// Both health bars in screen pixels
+    fn health_bars(&self, screen_w: u32, screen_h: u32) -> [FRect; 2] {
+        let (screen_w, screen_h) = (screen_w as f32, screen_h as f32);
+        let bar = self.health_bar;
+        let w = bar.w * screen_w;
+        let h = bar.h * screen_h;
+        let y = bar.y * screen_h;
+        [
+            FRect::new(bar.x * screen_w, y, w, h),
+            FRect::new((1.0 - bar.x - bar.w) * screen_w, y, w, h),
+        ]
+    }
+}
+
+/// How a finished match went, for the victory screen
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MatchResults {
+    pub score: (u32, u32),
+    pub stats: MatchStats,
+}
+
+/// Totals kept over a whole match, player1's first
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct MatchStats {
+    /// Most hits landed in one combo
+    pub max_combo: [u32; 2],
+    /// Health taken off the opponent, chip damage included
+    pub damage: [f32; 2],
+    /// Throws landed, teched ones don't count
+    pub throws: [u32; 2],
+    /// Hits landed while the opponent was stuck in a move of their own
+    pub punishes: [u32; 2],
+    /// Frames each punished opening had been open for, summed
+    punish_frames: [u32; 2],
+    /// Frames the opponent has been stuck in a move of their own, None while they can act or are stunned
+    openings: [Option<u32>; 2],
+}
+
+impl MatchStats {
+    /// Credits the health each player lost since `hp_before` to the other one
+    fn record(&mut self, context: &GameContext, hp_before: [f32; 2], state: &GameState) {
+        let player1_lost = (hp_before[0] - state.player1.hp()).max(0.0);
+        let player2_lost = (hp_before[1] - state.player2.hp()).max(0.0);
+        self.damage[0] += player2_lost;
+        self.damage[1] += player1_lost;
+        self.max_combo[0] = self.max_combo[0].max(state.player2.combo_hits());
+        self.max_combo[1] = self.max_combo[1].max(state.player1.combo_hits());
+
+        let players = [
+            (&state.player1, &context.player1),
+            (&state.player2, &context.player2),
+        ];
+        for player in 0..2 {
+            let opponent = 1 - player;
+            let (opponent_state, opponent_context) = players[opponent];
+            let hit = opponent_state.hp() < hp_before[opponent]
+                && !opponent_state.took_chip(opponent_context);
+            if let (true, Some(frames)) = (hit, self.openings[player]) {
+                self.punishes[player] += 1;
+                self.punish_frames[player] += frames;
+            }
+            let committed = !opponent_state.actionable(opponent_context)
+                && !opponent_state.stunned(opponent_context);
+            self.openings[player] =
+                committed.then(|| self.openings[player].map_or(0, |frames| frames + 1));
+        }
+    }
+
+    /// Average frames `player` took to punish an opening, None before their first punish
+    pub fn reaction(&self, player: usize) -> Option<f32> {
+        (self.punishes[player] > 0)
+            .then(|| self.punish_frames[player] as f32 / self.punishes[player] as f32)
+    }
+}
+
+/// Icons for rounds won and still to win
+#[derive(Clone, Copy)]
+pub struct WinIcons {
+    pub won: TextureRegion,
+    pub empty: TextureRegion,
+}
+
+/// Grows the icons of the latest round's winners in over `WIN_POP_FRAMES`
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct WinPop {
+    winners: (bool, bool),
+    frames: usize,
+}
+
+impl WinPop {
+    pub fn new(winners: (bool, bool)) -> Self {
+        Self { winners, frames: 0 }
+    }
+
+    pub fn tick(&mut self) {
+        self.frames = (self.frames + 1).min(WIN_POP_FRAMES);
+    }
+
+    /// Size of the newest icon against the others, it overshoots a little before settling
+    fn icon_scale(&self) -> f32 {
+        let progress = self.frames as f32 / WIN_POP_FRAMES as f32;
+        progress + (progress * std::f32::consts::PI).sin() * 0.5
+    }
+}
 
 pub trait GameplayScene {
     fn enter(&mut self, context: &GameContext, state: &mut GameState);
@@ -32,13 +179,23 @@ pub trait GameplayScene {
 pub enum GameplayScenes {
     RoundStart(RoundStart),
     DuringRound(DuringRound),
-    Exit,
+    /// The match is over
+    Exit(MatchResults),
 }
 
 impl GameplayScenes {
     pub fn new_round_start(score: (u32, u32)) -> GameplayScenes {
         Self::RoundStart(RoundStart::new(score))
     }
+
+    /// A super flash or knockout slow motion is playing
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        let super_flash = state.player1.super_flash() > 0 || state.player2.super_flash() > 0;
+        match self {
+            Self::DuringRound(during_round) => super_flash || during_round.in_ko(),
+            _ => false,
+        }
+    }
 }
 
 impl GameplayScene for GameplayScenes {
@@ -46,7 +203,7 @@ impl GameplayScene for GameplayScenes {
         match self {
             Self::DuringRound(during_round) => during_round.enter(context, state),
             Self::RoundStart(round_start) => round_start.enter(context, state),
-            Self::Exit => {}
+            Self::Exit(_) => {}
         }
     }
 
@@ -54,7 +211,7 @@ impl GameplayScene for GameplayScenes {
         match self {
             Self::DuringRound(during_round) => during_round.update(context, state),
             Self::RoundStart(round_start) => round_start.update(context, state),
-            Self::Exit => None,
+            Self::Exit(_) => None,
         }
     }
 
@@ -72,7 +229,7 @@ impl GameplayScene for GameplayScenes {
             Self::RoundStart(round_start) => {
                 round_start.render(canvas, global_textures, context, state)
             }
-            Self::Exit => Ok(()),
+            Self::Exit(_) => Ok(()),
         }
     }
 
@@ -80,7 +237,7 @@ impl GameplayScene for GameplayScenes {
         match self {
             Self::DuringRound(during_round) => during_round.exit(context, state),
             Self::RoundStart(round_start) => round_start.exit(context, state),
-            Self::Exit => {}
+            Self::Exit(_) => {}
         }
     }
 }
@@ -93,154 +250,455 @@ impl GameplayScene for GameplayScenes {
 //     }
 // }
 
+/// `framing` only moves the fighters, the HUD stays put
 fn render_gameplay(
     canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
     global_textures: &[sdl3::render::Texture],
     context: &GameContext,
     state: &GameState,
+    framing: &Framing,
     time: usize,
-    score: (u32, u32),
 ) -> Result<(), sdl3::Error> {
-    context.stage.render(canvas, global_textures)?;
-    state
-        .player1
-        .render(canvas, &context.camera, global_textures, &context.player1)?;
-    state
-        .player2
-        .render(canvas, &context.camera, global_textures, &context.player2)?;
-
-    let player1_hp_per = state.player1.hp_per(&context.player1);
-    let player2_hp_per = state.player2.hp_per(&context.player2);
-    render_health_bars(canvas, player1_hp_per, player2_hp_per)?;
-    render_timer(canvas, global_textures, &context.timer_animation, time)?;
-    render_scores(canvas, score)?;
+    let camera = context
+        .camera
+        .framed(framing, context.stage().floor_height());
+    context.stage().render(
+        canvas,
+        global_textures,
+        context.camera.viewport(),
+        framing.pan(context.stage()),
+    )?;
+    if state.player1.super_flash() > 0 || state.player2.super_flash() > 0 {
+        canvas.set_blend_mode(sdl3::render::BlendMode::Blend);
+        canvas.set_draw_color(SUPER_FLASH_SHADE);
+        canvas.fill_rect(context.camera.viewport())?;
+        canvas.set_blend_mode(sdl3::render::BlendMode::None);
+    }
+    state.player1.render(
+        canvas,
+        &camera,
+        global_textures,
+        &context.player1,
+        context.show_boxes,
+    )?;
+    state.player2.render(
+        canvas,
+        &camera,
+        global_textures,
+        &context.player2,
+        context.show_boxes,
+    )?;
+    for clash_spark in &state.clash_sparks {
+        draw_clash_spark_system(canvas, &camera, clash_spark)?;
+    }
+    for damage_number in &state.damage_numbers {
+        draw_damage_number_system(canvas, &camera, damage_number)?;
+    }
+    context.camera.render_letterbox(canvas)?;
+
+    render_health_bars(canvas, global_textures, context, state)?;
+    render_burst_gauges(canvas, context, state)?;
+    render_dizzy_gauges(canvas, context, state)?;
+    render_meter_bars(canvas, context, state)?;
+    render_timer(canvas, global_textures, context, time)?;
+    render_player_names(canvas, context)?;
 
     Ok(())
 }
 
+/// Seconds left in the round, counting down from `ROUND_LEN`
 fn render_timer(
     canvas: &mut Canvas<Window>,
     global_textures: &[Texture],
-    timer_animation: &Animation,
+    context: &GameContext,
     time: usize,
 ) -> Result<(), sdl3::Error> {
     let (screen_w, screen_h) = canvas.window().size();
-    let frame = time / FRAME_RATE;
-    let (texture, src) = timer_animation.get_frame(frame, global_textures);
+    let hud = &context.hud;
+    if let Some(timer_animation) = &context.timer_animation {
+        let (texture, src) = timer_animation.get_frame(time / FRAME_RATE, global_textures);
+        let timer_w = screen_w as f32 / 10.0;
+        let timer_h = screen_h as f32 / 5.625;
+        let dst = FRect::new(
+            screen_w as f32 * hud.timer.x - timer_w / 2.0,
+            0.0,
+            timer_w,
+            timer_h,
+        );
+        return canvas.copy(texture, src, dst);
+    }
+    let seconds_left = ROUND_LEN.saturating_sub(time / FRAME_RATE);
+    let label = format!("{seconds_left:02}");
+    let center_x = screen_w as f32 * hud.timer.x;
+    let y = screen_h as f32 * hud.timer.y;
+    let scale = screen_h as f32 * hud.timer_size;
+    if let Some(backing) = context.skin.timer {
+        // A digit's width of border around the text
+        let pad = text_width("0", scale);
+        let w = text_width(&label, scale) + pad * 2.0;
+        canvas.copy(
+            &global_textures[backing.texture],
+            backing.src,
+            FRect::new(
+                center_x - w / 2.0,
+                y - pad,
+                w,
+                text_height(scale) + pad * 2.0,
+            ),
+        )?;
+    }
+    draw_text_centered(canvas, &label, center_x, y, scale, Color::WHITE)
+}
 
-    let timer_w = screen_w as f32 / 10.0;
-    let timer_h = screen_h as f32 / 5.625;
-    let dst = FRect::new(screen_w as f32 * 0.5 - timer_w / 2.0, 0.0, timer_w, timer_h);
-    canvas.copy(texture, src, dst)
+/// Character names under the health bars, lined up with their outer ends
+fn render_player_names(
+    canvas: &mut Canvas<Window>,
+    context: &GameContext,
+) -> Result<(), sdl3::Error> {
+    let (screen_w, screen_h) = canvas.window().size();
+    let [player1_bar, player2_bar] = context.hud.health_bars(screen_w, screen_h);
+    let scale = screen_h as f32 / 300.0;
+    let pad = scale * 2.0;
+    let player2_name = context.player2.name();
+    draw_text(
+        canvas,
+        context.player1.name(),
+        player1_bar.x + pad,
+        player1_bar.y + player1_bar.h + pad,
+        scale,
+        Color::WHITE,
+    )?;
+    draw_text(
+        canvas,
+        player2_name,
+        player2_bar.x + player2_bar.w - pad - text_width(player2_name, scale),
+        player2_bar.y + player2_bar.h + pad,
+        scale,
+        Color::WHITE,
+    )
 }
 
-fn render_scores(canvas: &mut Canvas<Window>, score: (u32, u32)) -> Result<(), sdl3::Error> {
+/// One icon per round needed to win beside the timer, filled in from the timer out as rounds are won
+fn render_win_icons(
+    canvas: &mut Canvas<Window>,
+    global_textures: &[Texture],
+    context: &GameContext,
+    score: (u32, u32),
+    win_pop: &WinPop,
+) -> Result<(), sdl3::Error> {
     let (screen_w, screen_h) = canvas.window().size();
-    let y = screen_h as f32 / 15.0;
-    let score_w = screen_w as f32 / 40.0;
-    let score_h = screen_h as f32 / 22.5;
-
-    let player1_offset = screen_w as f32 * 0.5 - score_w * (2 * SCORE_TO_WIN + 3) as f32;
-    let player2_offset = screen_w as f32 * 0.5 + score_w * 4.0;
-    render_player1_score(canvas, score.0, y, score_w, score_h, player1_offset)?;
-    render_player2_score(canvas, score.1, y, score_w, score_h, player2_offset)?;
+    let hud = &context.hud;
+    let size = screen_h as f32 * hud.score_size;
+    let gap = size / 4.0;
+    let y = screen_h as f32 * hud.score.y;
+    let icons = &context.win_icons;
+    let players = [
+        (score.0, win_pop.winners.0, Side::Left),
+        (score.1, win_pop.winners.1, Side::Right),
+    ];
+
+    for (wins, won_latest, side) in players {
+        for slot in 0..SCORE_TO_WIN {
+            let offset = slot as f32 * (size + gap);
+            let x = match side {
+                Side::Left => screen_w as f32 * hud.score.x - size - offset,
+                Side::Right => screen_w as f32 * (1.0 - hud.score.x) + offset,
+            };
+            canvas.copy(
+                &global_textures[icons.empty.texture],
+                icons.empty.src,
+                FRect::new(x, y, size, size),
+            )?;
+            if slot >= wins {
+                continue;
+            }
+
+            let scale = if won_latest && slot + 1 == wins {
+                win_pop.icon_scale()
+            } else {
+                1.0
+            };
+            let icon_size = size * scale;
+            let inset = (size - icon_size) / 2.0;
+            canvas.copy(
+                &global_textures[icons.won.texture],
+                icons.won.src,
+                FRect::new(x + inset, y + inset, icon_size, icon_size),
+            )?;
+        }
+    }
     Ok(())
 }
 
-fn render_player1_score(
+fn render_health_bars(
     canvas: &mut Canvas<Window>,
-    score: u32,
-    y: f32,
-    w: f32,
-    h: f32,
-    x: f32,
+    global_textures: &[Texture],
+    context: &GameContext,
+    state: &GameState,
 ) -> Result<(), sdl3::Error> {
-    for i in 0..SCORE_TO_WIN {
-        let i_f32 = i as f32;
-        canvas.set_draw_color(Color::BLACK);
-        canvas.fill_rect(FRect::new(x + 2.0 * i_f32 * w, y, w, h))?;
-
-        if score > i {
-            canvas.set_draw_color(Color::WHITE);
-            canvas.fill_rect(FRect::new(
-                x + 2.0 * i_f32 * w + w * 0.2,
-                y + h * 0.2,
-                w * 0.6,
-                h * 0.6,
-            ))?;
+    let (screen_w, screen_h) = canvas.window().size();
+    let [player1_bar, player2_bar] = context.hud.health_bars(screen_w, screen_h);
+    let player1_hp_per = (
+        state.player1.hp_per(&context.player1),
+        state.player1.recoverable_hp_per(&context.player1),
+    );
+    let player2_hp_per = (
+        state.player2.hp_per(&context.player2),
+        state.player2.recoverable_hp_per(&context.player2),
+    );
+    render_player1_health(canvas, player1_hp_per, player1_bar)?;
+    render_player2_health(canvas, player2_hp_per, player2_bar)?;
+    if let Some(frame) = context.skin.health_bar_frame {
+        let texture = &global_textures[frame.texture];
+        canvas.copy(texture, frame.src, player1_bar)?;
+        canvas.copy_ex(texture, frame.src, player2_bar, 0.0, None, true, false)?;
+    }
+    Ok(())
+}
+
+/// One pip per frame under the timer, green when player1 is plus and red when minus
+pub fn render_frame_advantage(
+    canvas: &mut Canvas<Window>,
+    advantage: Option<i32>,
+) -> Result<(), sdl3::Error> {
+    let Some(advantage) = advantage.filter(|advantage| *advantage != 0) else {
+        return Ok(());
+    };
+    let (screen_w, screen_h) = canvas.window().size();
+    let size = screen_h as f32 / 60.0;
+    let gap = size / 2.0;
+    let pips = advantage.unsigned_abs().min(MAX_FRAME_ADVANTAGE_PIPS);
+    let row_w = pips as f32 * (size + gap) - gap;
+    let x = (screen_w as f32 - row_w) / 2.0;
+    let y = screen_h as f32 / 6.0;
+
+    canvas.set_draw_color(if advantage > 0 {
+        Color::GREEN
+    } else {
+        Color::RED
+    });
+    for pip in 0..pips {
+        canvas.fill_rect(FRect::new(x + pip as f32 * (size + gap), y, size, size))?;
+    }
+    Ok(())
+}
+
+/// Each player's commands down their side of the screen over a dimmed stage
+pub fn render_move_list(
+    canvas: &mut Canvas<Window>,
+    context: &GameContext,
+) -> Result<(), sdl3::Error> {
+    let (screen_w, screen_h) = canvas.window().size();
+    let (screen_w, screen_h) = (screen_w as f32, screen_h as f32);
+    canvas.set_blend_mode(sdl3::render::BlendMode::Blend);
+    canvas.set_draw_color(MOVE_LIST_SHADE);
+    canvas.fill_rect(FRect::new(0.0, 0.0, screen_w, screen_h))?;
+    canvas.set_blend_mode(sdl3::render::BlendMode::None);
+
+    let title_scale = screen_h / 150.0;
+    let row_scale = screen_h / 250.0;
+    let line_h = text_height(row_scale) * 1.5;
+    draw_text_centered(
+        canvas,
+        context.strings.get("MOVE LIST"),
+        screen_w / 2.0,
+        screen_h / 8.0,
+        title_scale,
+        Color::WHITE,
+    )?;
+    for (column, character) in [&context.player1, &context.player2].into_iter().enumerate() {
+        let x = screen_w * (0.1 + column as f32 * 0.5);
+        let command_x = x + screen_w * 0.25;
+        let mut y = screen_h / 4.0;
+        draw_text(canvas, character.name(), x, y, row_scale, Color::YELLOW)?;
+        for (name, command) in character.move_list() {
+            y += line_h;
+            draw_text(
+                canvas,
+                &name.replace('_', " "),
+                x,
+                y,
+                row_scale,
+                Color::WHITE,
+            )?;
+            draw_text(canvas, &command, command_x, y, row_scale, Color::GRAY)?;
         }
     }
+    Ok(())
+}
+
+/// Lit squares under the health bars while the burst is still unused
+fn render_burst_gauges(
+    canvas: &mut Canvas<Window>,
+    context: &GameContext,
+    state: &GameState,
+) -> Result<(), sdl3::Error> {
+    let (screen_w, screen_h) = canvas.window().size();
+    let [player1_bar, player2_bar] = context.hud.health_bars(screen_w, screen_h);
+    let size = screen_h as f32 / 30.0;
+    let y = player1_bar.y + player1_bar.h + size * 0.25;
+    let bursts = [
+        (
+            context.player1.has_burst(),
+            state.player1.burst_available(),
+            player1_bar.x + player1_bar.w - size,
+        ),
+        (
+            context.player2.has_burst(),
+            state.player2.burst_available(),
+            player2_bar.x,
+        ),
+    ];
+
+    for (has_burst, available, x) in bursts {
+        if !has_burst {
+            continue;
+        }
+        let color = if available {
+            Color::YELLOW
+        } else {
+            Color::GRAY
+        };
+        canvas.set_draw_color(color);
+        canvas.fill_rect(FRect::new(x, y, size, size))?;
+    }
 
     Ok(())
 }
 
-fn render_player2_score(
+/// Thin bars next to the burst gauges that fill towards the center, full and yellow while dizzied
+fn render_dizzy_gauges(
     canvas: &mut Canvas<Window>,
-    score: u32,
-    y: f32,
-    w: f32,
-    h: f32,
-    x: f32,
+    context: &GameContext,
+    state: &GameState,
 ) -> Result<(), sdl3::Error> {
-    for i in 0..SCORE_TO_WIN {
-        let i_f32 = i as f32;
+    let (screen_w, screen_h) = canvas.window().size();
+    let [player1_bar, player2_bar] = context.hud.health_bars(screen_w, screen_h);
+    let burst_size = screen_h as f32 / 30.0;
+    let bar_w = screen_w as f32 * 0.15;
+    let bar_h = burst_size / 2.0;
+    let y = player1_bar.y + player1_bar.h + burst_size * 0.25;
+    let gauges = [
+        (
+            state.player1.dizzy_per(&context.player1),
+            state.player1.dizzied(),
+            Side::Left,
+        ),
+        (
+            state.player2.dizzy_per(&context.player2),
+            state.player2.dizzied(),
+            Side::Right,
+        ),
+    ];
+
+    for (dizzy_per, dizzied, side) in gauges {
+        let fill_per = if dizzied { 1.0 } else { dizzy_per };
+        let fill_w = fill_per * bar_w;
+        let (x, fill_x) = match side {
+            Side::Left => {
+                let x = player1_bar.x + player1_bar.w - burst_size * 1.5 - bar_w;
+                (x, x + bar_w - fill_w)
+            }
+            Side::Right => (
+                player2_bar.x + burst_size * 1.5,
+                player2_bar.x + burst_size * 1.5,
+            ),
+        };
         canvas.set_draw_color(Color::BLACK);
-        canvas.fill_rect(FRect::new(x + 2.0 * i_f32 * w, y, w, h))?;
-
-        if score >= SCORE_TO_WIN - i {
-            canvas.set_draw_color(Color::WHITE);
-            canvas.fill_rect(FRect::new(
-                x + 2.0 * i_f32 * w + w * 0.2,
-                y + h * 0.2,
-                w * 0.6,
-                h * 0.6,
-            ))?;
-        }
+        canvas.fill_rect(FRect::new(x, y, bar_w, bar_h))?;
+        canvas.set_draw_color(if dizzied { Color::YELLOW } else { DIZZY_COLOR });
+        canvas.fill_rect(FRect::new(fill_x, y, fill_w, bar_h))?;
     }
 
     Ok(())
 }
 
-fn render_health_bars(
+/// Bars along the bottom corners that fill from the screen edge, half full pays for a guard cancel
+fn render_meter_bars(
     canvas: &mut Canvas<Window>,
-    player1_hp_per: f32,
-    player2_hp_per: f32,
+    context: &GameContext,
+    state: &GameState,
 ) -> Result<(), sdl3::Error> {
     let (screen_w, screen_h) = canvas.window().size();
-    let bar_h = screen_h as f32 / 20.0;
-    let bar_width = screen_w as f32 * 0.4;
-    render_player1_health(canvas, player1_hp_per, bar_h, bar_width)?;
-    render_player2_health(canvas, player2_hp_per, screen_w as f32, bar_h, bar_width)?;
+    let bar_w = screen_w as f32 * 0.25;
+    let bar_h = screen_h as f32 / 40.0;
+    let y = screen_h as f32 - bar_h * 2.0;
+    let meters = [
+        (
+            context.player1.has_guard_cancel(),
+            state.player1.meter() / MAX_METER,
+            Side::Left,
+        ),
+        (
+            context.player2.has_guard_cancel(),
+            state.player2.meter() / MAX_METER,
+            Side::Right,
+        ),
+    ];
+
+    for (has_guard_cancel, meter_per, side) in meters {
+        if !has_guard_cancel {
+            continue;
+        }
+        let fill_w = meter_per * bar_w;
+        let (x, fill_x) = match side {
+            Side::Left => (bar_h, bar_h),
+            Side::Right => (
+                screen_w as f32 - bar_h - bar_w,
+                screen_w as f32 - bar_h - fill_w,
+            ),
+        };
+        canvas.set_draw_color(Color::BLACK);
+        canvas.fill_rect(FRect::new(x, y, bar_w, bar_h))?;
+        canvas.set_draw_color(METER_COLOR);
+        canvas.fill_rect(FRect::new(fill_x, y, fill_w, bar_h))?;
+    }
+
     Ok(())
 }
 
+/// (health, health plus recoverable health), drains towards the left end of `bar`
 fn render_player1_health(
     canvas: &mut Canvas<Window>,
-    hp_per: f32,
-    bar_h: f32,
-    bar_width: f32,
+    (hp_per, recoverable_hp_per): (f32, f32),
+    bar: FRect,
 ) -> Result<(), sdl3::Error> {
     canvas.set_draw_color(Color::RED);
-    canvas.fill_rect(FRect::new(0.0, 0.0, bar_width, bar_h))?;
+    canvas.fill_rect(bar)?;
+    canvas.set_draw_color(RECOVERABLE_HEALTH_COLOR);
+    let recoverable_bar = recoverable_hp_per.powf(1.4) * bar.w;
+    canvas.fill_rect(FRect::new(
+        bar.x + bar.w - recoverable_bar,
+        bar.y,
+        recoverable_bar,
+        bar.h,
+    ))?;
     canvas.set_draw_color(Color::GREEN);
-    let health_bar = hp_per.powf(1.4) * bar_width;
-    canvas.fill_rect(FRect::new(bar_width - health_bar, 0.0, health_bar, bar_h))?;
+    let health_bar = hp_per.powf(1.4) * bar.w;
+    canvas.fill_rect(FRect::new(
+        bar.x + bar.w - health_bar,
+        bar.y,
+        health_bar,
+        bar.h,
+    ))?;
 
     Ok(())
 }
 
+/// (health, health plus recoverable health), drains towards the right end of `bar`
 fn render_player2_health(
     canvas: &mut Canvas<Window>,
-    hp_per: f32,
-    screen_w: f32,
-    bar_h: f32,
-    bar_width: f32,
+    (hp_per, recoverable_hp_per): (f32, f32),
+    bar: FRect,
 ) -> Result<(), sdl3::Error> {
     canvas.set_draw_color(Color::RED);
-    canvas.fill_rect(FRect::new(screen_w - bar_width, 0.0, bar_width, bar_h))?;
+    canvas.fill_rect(bar)?;
+    canvas.set_draw_color(RECOVERABLE_HEALTH_COLOR);
+    let recoverable_bar = recoverable_hp_per.powf(1.4) * bar.w;
+    canvas.fill_rect(FRect::new(bar.x, bar.y, recoverable_bar, bar.h))?;
     canvas.set_draw_color(Color::GREEN);
-    let health_bar = hp_per.powf(1.4) * bar_width;
-    canvas.fill_rect(FRect::new(screen_w - bar_width, 0.0, health_bar, bar_h))?;
+    let health_bar = hp_per.powf(1.4) * bar.w;
+    canvas.fill_rect(FRect::new(bar.x, bar.y, health_bar, bar.h))?;
 
     Ok(())
 }
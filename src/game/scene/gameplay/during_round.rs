@@ -1,18 +1,37 @@
 use std::cmp::Ordering;
 
+use sdl3::{pixels::Color, render::FPoint};
+
 use crate::game::{
     FRAME_RATE, GameContext, GameState, SCORE_TO_WIN,
-    physics::{check_hit_collisions, movement_system, side_detection},
+    audio::SoundCue,
+    boxes::{AttackKind, HitBox},
+    damage_number::DamageNumber,
+    physics::{check_hit_collisions, check_throw_collision, movement_system, side_detection},
+    projectile::ClashSpark,
+    render::{Framing, text::draw_text_centered},
     scene::gameplay::{
-        GameplayScene, GameplayScenes, ROUND_LEN, render_gameplay, round_start::RoundStart,
+        GameplayScene, GameplayScenes, MatchResults, MatchStats, ROUND_LEN, WinPop,
+        render_gameplay, render_win_icons, round_start::RoundStart,
     },
 };
 
+/// Game frames a knockout keeps playing for before the round ends
+const KO_FRAMES: usize = 45;
+/// Screen frames each game frame is held for during a knockout
+const KO_SLOWDOWN: usize = 2;
+
 #[derive(Clone, PartialEq)]
 pub struct DuringRound {
     hit_freeze: usize,
     score: (u32, u32),
     time: usize,
+    framing: Framing,
+    /// Screen frames left of the knockout, the round is already scored while it plays
+    ko_frames: usize,
+    slow_ko: bool,
+    win_pop: WinPop,
+    stats: MatchStats,
 }
 
 impl DuringRound {
@@ -21,9 +40,31 @@ impl DuringRound {
             hit_freeze: 0,
             score,
             time: 0,
+            framing: Framing::default(),
+            ko_frames: 0,
+            slow_ko: true,
+            win_pop: WinPop::default(),
+            stats: MatchStats::default(),
         }
     }
 
+    /// Keeps counting from the rounds before
+    pub fn with_stats(mut self, stats: MatchStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Knockouts end the round on the frame they land, for training where nothing watches
+    pub fn without_slow_ko(mut self) -> Self {
+        self.slow_ko = false;
+        self
+    }
+
+    /// Round clock, it stands still during hit freeze and super flashes
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
     pub fn timer(&self) -> f32 {
         self.time as f32 / (ROUND_LEN * FRAME_RATE) as f32
     }
@@ -32,13 +73,27 @@ impl DuringRound {
         self.score
     }
 
+    /// The knockout is playing in slow motion
+    pub fn in_ko(&self) -> bool {
+        self.ko_frames > 0
+    }
+
+    fn results(&self) -> MatchResults {
+        MatchResults {
+            score: self.score,
+            stats: self.stats,
+        }
+    }
+
     fn check_round_end(
         &mut self,
         context: &GameContext,
         state: &GameState,
     ) -> Option<GameplayScenes> {
+        let old_score = self.score;
         let player1_hp_ratio = state.player1.hp_per(&context.player1);
         let player2_hp_ratio = state.player2.hp_per(&context.player2);
+        let knocked_out = player1_hp_ratio == 0.0 || player2_hp_ratio == 0.0;
         match (player1_hp_ratio, player2_hp_ratio) {
             (0.0, 0.0) => self.score = (self.score.0 + 1, self.score.1 + 1),
             (0.0, _) => self.score.1 += 1,
@@ -59,32 +114,45 @@ impl DuringRound {
             }
         }
 
-        match self.score {
-            (SCORE_TO_WIN, SCORE_TO_WIN) => {
-                self.score = (SCORE_TO_WIN - 1, SCORE_TO_WIN - 1);
-                Some(GameplayScenes::RoundStart(RoundStart::new(self.score)))
-            }
-            (SCORE_TO_WIN, _) => {
-                if cfg!(feature = "debug") {
-                    println!("Player1 wins!");
-                }
-                Some(GameplayScenes::Exit)
-            }
-            (_, SCORE_TO_WIN) => {
-                if cfg!(feature = "debug") {
-                    println!("Player2 wins!");
-                }
-                Some(GameplayScenes::Exit)
-            }
-            _ => Some(GameplayScenes::RoundStart(RoundStart::new(self.score))),
+        self.win_pop = WinPop::new((self.score.0 > old_score.0, self.score.1 > old_score.1));
+
+        if knocked_out && self.slow_ko {
+            self.ko_frames = KO_FRAMES * KO_SLOWDOWN;
+            return None;
         }
+        self.next_scene()
     }
-}
 
-impl GameplayScene for DuringRound {
-    fn enter(&mut self, _context: &GameContext, _state: &mut GameState) {}
+    /// Runs one game frame, returns false while hit freeze holds the players in place
+    fn advance(&mut self, context: &GameContext, state: &mut GameState) -> bool {
+        state.sounds.clear();
+        let hp_before = [state.player1.hp(), state.player2.hp()];
+        let advanced = self.advance_players(context, state);
+        self.stats.record(context, hp_before, state);
+        spawn_damage_numbers(context, state, hp_before);
+        queue_move_sounds(state);
+        self.pan_sounds(context, state);
+        advanced
+    }
 
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Option<GameplayScenes> {
+    /// Pans each sound towards where its player is on screen
+    fn pan_sounds(&self, context: &GameContext, state: &mut GameState) {
+        if state.sounds.is_empty() {
+            return;
+        }
+        let camera = context
+            .camera
+            .framed(&self.framing, context.stage().floor_height());
+        let pans = [
+            camera.pan(state.player1.pos()),
+            camera.pan(state.player2.pos()),
+        ];
+        for cue in &mut state.sounds {
+            cue.pan = pans[cue.player];
+        }
+    }
+
+    fn advance_players(&mut self, context: &GameContext, state: &mut GameState) -> bool {
         // Side check first to prevent flickering
         if let Some(player1_side) = side_detection(state.player1.pos(), state.player2.pos()) {
             state.player1.set_side(&context.player1, player1_side);
@@ -92,16 +160,28 @@ impl GameplayScene for DuringRound {
                 .player2
                 .set_side(&context.player2, player1_side.opposite());
         }
-        state
-            .player1
-            .state_update(&state.player1_inputs, &context.player1);
-        state
-            .player2
-            .state_update(&state.player2_inputs, &context.player2);
+        let (player1_pos, player2_pos) = (state.player1.pos(), state.player2.pos());
+        if state.player2.super_flash() == 0 {
+            state
+                .player1
+                .state_update(&state.player1_inputs, &context.player1, player2_pos);
+        }
+        if state.player1.super_flash() == 0 {
+            state
+                .player2
+                .state_update(&state.player2_inputs, &context.player2, player1_pos);
+        }
+        // Checked again so the opponent freezes on the frame the super starts
+        let player1_frozen = state.player2.super_flash() > 0;
+        let player2_frozen = state.player1.super_flash() > 0;
 
         if self.hit_freeze == 0 {
-            state.player1.movement_update(&context.player1);
-            state.player2.movement_update(&context.player2);
+            if !player1_frozen {
+                state.player1.movement_update(&context.player1);
+            }
+            if !player2_frozen {
+                state.player2.movement_update(&context.player2);
+            }
 
             let (player1_pos, player2_pos) = movement_system(
                 state.player1.side(),
@@ -110,21 +190,92 @@ impl GameplayScene for DuringRound {
                 state.player2.side(),
                 state.player2.pos(),
                 state.player2.get_collision_box(&context.player2),
-                &context.stage,
+                context.stage(),
             );
             state.player1.set_pos(player1_pos);
             state.player2.set_pos(player2_pos);
 
-            self.hit_freeze = handle_hit_boxes(state, context);
+            self.hit_freeze = handle_hit_boxes(state, context, &mut self.stats);
 
-            state.player1.advance_frame();
-            state.player2.advance_frame();
+            if !player1_frozen {
+                state.player1.advance_frame(&context.player1);
+            }
+            if !player2_frozen {
+                state.player2.advance_frame(&context.player2);
+            }
+            state.player1.tick_super_flash();
+            state.player2.tick_super_flash();
 
-            self.time += 1;
+            state.clash_sparks.retain_mut(ClashSpark::update);
+            state.damage_numbers.retain_mut(DamageNumber::update);
+
+            if !player1_frozen && !player2_frozen {
+                self.time += 1;
+            }
+            true
         } else {
             self.hit_freeze -= 1;
+            false
         }
+    }
+
+    /// Where the round goes once it's been scored
+    fn next_scene(&mut self) -> Option<GameplayScenes> {
+        match self.score {
+            (SCORE_TO_WIN, SCORE_TO_WIN) => {
+                // Nobody gets a new icon for a draw on match point
+                self.score = (SCORE_TO_WIN - 1, SCORE_TO_WIN - 1);
+                Some(GameplayScenes::RoundStart(
+                    RoundStart::new(self.score).with_stats(self.stats),
+                ))
+            }
+            (SCORE_TO_WIN, _) => {
+                if cfg!(feature = "debug") {
+                    println!("Player1 wins!");
+                }
+                Some(GameplayScenes::Exit(self.results()))
+            }
+            (_, SCORE_TO_WIN) => {
+                if cfg!(feature = "debug") {
+                    println!("Player2 wins!");
+                }
+                Some(GameplayScenes::Exit(self.results()))
+            }
+            _ => Some(GameplayScenes::RoundStart(
+                RoundStart::new(self.score)
+                    .with_win_pop(self.win_pop)
+                    .with_stats(self.stats),
+            )),
+        }
+    }
+}
 
+impl GameplayScene for DuringRound {
+    fn enter(&mut self, context: &GameContext, state: &mut GameState) {
+        self.framing =
+            Framing::on_players(context.stage(), state.player1.pos(), state.player2.pos());
+    }
+
+    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Option<GameplayScenes> {
+        if self.ko_frames > 0 {
+            self.ko_frames -= 1;
+            self.win_pop.tick();
+            if self.ko_frames.is_multiple_of(KO_SLOWDOWN) {
+                self.advance(context, state);
+            }
+            self.framing
+                .ease_to(Framing::close_up(context.stage(), ko_focus(context, state)));
+            return if self.ko_frames == 0 {
+                self.next_scene()
+            } else {
+                None
+            };
+        }
+
+        if self.advance(context, state) {
+            self.framing
+                .track(context.stage(), state.player1.pos(), state.player2.pos());
+        }
         self.check_round_end(context, state)
     }
 
@@ -140,23 +291,115 @@ impl GameplayScene for DuringRound {
             global_textures,
             context,
             state,
+            &self.framing,
             self.time,
-            self.score,
-        )
+        )?;
+        render_win_icons(canvas, global_textures, context, self.score, &self.win_pop)?;
+
+        if self.ko_frames > 0 {
+            let (w, h) = canvas.window().size();
+            let double_ko = state.player1.hp_per(&context.player1) == 0.0
+                && state.player2.hp_per(&context.player2) == 0.0;
+            let label = context
+                .strings
+                .get(if double_ko { "DOUBLE K.O." } else { "K.O." });
+            let scale = h as f32 / 60.0;
+            draw_text_centered(
+                canvas,
+                label,
+                w as f32 / 2.0,
+                h as f32 / 3.0,
+                scale,
+                Color::WHITE,
+            )?;
+        }
+        Ok(())
     }
 
     fn exit(&mut self, _context: &GameContext, _state: &mut GameState) {}
 }
 
+/// The player left standing, between both on a double knockout
+fn ko_focus(context: &GameContext, state: &GameState) -> FPoint {
+    let (player1_pos, player2_pos) = (state.player1.pos(), state.player2.pos());
+    match (
+        state.player1.hp_per(&context.player1) == 0.0,
+        state.player2.hp_per(&context.player2) == 0.0,
+    ) {
+        (false, true) => player1_pos,
+        (true, false) => player2_pos,
+        _ => FPoint::new(
+            (player1_pos.x + player2_pos.x) / 2.0,
+            (player1_pos.y + player2_pos.y) / 2.0,
+        ),
+    }
+}
+
+/// Pops a number over the head of each player that lost health since `hp_before`
+fn spawn_damage_numbers(context: &GameContext, state: &mut GameState, hp_before: [f32; 2]) {
+    let players = [
+        (&state.player1, &context.player1, hp_before[0]),
+        (&state.player2, &context.player2, hp_before[1]),
+    ];
+    let spawned: Vec<DamageNumber> = players
+        .into_iter()
+        .filter(|(player, _, hp_before)| player.hp() < *hp_before)
+        .map(|(player, player_context, hp_before)| {
+            let collision = player
+                .get_collision_box(player_context)
+                .on_side(player.side(), player.pos());
+            DamageNumber::new(
+                FPoint::new(collision.x + collision.w / 2.0, collision.y),
+                hp_before - player.hp(),
+                player.took_chip(player_context),
+            )
+        })
+        .collect();
+    state.damage_numbers.extend(spawned);
+}
+
+/// Queues the sounds of the moves either player started this frame, panned once the frame is over
+fn queue_move_sounds(state: &mut GameState) {
+    let started = [state.player1.take_sounds(), state.player2.take_sounds()];
+    for (player, sounds) in started.into_iter().enumerate() {
+        state
+            .sounds
+            .extend(sounds.into_iter().map(|sound| SoundCue {
+                player,
+                sound,
+                pan: 0.0,
+            }));
+    }
+}
+
+/// Queues the impact sound of a hit from `player`, who owns the hit box
+fn queue_hit_sound(state: &mut GameState, player: usize, hit: &HitBox, blocked: bool) {
+    state
+        .sounds
+        .extend(hit.sound(blocked).map(|sound| SoundCue {
+            player,
+            sound,
+            pan: 0.0,
+        }));
+}
+
 // Returns the amount of frames for hit freeze
-fn handle_hit_boxes(state: &mut GameState, context: &GameContext) -> usize {
+fn handle_hit_boxes(state: &mut GameState, context: &GameContext, stats: &mut MatchStats) -> usize {
+    if let Some(throw_freeze) = handle_throws(state, context, stats) {
+        return throw_freeze;
+    }
+
     let player1_pos = state.player1.pos();
     let player1_side = state.player1.side();
     let player2_pos = state.player2.pos();
     let player2_side = state.player2.side();
+    let player1_cornered = context.stage().in_corner(player1_pos);
+    let player2_cornered = context.stage().in_corner(player2_pos);
 
     let player1_hit_boxes = state.player1.get_hit_boxes(&context.player1);
-    let player2_hurt_boxes = state.player2.get_hurt_boxes(&context.player2);
+    let player2_hurt_boxes = state
+        .player2
+        .get_hurt_boxes_against(&context.player2, AttackKind::Strike);
     let player1_hit = check_hit_collisions(
         player1_side,
         player1_pos,
@@ -164,10 +407,13 @@ fn handle_hit_boxes(state: &mut GameState, context: &GameContext) -> usize {
         player2_side,
         player2_pos,
         player2_hurt_boxes,
-    );
+    )
+    .map(|hit| state.player1.buff_hit(&context.player1, hit));
 
     let player2_hit_boxes = state.player2.get_hit_boxes(&context.player2);
-    let player1_hurt_boxes = state.player1.get_hurt_boxes(&context.player1);
+    let player1_hurt_boxes = state
+        .player1
+        .get_hurt_boxes_against(&context.player1, AttackKind::Strike);
     let player2_hit = check_hit_collisions(
         player2_side,
         player2_pos,
@@ -175,32 +421,236 @@ fn handle_hit_boxes(state: &mut GameState, context: &GameContext) -> usize {
         player1_side,
         player1_pos,
         player1_hurt_boxes,
-    );
+    )
+    .map(|hit| state.player2.buff_hit(&context.player2, hit));
 
-    match (player1_hit, player2_hit) {
+    let strike_freeze = match (player1_hit, player2_hit) {
         (Some(player1_hit), None) => {
-            let blocked = state.player2.receive_hit(&context.player1, &player1_hit);
+            let blocked =
+                state
+                    .player2
+                    .receive_hit(&context.player2, &player1_hit, player2_cornered);
             state
                 .player1
-                .successful_hit(&context.player1, &player1_hit, blocked);
-            4
+                .successful_hit(&context.player1, &player1_hit, blocked, player2_cornered);
+            queue_hit_sound(state, 0, &player1_hit, blocked);
+            player1_hit.hit_stop()
         }
         (None, Some(player2_hit)) => {
-            let blocked = state.player1.receive_hit(&context.player1, &player2_hit);
+            let blocked =
+                state
+                    .player1
+                    .receive_hit(&context.player1, &player2_hit, player1_cornered);
             state
                 .player2
-                .successful_hit(&context.player2, &player2_hit, blocked);
-            4
+                .successful_hit(&context.player2, &player2_hit, blocked, player1_cornered);
+            queue_hit_sound(state, 1, &player2_hit, blocked);
+            player2_hit.hit_stop()
         }
         (Some(player1_hit), Some(player2_hit)) => {
             state
                 .player1
-                .successful_hit(&context.player1, &player1_hit, true);
+                .successful_hit(&context.player1, &player1_hit, true, player2_cornered);
             state
                 .player2
-                .successful_hit(&context.player2, &player2_hit, true);
-            8
+                .successful_hit(&context.player2, &player2_hit, true, player1_cornered);
+            queue_hit_sound(state, 0, &player1_hit, true);
+            queue_hit_sound(state, 1, &player2_hit, true);
+            player1_hit.hit_stop().max(player2_hit.hit_stop())
         }
         _ => 0,
+    };
+
+    strike_freeze
+        .max(handle_projectiles(state, context))
+        .max(handle_assists(state, context))
+}
+
+/// Assists hit on their own like projectiles, the caller isn't pushed back
+///
+/// Strikes and projectiles that didn't land on the opponent can still land on their assist
+fn handle_assists(state: &mut GameState, context: &GameContext) -> usize {
+    let mut freeze = 0;
+    let player1_assist_hit =
+        state
+            .player1
+            .hit_assist(&context.player1, &mut state.player2, &context.player2);
+    let player2_assist_hit =
+        state
+            .player2
+            .hit_assist(&context.player2, &mut state.player1, &context.player1);
+    for (player, hit) in [(0, player1_assist_hit), (1, player2_assist_hit)] {
+        if let Some(hit) = hit {
+            queue_hit_sound(state, player, &hit, false);
+            freeze = freeze.max(hit.hit_stop());
+        }
+    }
+
+    let player1_hit = state.player1.assist_hit(
+        &context.player1,
+        state.player2.side(),
+        state.player2.pos(),
+        state
+            .player2
+            .get_hurt_boxes_against(&context.player2, AttackKind::Strike),
+    );
+    let player2_hit = state.player2.assist_hit(
+        &context.player2,
+        state.player1.side(),
+        state.player1.pos(),
+        state
+            .player1
+            .get_hurt_boxes_against(&context.player1, AttackKind::Strike),
+    );
+
+    freeze.max(receive_independent_hits(
+        state,
+        context,
+        player1_hit,
+        player2_hit,
+    ))
+}
+
+/// Projectiles hit on their own, so a trade with the owner's strike still lands both
+fn handle_projectiles(state: &mut GameState, context: &GameContext) -> usize {
+    let clashes =
+        state
+            .player1
+            .clash_projectiles(&context.player1, &mut state.player2, &context.player2);
+    state
+        .clash_sparks
+        .extend(clashes.into_iter().map(ClashSpark::new));
+
+    let player1_hit = state.player1.projectile_hit(
+        &context.player1,
+        state.player2.side(),
+        state.player2.pos(),
+        state
+            .player2
+            .get_hurt_boxes_against(&context.player2, AttackKind::Projectile),
+    );
+    let player2_hit = state.player2.projectile_hit(
+        &context.player2,
+        state.player1.side(),
+        state.player1.pos(),
+        state
+            .player1
+            .get_hurt_boxes_against(&context.player1, AttackKind::Projectile),
+    );
+
+    receive_independent_hits(state, context, player1_hit, player2_hit)
+}
+
+/// Lands hits that don't belong to the players' own states, both can connect on the same frame
+fn receive_independent_hits(
+    state: &mut GameState,
+    context: &GameContext,
+    player1_hit: Option<HitBox>,
+    player2_hit: Option<HitBox>,
+) -> usize {
+    let mut freeze = 0;
+    if let Some(player1_hit) = player1_hit {
+        let cornered = context.stage().in_corner(state.player2.pos());
+        let blocked = state
+            .player2
+            .receive_hit(&context.player2, &player1_hit, cornered);
+        queue_hit_sound(state, 0, &player1_hit, blocked);
+        freeze = player1_hit.hit_stop();
+    }
+    if let Some(player2_hit) = player2_hit {
+        let cornered = context.stage().in_corner(state.player1.pos());
+        let blocked = state
+            .player1
+            .receive_hit(&context.player1, &player2_hit, cornered);
+        queue_hit_sound(state, 1, &player2_hit, blocked);
+        freeze = freeze.max(player2_hit.hit_stop());
+    }
+    freeze
+}
+
+/// Resolves throws before strikes, returns the hit freeze if a throw landed or was broken
+fn handle_throws(
+    state: &mut GameState,
+    context: &GameContext,
+    stats: &mut MatchStats,
+) -> Option<usize> {
+    let player1_throw = state
+        .player1
+        .get_throw_box(&context.player1)
+        .filter(|throw_box| {
+            state.player2.throwable(&context.player2)
+                && check_throw_collision(
+                    state.player1.side(),
+                    state.player1.pos(),
+                    throw_box,
+                    state.player2.side(),
+                    state.player2.pos(),
+                    state
+                        .player2
+                        .get_hurt_boxes_against(&context.player2, AttackKind::Throw),
+                )
+        });
+    let player2_throw = state
+        .player2
+        .get_throw_box(&context.player2)
+        .filter(|throw_box| {
+            state.player1.throwable(&context.player1)
+                && check_throw_collision(
+                    state.player2.side(),
+                    state.player2.pos(),
+                    throw_box,
+                    state.player1.side(),
+                    state.player1.pos(),
+                    state
+                        .player1
+                        .get_hurt_boxes_against(&context.player1, AttackKind::Throw),
+                )
+        });
+
+    match (player1_throw, player2_throw) {
+        (Some(throw_box), None) => {
+            if state
+                .player2
+                .techs_throw(&context.player2, &state.player1, throw_box)
+            {
+                state.player1.tech_throw(&context.player1);
+                state.player2.tech_throw(&context.player2);
+            } else {
+                state.player2.receive_throw(
+                    &context.player2,
+                    throw_box,
+                    state.player1.side(),
+                    state.player1.pos(),
+                );
+                state.player1.successful_throw();
+                stats.throws[0] += 1;
+            }
+            Some(8)
+        }
+        (None, Some(throw_box)) => {
+            if state
+                .player1
+                .techs_throw(&context.player1, &state.player2, throw_box)
+            {
+                state.player1.tech_throw(&context.player1);
+                state.player2.tech_throw(&context.player2);
+            } else {
+                state.player1.receive_throw(
+                    &context.player1,
+                    throw_box,
+                    state.player2.side(),
+                    state.player2.pos(),
+                );
+                state.player2.successful_throw();
+                stats.throws[1] += 1;
+            }
+            Some(8)
+        }
+        (Some(_), Some(_)) => {
+            state.player1.tech_throw(&context.player1);
+            state.player2.tech_throw(&context.player2);
+            Some(8)
+        }
+        (None, None) => None,
     }
 }
@@ -2,7 +2,11 @@ use sdl3::render::FPoint;
 
 use crate::game::{
     FRAME_RATE, GameContext, GameState, SCORE_TO_WIN,
-    scene::gameplay::{GameplayScene, GameplayScenes, during_round::DuringRound, render_gameplay},
+    render::Framing,
+    scene::gameplay::{
+        GameplayScene, GameplayScenes, MatchStats, WinPop, during_round::DuringRound,
+        render_gameplay, render_win_icons,
+    },
 };
 
 const PAUSE_DURATION: u32 = ROUND_DISPLAY_DURATION + FIGHT_DISPLAY_DURATION;
@@ -14,6 +18,8 @@ pub struct RoundStart {
     score: (u32, u32),
     round: u32,
     timer: u32,
+    win_pop: WinPop,
+    stats: MatchStats,
 }
 
 impl GameplayScene for RoundStart {
@@ -23,15 +29,18 @@ impl GameplayScene for RoundStart {
 
     fn update(
         &mut self,
-        _context: &GameContext,
+        context: &GameContext,
         state: &mut GameState,
     ) -> Option<super::GameplayScenes> {
-        state.player1.advance_frame();
-        state.player2.advance_frame();
+        state.player1.advance_frame(&context.player1);
+        state.player2.advance_frame(&context.player2);
 
         self.timer += 1;
+        self.win_pop.tick();
         if self.timer == PAUSE_DURATION {
-            Some(GameplayScenes::DuringRound(DuringRound::new(self.score)))
+            Some(GameplayScenes::DuringRound(
+                DuringRound::new(self.score).with_stats(self.stats),
+            ))
         } else {
             None
         }
@@ -44,7 +53,11 @@ impl GameplayScene for RoundStart {
         context: &GameContext,
         state: &GameState,
     ) -> Result<(), sdl3::Error> {
-        render_gameplay(canvas, global_textures, context, state, 0, self.score)?;
+        // Already framed the way the round will start
+        let framing =
+            Framing::on_players(context.stage(), state.player1.pos(), state.player2.pos());
+        render_gameplay(canvas, global_textures, context, state, &framing, 0)?;
+        render_win_icons(canvas, global_textures, context, self.score, &self.win_pop)?;
 
         let text_frame = if self.timer < ROUND_DISPLAY_DURATION {
             self.round as usize
@@ -70,6 +83,19 @@ impl RoundStart {
             timer: 0,
             score,
             round,
+            win_pop: WinPop::default(),
+            stats: MatchStats::default(),
         }
     }
+
+    pub fn with_stats(mut self, stats: MatchStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Carries on growing the icons of the round that just ended
+    pub fn with_win_pop(mut self, win_pop: WinPop) -> Self {
+        self.win_pop = win_pop;
+        self
+    }
 }
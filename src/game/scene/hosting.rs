@@ -1,5 +1,5 @@
 use crate::game::{
-    GameContext, GameState, PlayerInputs,
+    CharacterPicks, GameContext, GameState, PlayerInputs, Side,
     net::host::UdpHost,
     scene::{Scene, Scenes, online_play::OnlinePlay},
 };
@@ -29,15 +29,29 @@ impl Scene for Hosting {
         Ok(())
     }
 
-    fn update(&mut self, _context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
         if let Some(connection) = self
             .host
             .update(self.current_frame)
             .map_err(|err| err.to_string())?
         {
+            // Host always plays on the left
+            context.pick_characters(CharacterPicks {
+                player1: self.host.local_character(),
+                player2: self.host.peer_character(),
+                player1_palette: 0,
+                player2_palette: 0,
+                player1_side: Side::Left,
+            })?;
+            context.pick_stage(self.host.stage())?;
             Ok(Some(Scenes::OnlinePlay(OnlinePlay::new(
                 connection,
-                crate::game::Side::Left,
+                Side::Left,
+                self.host.netplay(),
                 state,
             ))))
         } else {
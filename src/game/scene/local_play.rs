@@ -1,42 +1,95 @@
+use sdl3::{pixels::Color, render::FRect};
+
 use crate::game::{
     GameContext, GameState, PlayerInputs,
+    frame_advantage::FrameAdvantage,
+    input::{ButtonFlag, Direction},
+    render::text::{draw_text, draw_text_centered, text_height},
+    replay::Replay,
     scene::{
         Scene, Scenes,
-        gameplay::{GameplayScene, GameplayScenes},
+        gameplay::{
+            GameplayScene, GameplayScenes, MOVE_LIST_SHADE, render_frame_advantage,
+            render_move_list,
+        },
         main_menu::MainMenu,
+        victory::Victory,
     },
 };
 
+const PAUSE_OPTIONS: usize = 4;
+/// Labels in scroll order
+const PAUSE_LABELS: [&str; PAUSE_OPTIONS] =
+    ["Resume", "Move List", "Button Config", "Quit to Menu"];
+
+/// Doubles as training mode, so it shows the frame advantage of the last interaction
 pub struct LocalPlay {
     scene: GameplayScenes,
+    frame_advantage: FrameAdvantage,
+    /// The simulation is frozen while the pause menu is up
+    paused: Option<PauseMenu>,
+    /// Unpaused since the last input update, the inputs held through the menu still need clearing
+    resumed: bool,
+    /// Saved for the replay viewer once the scene is left
+    replay: Option<Replay>,
 }
 
 impl Scene for LocalPlay {
     fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
         inputs.local_key_mapping();
         self.scene.enter(context, state);
+        self.replay = Some(Replay::new(context));
     }
 
     fn handle_input(
         &mut self,
         _context: &GameContext,
         inputs: &mut crate::game::PlayerInputs,
-        _state: &mut GameState,
+        state: &mut GameState,
     ) -> Result<(), String> {
+        // Menu scrolling would otherwise read as motions, and a held press as a fresh one
+        if std::mem::take(&mut self.resumed) {
+            inputs.reset_player1();
+            inputs.reset_player2();
+            state.player1_inputs.reset();
+            state.player2_inputs.reset();
+        }
         inputs.update_player1();
         inputs.update_player2();
         Ok(())
     }
 
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        if let Some(pause) = &mut self.paused {
+            return match pause.update(state) {
+                Some(PauseAction::Resume) => {
+                    self.toggle_pause();
+                    Ok(None)
+                }
+                Some(PauseAction::Quit) => Ok(Some(Scenes::MainMenu(MainMenu::new()))),
+                None => Ok(None),
+            };
+        }
+
+        if let Some(replay) = &mut self.replay {
+            replay.record(state);
+        }
         if let Some(new_gameplay_scene) = self.scene.update(context, state) {
             self.scene.exit(context, state);
             self.scene = new_gameplay_scene;
             self.scene.enter(context, state);
         }
+        if let GameplayScenes::DuringRound(during_round) = &self.scene {
+            self.frame_advantage
+                .update(context, state, during_round.time());
+        }
 
         match self.scene {
-            GameplayScenes::Exit => Ok(Some(Scenes::MainMenu(MainMenu::new()))),
+            GameplayScenes::Exit(results) => Ok(Some(Scenes::Victory(Victory::new(results)))),
             _ => Ok(None),
         }
     }
@@ -48,11 +101,24 @@ impl Scene for LocalPlay {
         context: &GameContext,
         state: &GameState,
     ) -> Result<(), sdl3::Error> {
-        self.scene.render(canvas, global_textures, context, state)
+        self.scene.render(canvas, global_textures, context, state)?;
+        render_frame_advantage(canvas, self.frame_advantage.advantage())?;
+        if let Some(pause) = &self.paused {
+            pause.render(canvas, context)?;
+        } else if context.show_move_list {
+            render_move_list(canvas, context)?;
+        }
+        Ok(())
     }
 
     fn exit(&mut self, context: &GameContext, _inputs: &mut PlayerInputs, state: &mut GameState) {
         self.scene.exit(context, state);
+        if let Some(replay) = self.replay.take().filter(|replay| replay.frames_len() > 0)
+            && let Err(err) = replay.save()
+            && cfg!(feature = "debug")
+        {
+            println!("[WARNING] Failed to save replay: {err}");
+        }
     }
 }
 
@@ -60,6 +126,200 @@ impl LocalPlay {
     pub fn new() -> Self {
         Self {
             scene: GameplayScenes::new_round_start((0, 0)),
+            frame_advantage: FrameAdvantage::default(),
+            paused: None,
+            resumed: false,
+            replay: None,
+        }
+    }
+
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.scene.cinematic(state)
+    }
+
+    /// Player who took the match once it's over
+    pub fn winner(&self) -> Option<usize> {
+        match self.scene {
+            GameplayScenes::Exit(results) => Some(if results.score.0 > results.score.1 {
+                0
+            } else {
+                1
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if self.paused.take().is_some() {
+            self.resumed = true;
+        } else {
+            self.paused = Some(PauseMenu::new());
+        }
+    }
+}
+
+enum PauseAction {
+    Resume,
+    Quit,
+}
+
+/// What the pause menu is showing, the pages go back to the options with L
+#[derive(Clone, Copy, PartialEq)]
+enum PausePage {
+    Options,
+    MoveList,
+    Controls,
+}
+
+/// Scrolled by player1 like the main menu, an option is picked once L is let go
+struct PauseMenu {
+    page: PausePage,
+    scroll_pos: usize,
+    last_dir: Direction,
+    l_button_pressed: bool,
+}
+
+impl PauseMenu {
+    fn new() -> Self {
+        Self {
+            page: PausePage::Options,
+            scroll_pos: 0,
+            last_dir: Direction::Neutral,
+            l_button_pressed: false,
+        }
+    }
+
+    fn update(&mut self, state: &GameState) -> Option<PauseAction> {
+        let just_pressed = state.player1_inputs.just_pressed_buttons();
+        let held = state.player1_inputs.active_buttons();
+
+        if self.l_button_pressed && !ButtonFlag::L.intersects(held) {
+            self.l_button_pressed = false;
+            return self.select();
+        }
+        self.l_button_pressed = self.l_button_pressed || ButtonFlag::L.intersects(just_pressed);
+
+        let held_dir = state.player1_inputs.dir();
+        if held_dir != self.last_dir && self.page == PausePage::Options {
+            self.scroll_pos = match held_dir {
+                Direction::Down => (self.scroll_pos + 1) % PAUSE_OPTIONS,
+                Direction::Up => (self.scroll_pos + PAUSE_OPTIONS - 1) % PAUSE_OPTIONS,
+                _ => self.scroll_pos,
+            };
+        }
+        self.last_dir = held_dir;
+        None
+    }
+
+    fn select(&mut self) -> Option<PauseAction> {
+        if self.page != PausePage::Options {
+            self.page = PausePage::Options;
+            return None;
+        }
+        match self.scroll_pos {
+            0 => Some(PauseAction::Resume),
+            1 => {
+                self.page = PausePage::MoveList;
+                None
+            }
+            2 => {
+                self.page = PausePage::Controls;
+                None
+            }
+            _ => Some(PauseAction::Quit),
+        }
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        context: &GameContext,
+    ) -> Result<(), sdl3::Error> {
+        match self.page {
+            PausePage::Options => render_options(canvas, context, self.scroll_pos),
+            PausePage::MoveList => render_move_list(canvas, context),
+            PausePage::Controls => render_controls(canvas, context),
+        }
+    }
+}
+
+fn render_shade(canvas: &mut sdl3::render::Canvas<sdl3::video::Window>) -> Result<(), sdl3::Error> {
+    let (w, h) = canvas.window().size();
+    canvas.set_blend_mode(sdl3::render::BlendMode::Blend);
+    canvas.set_draw_color(MOVE_LIST_SHADE);
+    canvas.fill_rect(FRect::new(0.0, 0.0, w as f32, h as f32))?;
+    canvas.set_blend_mode(sdl3::render::BlendMode::None);
+    Ok(())
+}
+
+fn render_options(
+    canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+    context: &GameContext,
+    scroll_pos: usize,
+) -> Result<(), sdl3::Error> {
+    let strings = &context.strings;
+    render_shade(canvas)?;
+    let (w, h) = canvas.window().size();
+    let (w, h) = (w as f32, h as f32);
+
+    let title_scale = h / 100.0;
+    let label_scale = h / 160.0;
+    let row_h = h / 10.0;
+    draw_text_centered(
+        canvas,
+        strings.get("PAUSED"),
+        w / 2.0,
+        h / 4.0,
+        title_scale,
+        Color::WHITE,
+    )?;
+    let y_start = h * 5.0 / 12.0;
+    for (option, label) in PAUSE_LABELS.into_iter().enumerate() {
+        let color = if option == scroll_pos {
+            Color::YELLOW
+        } else {
+            Color::GRAY
+        };
+        let y = y_start + option as f32 * row_h;
+        draw_text_centered(canvas, strings.get(label), w / 2.0, y, label_scale, color)?;
+    }
+    Ok(())
+}
+
+/// Both players' keys, they're rebound in the settings menu
+fn render_controls(
+    canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+    context: &GameContext,
+) -> Result<(), sdl3::Error> {
+    render_shade(canvas)?;
+    let (w, h) = canvas.window().size();
+    let (w, h) = (w as f32, h as f32);
+
+    let title_scale = h / 150.0;
+    let row_scale = h / 250.0;
+    let line_h = text_height(row_scale) * 1.5;
+    draw_text_centered(
+        canvas,
+        context.strings.get("BUTTON CONFIG"),
+        w / 2.0,
+        h / 8.0,
+        title_scale,
+        Color::WHITE,
+    )?;
+    let [player1, player2] = context.settings.controls.0;
+    for (column, labels) in [player1.labels(), player2.labels()].into_iter().enumerate() {
+        let x = w * (0.2 + column as f32 * 0.4);
+        let mut y = h / 4.0;
+        let player = context.strings.fill("Player {}", &[&(column + 1)]);
+        draw_text(canvas, &player, x, y, row_scale, Color::YELLOW)?;
+        for label in labels {
+            y += line_h;
+            draw_text(canvas, &label, x, y, row_scale, Color::WHITE)?;
         }
     }
+    Ok(())
 }
@@ -1,29 +1,116 @@
 use sdl3::{pixels::Color, render::FRect};
 
 use crate::game::{
-    GameContext, GameState, PlayerInputs,
+    FRAME_RATE, GameContext, GameState, PlayerInputs, Side,
     input::{ButtonFlag, Direction},
+    render::text::{draw_text, draw_text_centered, text_height, text_width},
+    replay::Replay,
     scene::{
-        Scene, Scenes, local_play::LocalPlay, matching::Matching, spectate_ai::SpectateAi,
+        Scene, Scenes,
+        arcade::Arcade,
+        character_select::{AfterSelect, CharacterSelect},
+        model_select::ModelSelect,
+        replay_viewer::ReplayViewer,
+        settings_menu::SettingsMenu,
+        spectate_ai::SpectateAi,
+        tutorial::Tutorial,
         verses_ai::VersesAi,
+        winner_stays::Session,
     },
 };
 
-const MAIN_MENU_OPTIONS: i32 = 4;
+/// A main menu row, disabled ones are greyed out and skipped over
+struct MenuEntry {
+    label: &'static str,
+    /// Checked each time the menu is entered
+    enabled: fn(&GameContext) -> bool,
+    scene: fn(&mut GameContext) -> Result<Scenes, String>,
+}
+
+/// Rows in scroll order
+const MENU_ENTRIES: [MenuEntry; 8] = [
+    MenuEntry {
+        label: "Local Play",
+        enabled: |_| true,
+        scene: |_| {
+            Ok(Scenes::CharacterSelect(CharacterSelect::new(
+                AfterSelect::LocalPlay,
+            )))
+        },
+    },
+    MenuEntry {
+        label: "Winner Stays",
+        enabled: |_| true,
+        scene: |_| {
+            Ok(Scenes::CharacterSelect(CharacterSelect::new(
+                AfterSelect::WinnerStays(Session::default()),
+            )))
+        },
+    },
+    MenuEntry {
+        label: "Vs. AI",
+        enabled: |_| true,
+        scene: |context| {
+            let model_path = context.verses_agent_filepath(&context.player2).to_string();
+            let ai_agent = context.take_agent(&model_path)?;
+            Ok(Scenes::VersesAi(VersesAi::from_agent(
+                ai_agent,
+                context.agent_action_repeat,
+            )))
+        },
+    },
+    MenuEntry {
+        label: "Spectate AI",
+        enabled: |_| true,
+        scene: |context| Ok(Scenes::SpectateAi(spectate_ai(context)?)),
+    },
+    MenuEntry {
+        label: "Online Play",
+        enabled: |_| true,
+        scene: |_| {
+            Ok(Scenes::CharacterSelect(CharacterSelect::new(
+                AfterSelect::Online,
+            )))
+        },
+    },
+    MenuEntry {
+        label: "Settings",
+        enabled: |_| true,
+        scene: |context| Ok(Scenes::Settings(SettingsMenu::new(context))),
+    },
+    MenuEntry {
+        label: "Replays",
+        enabled: |_| Replay::any_saved(),
+        scene: |context| Ok(Scenes::ReplayViewer(ReplayViewer::latest(context)?)),
+    },
+    MenuEntry {
+        label: "Tutorial",
+        enabled: |_| true,
+        scene: |_| Ok(Scenes::Tutorial(Tutorial::new())),
+    },
+];
+/// Shade of the entries that can't be picked
+const DISABLED_COLOR: Color = Color::RGB(96, 96, 96);
+const HINT_LABEL: &str = "M: AI models   H: Arcade";
+const ERROR_COLOR: Color = Color::RGB(200, 0, 0);
+/// Idle time before the AI plays an attract demo
+const ATTRACT_IDLE_FRAMES: usize = 30 * FRAME_RATE;
 
 pub struct MainMenu {
     l_button_pressed: bool,
     last_dir: Direction,
-    scroll_pos: i32,
+    scroll_pos: usize,
+    /// Whether each of `MENU_ENTRIES` can be picked
+    enabled: [bool; MENU_ENTRIES.len()],
+    /// Ticks since player1 last touched anything
+    idle_frames: usize,
+    /// Why the last picked entry couldn't start, shown until the cursor moves
+    error: Option<String>,
 }
 
 impl Scene for MainMenu {
-    fn enter(
-        &mut self,
-        _context: &GameContext,
-        _inputs: &mut PlayerInputs,
-        _state: &mut GameState,
-    ) {
+    fn enter(&mut self, context: &GameContext, _inputs: &mut PlayerInputs, _state: &mut GameState) {
+        self.enabled = MENU_ENTRIES.map(|entry| (entry.enabled)(context));
     }
 
     fn handle_input(
@@ -37,24 +124,51 @@ impl Scene for MainMenu {
         Ok(())
     }
 
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Result<Option<super::Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<super::Scenes>, String> {
         let just_pressed = state.player1_inputs.just_pressed_buttons();
         let held = state.player1_inputs.active_buttons();
 
         if self.l_button_pressed && !ButtonFlag::L.intersects(held) {
-            return Ok(Some(self.select_scene(context)?));
+            self.l_button_pressed = false;
+            return Ok(self.start((MENU_ENTRIES[self.scroll_pos].scene)(context)));
+        }
+
+        // Swap the AI models without restarting
+        if ButtonFlag::M.intersects(just_pressed) {
+            return Ok(Some(Scenes::ModelSelect(ModelSelect::new(context))));
+        }
+
+        // Climb the ladder of training checkpoints
+        if ButtonFlag::H.intersects(just_pressed) {
+            return Ok(self.start(Arcade::new(context).map(Scenes::Arcade)));
         }
 
         let held_dir = state.player1_inputs.dir();
 
+        if held_dir != Direction::Neutral || !held.is_empty() {
+            self.idle_frames = 0;
+        } else {
+            self.idle_frames += 1;
+        }
+        if self.idle_frames >= ATTRACT_IDLE_FRAMES {
+            self.idle_frames = 0;
+            return Ok(self
+                .start(spectate_ai(context).map(|demo| Scenes::SpectateAi(demo.with_attract()))));
+        }
+
         if held_dir != self.last_dir {
-            let scroll_dif = match state.player1_inputs.dir() {
-                Direction::Down => 1,
-                Direction::Up => -1,
-                _ => 0,
-            };
-            self.scroll_pos =
-                (MAIN_MENU_OPTIONS + self.scroll_pos + scroll_dif) % MAIN_MENU_OPTIONS;
+            if held_dir != Direction::Neutral {
+                self.error = None;
+            }
+            match held_dir {
+                Direction::Down => self.scroll(1),
+                Direction::Up => self.scroll(-1),
+                _ => {}
+            }
             self.last_dir = held_dir;
         }
 
@@ -70,20 +184,74 @@ impl Scene for MainMenu {
         context: &GameContext,
         _state: &GameState,
     ) -> Result<(), sdl3::Error> {
-        canvas.copy(&global_textures[context.main_menu_texture], None, None)?;
+        let background = context.main_menu_texture;
+        canvas.copy(&global_textures[background.texture], background.src, None)?;
         let (w, h) = canvas.window().size();
         let w = w as f32;
         let h = h as f32;
 
-        let rect_w = w / 30.0;
-        let rect_h = h / 16.875;
-        let x = w * 3.0 / 10.0;
-        let y_start = h * 5.0 / 12.0;
-        let y = y_start + (self.scroll_pos * 2) as f32 * rect_h;
+        let title_scale = h / 100.0;
+        let label_scale = h / 160.0;
+        let row_h = h / 14.0;
+        let strings = &context.strings;
+        draw_text_centered(
+            canvas,
+            strings.get("MAIN MENU"),
+            w / 2.0,
+            h / 6.0,
+            title_scale,
+            Color::BLACK,
+        )?;
+
+        let y_start = h / 3.0;
+        for (option, entry) in MENU_ENTRIES.iter().enumerate() {
+            let y = y_start + option as f32 * row_h;
+            let color = if option == self.scroll_pos {
+                Color::WHITE
+            } else if self.enabled[option] {
+                Color::BLACK
+            } else {
+                DISABLED_COLOR
+            };
+            let label = strings.get(entry.label);
+            draw_text_centered(canvas, label, w / 2.0, y, label_scale, color)?;
+        }
+
+        // Cursor to the left of the picked option
+        let picked = strings.get(MENU_ENTRIES[self.scroll_pos].label);
+        let cursor_x = (w - text_width(picked, label_scale)) / 2.0 - text_width("> ", label_scale);
+        let cursor_y = y_start + self.scroll_pos as f32 * row_h;
+        match context.skin.menu_cursor {
+            Some(cursor) => {
+                let size = text_height(label_scale);
+                canvas.copy(
+                    &global_textures[cursor.texture],
+                    cursor.src,
+                    FRect::new(cursor_x, cursor_y, size, size),
+                )?;
+            }
+            None => draw_text(canvas, ">", cursor_x, cursor_y, label_scale, Color::WHITE)?,
+        }
 
-        let rect = FRect::new(x, y, rect_w, rect_h);
-        canvas.set_draw_color(Color::BLACK);
-        canvas.fill_rect(rect)?;
+        let hint_scale = label_scale / 2.0;
+        draw_text_centered(
+            canvas,
+            strings.get(HINT_LABEL),
+            w / 2.0,
+            h - text_height(hint_scale) * 3.0,
+            hint_scale,
+            Color::BLACK,
+        )?;
+        if let Some(error) = &self.error {
+            draw_text_centered(
+                canvas,
+                error,
+                w / 2.0,
+                h - text_height(hint_scale) * 5.0,
+                hint_scale,
+                ERROR_COLOR,
+            )?;
+        }
 
         Ok(())
     }
@@ -98,21 +266,56 @@ impl MainMenu {
             l_button_pressed: false,
             last_dir: Direction::Neutral,
             scroll_pos: 0,
+            enabled: [true; MENU_ENTRIES.len()],
+            idle_frames: 0,
+            error: None,
         }
     }
 
-    fn select_scene(&self, context: &GameContext) -> Result<Scenes, String> {
-        let scene = match self.scroll_pos {
-            0 => Scenes::LocalPlay(LocalPlay::new()),
-            1 => Scenes::VersesAi(VersesAi::new(&context.left_agent_filepath)?),
-            2 => Scenes::SpectateAi(SpectateAi::new(
-                &context.left_agent_filepath,
-                &context.right_agent_filepath,
-            )?),
-            3 => Scenes::Matching(Matching::new(&context.matchmaking_server)?),
-            _ => return Err(String::from("Invalid scene selected")),
-        };
-
-        Ok(scene)
+    /// Moves on to `scene`, or stays with its error on screen when it couldn't start
+    fn start(&mut self, scene: Result<Scenes, String>) -> Option<Scenes> {
+        scene
+            .inspect_err(|err| {
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] {err}");
+                }
+                self.error = Some(err.clone());
+            })
+            .ok()
+    }
+
+    /// Moves the cursor `step` rows, wrapping around and over the disabled entries
+    fn scroll(&mut self, step: isize) {
+        let len = MENU_ENTRIES.len() as isize;
+        let mut pos = self.scroll_pos;
+        for _ in 0..len {
+            pos = (pos as isize + step).rem_euclid(len) as usize;
+            if self.enabled[pos] {
+                self.scroll_pos = pos;
+                return;
+            }
+        }
     }
 }
+
+/// Both sides played by their characters' models
+fn spectate_ai(context: &mut GameContext) -> Result<SpectateAi, String> {
+    let left_agent_path = context
+        .agent_filepath(&context.player1, Side::Left)
+        .to_string();
+    let right_agent_path = context
+        .agent_filepath(&context.player2, Side::Right)
+        .to_string();
+    let ai_agent1 = context.take_agent(&left_agent_path)?;
+    // Both sides run in one batch when they play the same model
+    let ai_agent2 = if left_agent_path == right_agent_path {
+        None
+    } else {
+        Some(context.take_agent(&right_agent_path)?)
+    };
+    Ok(SpectateAi::new(
+        ai_agent1,
+        ai_agent2,
+        context.agent_action_repeat,
+    ))
+}
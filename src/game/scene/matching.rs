@@ -1,6 +1,9 @@
 use crate::game::{
     GameContext, GameState, PlayerInputs,
-    net::matching::{MatchingSocket, PeerConnectionType},
+    net::{
+        Lineup,
+        matching::{MatchingSocket, PeerConnectionType},
+    },
     scene::{Scene, Scenes, connecting::Connecting, hosting::Hosting},
 };
 
@@ -29,17 +32,24 @@ impl Scene for Matching {
         Ok(())
     }
 
-    fn update(&mut self, _context: &GameContext, _state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        _state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        let netplay = context.settings.netplay;
         if let Some(connection) = self
             .socket
             .update(self.current_frame)
             .map_err(|err| err.to_string())?
         {
             match connection {
-                PeerConnectionType::Hosting(host) => Ok(Some(Scenes::Hosting(Hosting::new(host)))),
-                PeerConnectionType::Joining(client) => {
-                    Ok(Some(Scenes::Connecting(Connecting::new(client))))
-                }
+                PeerConnectionType::Hosting(host) => Ok(Some(Scenes::Hosting(Hosting::new(
+                    host.with_netplay(netplay),
+                )))),
+                PeerConnectionType::Joining(client) => Ok(Some(Scenes::Connecting(
+                    Connecting::new(client.with_netplay(netplay)),
+                ))),
             }
         } else {
             self.current_frame += 1;
@@ -62,9 +72,9 @@ impl Scene for Matching {
 }
 
 impl Matching {
-    pub fn new(server_addr: &str) -> Result<Self, String> {
+    pub fn new(server_addr: &str, lineup: Lineup) -> Result<Self, String> {
         Ok(Self {
-            socket: MatchingSocket::bind("0.0.0.0:0", server_addr)
+            socket: MatchingSocket::bind("0.0.0.0:0", server_addr, lineup)
                 .map_err(|err| err.to_string())?,
             current_frame: 0,
         })
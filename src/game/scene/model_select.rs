@@ -0,0 +1,309 @@
+use std::path::{Path, PathBuf};
+
+use candle_core::Device;
+use sdl3::{pixels::Color, render::FRect};
+
+use crate::game::{
+    GameContext, GameState, PlayerInputs,
+    ai::load_inference_model,
+    input::{ButtonFlag, Direction},
+    render::text::{draw_text, draw_text_centered, text_height},
+    scene::{Scene, Scenes, main_menu::MainMenu},
+};
+
+const MODEL_EXTENSION: &str = "safetensors";
+const VISIBLE_ROWS: usize = 12;
+const ERROR_COLOR: Color = Color::RGB(200, 0, 0);
+const HINT_LABEL: &str = "M: Up   H: Back   L: Pick";
+
+#[derive(Clone, Copy, PartialEq)]
+enum ModelSlot {
+    Left,
+    Right,
+}
+
+impl ModelSlot {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Left => "Left",
+            Self::Right => "Right",
+        }
+    }
+}
+
+struct BrowserEntry {
+    path: PathBuf,
+    is_dir: bool,
+}
+
+/// File browser used to pick the left/right agent models at runtime
+pub struct ModelSelect {
+    slot: ModelSlot,
+    current_dir: PathBuf,
+    entries: Vec<BrowserEntry>,
+    scroll_pos: usize,
+    last_dir: Direction,
+    /// Why the last picked file couldn't be loaded, shown until the cursor moves
+    error: Option<String>,
+}
+
+impl Scene for ModelSelect {
+    fn enter(
+        &mut self,
+        _context: &GameContext,
+        _inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) {
+        self.refresh();
+    }
+
+    fn handle_input(
+        &mut self,
+        _context: &GameContext,
+        inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) -> Result<(), String> {
+        inputs.update_player1();
+        inputs.skip_player2();
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        let just_pressed = state.player1_inputs.just_pressed_buttons();
+
+        if ButtonFlag::H.intersects(just_pressed) {
+            return Ok(Some(Scenes::MainMenu(MainMenu::new())));
+        }
+
+        if ButtonFlag::M.intersects(just_pressed) {
+            if let Some(parent) = self.current_dir.parent() {
+                self.current_dir = parent.to_path_buf();
+                self.refresh();
+            }
+            return Ok(None);
+        }
+
+        if ButtonFlag::L.intersects(just_pressed) {
+            return Ok(self.activate_entry(context));
+        }
+
+        let held_dir = state.player1_inputs.dir();
+        if held_dir != self.last_dir {
+            if held_dir != Direction::Neutral {
+                self.error = None;
+            }
+            let len = self.entries.len().max(1);
+            self.scroll_pos = match held_dir {
+                Direction::Down => (self.scroll_pos + 1) % len,
+                Direction::Up => (len + self.scroll_pos - 1) % len,
+                _ => self.scroll_pos,
+            };
+            self.last_dir = held_dir;
+
+            if cfg!(feature = "debug")
+                && let Some(entry) = self.entries.get(self.scroll_pos)
+            {
+                println!("Model select: {}", entry.path.display());
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        _global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        _state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        let (w, h) = canvas.window().size();
+        let w = w as f32;
+        let h = h as f32;
+        let strings = &context.strings;
+
+        // Header shows which agent is being picked and where
+        let header_color = match self.slot {
+            ModelSlot::Left => Color::BLUE,
+            ModelSlot::Right => Color::RED,
+        };
+        canvas.set_draw_color(header_color);
+        canvas.fill_rect(FRect::new(0.0, 0.0, w, h / 12.0))?;
+        draw_text_centered(
+            canvas,
+            &strings.fill("{} model", &[&strings.get(self.slot.label())]),
+            w / 2.0,
+            h / 96.0,
+            h / 320.0,
+            Color::WHITE,
+        )?;
+        draw_text_centered(
+            canvas,
+            &self.current_dir.to_string_lossy(),
+            w / 2.0,
+            h / 24.0,
+            h / 480.0,
+            Color::WHITE,
+        )?;
+
+        // Room under the list for the hint and the error
+        let row_h = h / (VISIBLE_ROWS as f32 + 3.0);
+        let name_scale = row_h * 0.4 / text_height(1.0);
+        let first_row = self.scroll_pos.saturating_sub(VISIBLE_ROWS - 1);
+        for (row, entry) in self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(first_row)
+            .take(VISIBLE_ROWS)
+        {
+            let y = row_h * 1.5 + (row - first_row) as f32 * row_h;
+            let row_color = if row == self.scroll_pos {
+                Color::WHITE
+            } else if entry.is_dir {
+                Color::RGB(90, 90, 140)
+            } else {
+                Color::RGB(140, 140, 140)
+            };
+            canvas.set_draw_color(row_color);
+            canvas.fill_rect(FRect::new(w * 0.1, y, w * 0.8, row_h * 0.8))?;
+
+            let name = entry.path.file_name().map_or_else(
+                || entry.path.to_string_lossy(),
+                |name| name.to_string_lossy(),
+            );
+            let name = if entry.is_dir {
+                format!("{name}/")
+            } else {
+                name.into_owned()
+            };
+            draw_text(
+                canvas,
+                &name,
+                w * 0.12,
+                y + (row_h * 0.8 - text_height(name_scale)) / 2.0,
+                name_scale,
+                Color::BLACK,
+            )?;
+        }
+
+        draw_text_centered(
+            canvas,
+            strings.get(HINT_LABEL),
+            w / 2.0,
+            h * 11.0 / 12.0,
+            h / 320.0,
+            Color::GRAY,
+        )?;
+
+        if let Some(error) = &self.error {
+            let scale = h / 320.0;
+            draw_text_centered(
+                canvas,
+                error,
+                w / 2.0,
+                h - text_height(scale) * 2.0,
+                scale,
+                ERROR_COLOR,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn exit(&mut self, _context: &GameContext, _inputs: &mut PlayerInputs, _state: &mut GameState) {
+    }
+}
+
+impl ModelSelect {
+    pub fn new(context: &GameContext) -> Self {
+        let current_dir = Path::new(&context.left_agent_filepath)
+            .parent()
+            .and_then(|dir| std::fs::canonicalize(dir).ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        Self {
+            slot: ModelSlot::Left,
+            current_dir,
+            entries: Vec::new(),
+            scroll_pos: 0,
+            last_dir: Direction::Neutral,
+            error: None,
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.scroll_pos = 0;
+        self.entries = list_entries(&self.current_dir);
+    }
+
+    /// A model that fails to load stays unpicked, with the error shown under the list
+    fn activate_entry(&mut self, context: &mut GameContext) -> Option<Scenes> {
+        let entry = self.entries.get(self.scroll_pos)?;
+
+        if entry.is_dir {
+            self.current_dir = entry.path.clone();
+            self.refresh();
+            return None;
+        }
+
+        let model_path = entry.path.to_string_lossy().into_owned();
+        // Loaded up front so a broken file is caught here, the AI scenes take it from the context
+        let agent = match load_inference_model(&model_path, &Device::Cpu) {
+            Ok(agent) => agent,
+            Err(err) => {
+                let err = format!("Failed to load model '{model_path}': {err}");
+                if cfg!(feature = "debug") {
+                    println!("[WARNING] {err}");
+                }
+                self.error = Some(err);
+                return None;
+            }
+        };
+        context.picked_agents.insert(model_path.clone(), agent);
+
+        if cfg!(feature = "debug") {
+            println!("Selected model: {model_path}");
+        }
+
+        match self.slot {
+            ModelSlot::Left => {
+                context.left_agent_filepath = model_path;
+                context.agents_picked[0] = true;
+                // An explicit pick wins over the distilled default
+                context.distilled_agent_filepath = None;
+                self.slot = ModelSlot::Right;
+                None
+            }
+            ModelSlot::Right => {
+                context.right_agent_filepath = model_path;
+                context.agents_picked[1] = true;
+                Some(Scenes::MainMenu(MainMenu::new()))
+            }
+        }
+    }
+}
+
+/// Directories first, then model files, both sorted by name
+fn list_entries(dir: &Path) -> Vec<BrowserEntry> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<BrowserEntry> = read_dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter_map(|path| {
+            let is_dir = path.is_dir();
+            let is_model = path.extension().is_some_and(|ext| ext == MODEL_EXTENSION);
+            (is_dir || is_model).then_some(BrowserEntry { path, is_dir })
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.path.cmp(&b.path)));
+    entries
+}
@@ -1,16 +1,24 @@
+use std::collections::VecDeque;
+
 use crate::{
     game::{
         GameContext, GameState, MAX_ROLLBACK_FRAMES, PlayerInputs, Side,
+        audio::SoundCue,
         net::stream::UdpStream,
         scene::{
             Scene, Scenes,
             gameplay::{GameplayScene, GameplayScenes},
             main_menu::MainMenu,
+            victory::Victory,
         },
+        settings::NetplaySettings,
     },
     ring_buf::RingBuf,
 };
 
+/// Frames a sound waits before playing, rollbacks rarely reach further back than this
+const SOUND_CONFIRM_FRAMES: usize = 8;
+
 pub struct OnlinePlay {
     local_side: Side,
     scene: GameplayScenes,
@@ -19,12 +27,22 @@ pub struct OnlinePlay {
     connection: UdpStream,
     current_frame: usize,
     delay: usize,
+    /// Frames a rollback may rewind, agreed on with the peer
+    max_rollback: usize,
+    /// Frames re-simulated by rollbacks since the match started
+    rolled_back: usize,
+    /// Sounds made by frames still inside the confirm window, oldest first
+    pending_sounds: VecDeque<(usize, Vec<SoundCue>)>,
+    /// Frames before this one have had their sounds played, re-simulating them stays quiet
+    sounds_confirmed: usize,
 }
 
 impl Scene for OnlinePlay {
     fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
         inputs.set_delay(self.delay);
         self.scene.enter(context, state);
+        // Characters may have been swapped since construction, so restart the history from here
+        self.game_state_history = RingBuf::new((self.scene.clone(), state.clone()));
     }
 
     fn handle_input(
@@ -49,13 +67,17 @@ impl Scene for OnlinePlay {
             .connection
             .update(self.current_frame, local_inputs, peer_inputs)
             .map_err(|err| err.to_string())?;
-        self.rollback(context, inputs, state, rollback, fastforward);
+        self.rollback(context, inputs, state, rollback, fastforward)?;
         self.fast_forward(context, inputs, state, fastforward);
 
         Ok(())
     }
 
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
         if self.connection.is_aborted() {
             return Ok(Some(Scenes::MainMenu(MainMenu::new())));
         }
@@ -66,13 +88,23 @@ impl Scene for OnlinePlay {
             self.scene.enter(context, state);
         }
 
+        self.defer_sounds(self.current_frame, state);
         self.current_frame += 1;
 
         self.append_game_snapshot(state);
 
         match self.scene {
-            GameplayScenes::Exit => Ok(Some(Scenes::MainMenu(MainMenu::new()))),
-            _ => Ok(None),
+            GameplayScenes::Exit(results) => {
+                // Nothing can roll back once the match is over
+                self.sounds_confirmed = self.current_frame;
+                self.confirm_sounds(state);
+                Ok(Some(Scenes::Victory(Victory::new(results))))
+            }
+            _ => {
+                self.sounds_confirmed = self.current_frame.saturating_sub(SOUND_CONFIRM_FRAMES);
+                self.confirm_sounds(state);
+                Ok(None)
+            }
         }
     }
 
@@ -100,7 +132,13 @@ impl Scene for OnlinePlay {
 }
 
 impl OnlinePlay {
-    pub fn new(connection: UdpStream, local_side: Side, state: &GameState) -> Self {
+    /// `netplay` is what the peers settled on during the handshake
+    pub fn new(
+        connection: UdpStream,
+        local_side: Side,
+        netplay: NetplaySettings,
+        state: &GameState,
+    ) -> Self {
         let scene = GameplayScenes::new_round_start((0, 0));
         let initial_state = (scene.clone(), state.clone());
         Self {
@@ -109,7 +147,11 @@ impl OnlinePlay {
             scene,
             current_frame: 0,
             game_state_history: RingBuf::new(initial_state),
-            delay: 3,
+            delay: netplay.input_delay,
+            max_rollback: netplay.max_rollback,
+            rolled_back: 0,
+            pending_sounds: VecDeque::new(),
+            sounds_confirmed: 0,
         }
     }
 
@@ -120,11 +162,18 @@ impl OnlinePlay {
         state: &mut GameState,
         rollback_frames: usize,
         fastforward_frames: usize,
-    ) {
+    ) -> Result<(), String> {
         if rollback_frames <= self.delay {
-            return;
+            return Ok(());
         }
         let frames = rollback_frames - self.delay;
+        if frames > self.max_rollback {
+            return Err(format!(
+                "Peer fell {frames} frames behind, past the {} frame rollback limit",
+                self.max_rollback
+            ));
+        }
+        self.rolled_back += frames;
 
         if cfg!(feature = "debug") {
             println!("rolling back: {frames}");
@@ -133,8 +182,13 @@ impl OnlinePlay {
         let (old_scene, old_state) = self.game_state_history.rewind(frames);
         self.scene = old_scene;
         *state = old_state;
+        // The re-simulated frames make their sounds again, however they turn out this time
+        let resimulated_from = self.current_frame.saturating_sub(frames);
+        self.pending_sounds
+            .retain(|(frame, _)| *frame < resimulated_from);
 
         self.fast_simulate(context, inputs, state, frames, fastforward_frames);
+        Ok(())
     }
 
     fn fast_simulate(
@@ -161,6 +215,7 @@ impl OnlinePlay {
                 self.scene = new_scene;
             }
 
+            self.defer_sounds(self.current_frame.saturating_sub(frame), state);
             self.append_game_snapshot(state);
         }
     }
@@ -197,12 +252,43 @@ impl OnlinePlay {
                 self.scene = new_scene;
             }
 
+            self.defer_sounds(self.current_frame + frames - frame, state);
             self.append_game_snapshot(state);
         }
 
         self.current_frame += frames;
     }
 
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.scene.cinematic(state)
+    }
+
+    pub fn rolled_back(&self) -> usize {
+        self.rolled_back
+    }
+
+    /// Holds the sounds `frame` made until a rollback can no longer undo it
+    fn defer_sounds(&mut self, frame: usize, state: &mut GameState) {
+        let cues = std::mem::take(&mut state.sounds);
+        if frame >= self.sounds_confirmed && !cues.is_empty() {
+            self.pending_sounds.push_back((frame, cues));
+        }
+    }
+
+    /// Hands the sounds of frames before `sounds_confirmed` to the game to play
+    fn confirm_sounds(&mut self, state: &mut GameState) {
+        let confirmed = self
+            .pending_sounds
+            .iter()
+            .take_while(|(frame, _)| *frame < self.sounds_confirmed)
+            .count();
+        state.sounds.extend(
+            self.pending_sounds
+                .drain(..confirmed)
+                .flat_map(|(_, cues)| cues),
+        );
+    }
+
     fn append_game_snapshot(&mut self, state: &GameState) {
         self.game_state_history
             .append((self.scene.clone(), state.clone()));
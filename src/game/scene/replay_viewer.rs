@@ -0,0 +1,215 @@
+use std::path::PathBuf;
+
+use sdl3::pixels::Color;
+
+use crate::game::{
+    GameContext, GameState, PlayerInputs,
+    input::{ButtonFlag, Direction},
+    render::text::{draw_text_centered, text_height},
+    replay::Replay,
+    scene::{
+        Scene, Scenes,
+        gameplay::{GameplayScene, GameplayScenes},
+        victory::Victory,
+    },
+};
+
+const HINT_LABEL: &str = "L: Pause   M: Step   H: Speed   Right: Next round";
+
+/// Screen ticks each replay frame is held for
+#[derive(Clone, Copy, PartialEq)]
+enum Speed {
+    Full,
+    Half,
+    Quarter,
+}
+
+impl Speed {
+    fn ticks_per_frame(self) -> usize {
+        match self {
+            Self::Full => 1,
+            Self::Half => 2,
+            Self::Quarter => 4,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Full => Self::Half,
+            Self::Half => Self::Quarter,
+            Self::Quarter => Self::Full,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Full => "1x",
+            Self::Half => "0.5x",
+            Self::Quarter => "0.25x",
+        }
+    }
+}
+
+/// Re-simulates a saved local match from its inputs, player1's keys control the playback
+pub struct ReplayViewer {
+    replay: Replay,
+    scene: GameplayScenes,
+    /// Next replay frame to simulate
+    frame: usize,
+    paused: bool,
+    speed: Speed,
+    /// Screen ticks since the last simulated frame
+    ticks: usize,
+    last_dir: Direction,
+}
+
+impl Scene for ReplayViewer {
+    fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
+        inputs.local_key_mapping();
+        self.scene.enter(context, state);
+    }
+
+    fn handle_input(
+        &mut self,
+        _context: &GameContext,
+        inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) -> Result<(), String> {
+        inputs.update_player1();
+        inputs.skip_player2();
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        // Player1's live inputs, the replay's overwrite them once a frame is simulated
+        let just_pressed = state.player1_inputs.just_pressed_buttons();
+        let held_dir = state.player1_inputs.dir();
+        let next_round = held_dir == Direction::Right && self.last_dir != Direction::Right;
+        self.last_dir = held_dir;
+
+        if ButtonFlag::L.intersects(just_pressed) {
+            self.paused = !self.paused;
+        }
+        if ButtonFlag::H.intersects(just_pressed) {
+            self.speed = self.speed.next();
+        }
+
+        if next_round {
+            self.skip_round(context, state);
+        } else if self.paused {
+            if ButtonFlag::M.intersects(just_pressed) {
+                self.step(context, state);
+            }
+        } else {
+            self.ticks += 1;
+            if self.ticks >= self.speed.ticks_per_frame() {
+                self.ticks = 0;
+                self.step(context, state);
+            }
+        }
+
+        match self.scene {
+            GameplayScenes::Exit(results) => Ok(Some(Scenes::Victory(Victory::new(results)))),
+            _ => Ok(None),
+        }
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        self.scene.render(canvas, global_textures, context, state)?;
+
+        let (w, h) = canvas.window().size();
+        let (w, h) = (w as f32, h as f32);
+        let scale = h / 250.0;
+        let strings = &context.strings;
+        let status = if self.paused {
+            strings.get("PAUSED")
+        } else {
+            self.speed.label()
+        };
+        draw_text_centered(
+            canvas,
+            &strings.fill(
+                "REPLAY {}   {}/{}",
+                &[&status, &self.frame, &self.replay.frames_len()],
+            ),
+            w / 2.0,
+            h - text_height(scale) * 6.0,
+            scale,
+            Color::WHITE,
+        )?;
+        draw_text_centered(
+            canvas,
+            strings.get(HINT_LABEL),
+            w / 2.0,
+            h - text_height(scale) * 4.0,
+            scale,
+            Color::GRAY,
+        )
+    }
+
+    fn exit(&mut self, context: &GameContext, _inputs: &mut PlayerInputs, state: &mut GameState) {
+        self.scene.exit(context, state);
+    }
+}
+
+impl ReplayViewer {
+    /// Loads the newest replay and queues its characters and stage
+    pub fn latest(context: &mut GameContext) -> Result<Self, String> {
+        Self::with_replay(context, Replay::load_latest()?)
+    }
+
+    /// Loads the replay at `path` and queues its characters and stage
+    pub fn open(context: &mut GameContext, path: PathBuf) -> Result<Self, String> {
+        Self::with_replay(context, Replay::load(path)?)
+    }
+
+    fn with_replay(context: &mut GameContext, replay: Replay) -> Result<Self, String> {
+        replay.pick(context)?;
+        Ok(Self {
+            replay,
+            scene: GameplayScenes::new_round_start((0, 0)),
+            frame: 0,
+            paused: false,
+            speed: Speed::Full,
+            ticks: 0,
+            last_dir: Direction::Neutral,
+        })
+    }
+
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.scene.cinematic(state)
+    }
+
+    /// Simulates the next recorded frame, false once the replay has run out
+    fn step(&mut self, context: &GameContext, state: &mut GameState) -> bool {
+        if matches!(self.scene, GameplayScenes::Exit(_))
+            || !self.replay.play_frame(self.frame, state)
+        {
+            return false;
+        }
+        if let Some(new_gameplay_scene) = self.scene.update(context, state) {
+            self.scene.exit(context, state);
+            self.scene = new_gameplay_scene;
+            self.scene.enter(context, state);
+        }
+        self.frame += 1;
+        true
+    }
+
+    /// Plays through to the start of the next round, or to the end of the replay
+    fn skip_round(&mut self, context: &GameContext, state: &mut GameState) {
+        // Out of the round start being shown first, then on to the next one
+        while matches!(self.scene, GameplayScenes::RoundStart(_)) && self.step(context, state) {}
+        while !matches!(self.scene, GameplayScenes::RoundStart(_)) && self.step(context, state) {}
+    }
+}
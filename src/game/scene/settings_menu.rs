@@ -0,0 +1,317 @@
+use sdl3::{keyboard::Keycode, pixels::Color};
+
+use crate::game::{
+    GameContext, GameState, PlayerInputs,
+    display::{DISPLAY_MODES, RESOLUTIONS, SCALING_FILTERS},
+    input::{ButtonFlag, Direction},
+    locale::Strings,
+    render::text::draw_text_centered,
+    scene::{Scene, Scenes, main_menu::MainMenu},
+    settings::{MAX_INPUT_DELAY, MAX_ROLLBACK, Settings, VOLUME_STEPS},
+};
+
+/// Controls rows before the first key binding, the page tab, the player and their profile
+const CONTROLS_HEADER_ROWS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Page {
+    Video,
+    Audio,
+    Controls,
+    Netplay,
+    General,
+}
+
+const PAGES: [Page; 5] = [
+    Page::Video,
+    Page::Audio,
+    Page::Controls,
+    Page::Netplay,
+    Page::General,
+];
+
+impl Page {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Video => "Video",
+            Self::Audio => "Audio",
+            Self::Controls => "Controls",
+            Self::Netplay => "Netplay",
+            Self::General => "General",
+        }
+    }
+}
+
+/// Up and down pick the row, left and right change it, the top row flips the page
+///
+/// H saves and applies everything, M backs out unchanged, L on a key binding waits for the new key
+pub struct SettingsMenu {
+    settings: Settings,
+    page: Page,
+    row: usize,
+    /// Player whose keys the controls page shows
+    player: usize,
+    /// Key binding slot waiting on a key press
+    rebinding: Option<usize>,
+    /// Languages with a string table, found when the menu opened
+    languages: Vec<String>,
+    last_dir: Direction,
+}
+
+impl Scene for SettingsMenu {
+    fn enter(
+        &mut self,
+        _context: &GameContext,
+        _inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) {
+    }
+
+    fn handle_input(
+        &mut self,
+        _context: &GameContext,
+        inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) -> Result<(), String> {
+        inputs.update_player1();
+        inputs.skip_player2();
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        let held_dir = state.player1_inputs.dir();
+        // Every key goes to the binding until one is pressed
+        if self.rebinding.is_some() {
+            self.last_dir = held_dir;
+            return Ok(None);
+        }
+
+        let just_pressed = state.player1_inputs.just_pressed_buttons();
+
+        if ButtonFlag::M.intersects(just_pressed) {
+            return Ok(Some(Scenes::MainMenu(MainMenu::new())));
+        }
+
+        if ButtonFlag::H.intersects(just_pressed) {
+            context.change_settings(self.settings.clone());
+            return Ok(Some(Scenes::MainMenu(MainMenu::new())));
+        }
+
+        if ButtonFlag::L.intersects(just_pressed)
+            && self.page == Page::Controls
+            && self.row >= CONTROLS_HEADER_ROWS
+        {
+            self.rebinding = Some(self.row - CONTROLS_HEADER_ROWS);
+        }
+
+        if held_dir != self.last_dir {
+            let rows = self.rows(&context.strings).len();
+            match held_dir {
+                Direction::Down => self.row = (self.row + 1) % rows,
+                Direction::Up => self.row = (self.row + rows - 1) % rows,
+                Direction::Right => self.step_row(1),
+                Direction::Left => self.step_row(-1),
+                _ => {}
+            }
+            self.last_dir = held_dir;
+        }
+
+        Ok(None)
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        _global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        _state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        let strings = &context.strings;
+        let (w, h) = canvas.window().size();
+        let w = w as f32;
+        let h = h as f32;
+
+        let title_scale = h / 100.0;
+        let row_scale = h / 160.0;
+        draw_text_centered(
+            canvas,
+            strings.get("SETTINGS"),
+            w / 2.0,
+            h / 8.0,
+            title_scale,
+            Color::WHITE,
+        )?;
+
+        for (row, label) in self.rows(strings).iter().enumerate() {
+            let color = if row == self.row {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+            let y = h / 4.0 + row as f32 * h / 16.0;
+            draw_text_centered(canvas, label, w / 2.0, y, row_scale, color)?;
+        }
+
+        let hint = match self.page {
+            Page::Controls => "L: Rebind   H: Save   M: Back",
+            _ => "H: Save   M: Back",
+        };
+        draw_text_centered(
+            canvas,
+            strings.get(hint),
+            w / 2.0,
+            h * 11.0 / 12.0,
+            row_scale / 2.0,
+            Color::GRAY,
+        )?;
+
+        Ok(())
+    }
+
+    fn exit(&mut self, _context: &GameContext, _inputs: &mut PlayerInputs, _state: &mut GameState) {
+    }
+}
+
+impl SettingsMenu {
+    /// Starts on the settings in use
+    pub fn new(context: &GameContext) -> Self {
+        Self {
+            settings: context.settings.clone(),
+            page: Page::Video,
+            row: 0,
+            player: 0,
+            rebinding: None,
+            languages: context.languages.clone(),
+            last_dir: Direction::Neutral,
+        }
+    }
+
+    /// Binds the key pressed while waiting on one, false if nothing was waiting
+    pub fn capture_key(&mut self, key: Keycode) -> bool {
+        let Some(slot) = self.rebinding.take() else {
+            return false;
+        };
+        self.settings.rebind(self.player, slot, key);
+        true
+    }
+
+    /// Labels of the page's rows, the page tab first
+    fn rows(&self, strings: &Strings) -> Vec<String> {
+        let row = |name: &str, value: &dyn std::fmt::Display| {
+            format!("< {}: {value} >", strings.get(name))
+        };
+        let on_off = |on: bool| strings.get(if on { "On" } else { "Off" });
+        let mut rows = vec![format!("< {} >", strings.get(self.page.name()))];
+        match self.page {
+            Page::Video => {
+                let display = self.settings.display;
+                let (res_w, res_h) = display.resolution;
+                rows.extend([
+                    row("Mode", &strings.get(display.mode.name())),
+                    row("Resolution", &format!("{res_w}x{res_h}")),
+                    row("VSync", &on_off(display.vsync)),
+                    row("Filter", &strings.get(display.filter.name())),
+                    row("Pixel Perfect", &on_off(display.pixel_perfect)),
+                ]);
+            }
+            Page::Audio => {
+                let audio = self.settings.audio;
+                rows.extend([
+                    row("Music", &format!("{}/{VOLUME_STEPS}", audio.music_volume)),
+                    row(
+                        "Effects",
+                        &format!("{}/{VOLUME_STEPS}", audio.effects_volume),
+                    ),
+                ]);
+            }
+            Page::Controls => {
+                let profile = self.settings.picked_profiles[self.player];
+                rows.extend([
+                    format!("< {} >", strings.fill("Player {}", &[&(self.player + 1)])),
+                    row("Profile", &self.settings.profiles[profile].name),
+                ]);
+                let labels = self.settings.controls.0[self.player].labels();
+                rows.extend(labels.into_iter().enumerate().map(|(slot, label)| {
+                    match (self.rebinding == Some(slot), label.split_once(':')) {
+                        (true, Some((name, _))) => {
+                            format!("{name}: {}", strings.get("Press a key"))
+                        }
+                        _ => label,
+                    }
+                }));
+            }
+            Page::Netplay => {
+                let netplay = self.settings.netplay;
+                rows.extend([
+                    row("Input Delay", &netplay.input_delay),
+                    row("Max Rollback", &netplay.max_rollback),
+                ]);
+            }
+            Page::General => {
+                let language = match self.settings.language.as_str() {
+                    "" => strings.get("English"),
+                    language => language,
+                };
+                rows.push(row("Language", &language));
+            }
+        }
+        rows
+    }
+
+    /// Cycles the picked row's option, toggles just flip and numbers stop at their ends
+    fn step_row(&mut self, step: isize) {
+        let step_number = |number: usize, max: usize| number.saturating_add_signed(step).min(max);
+        let display = &mut self.settings.display;
+        let audio = &mut self.settings.audio;
+        match (self.page, self.row) {
+            (_, 0) => self.page = cycle(&PAGES, self.page, step),
+            (Page::Video, 1) => display.mode = cycle(&DISPLAY_MODES, display.mode, step),
+            (Page::Video, 2) => display.resolution = cycle(&RESOLUTIONS, display.resolution, step),
+            (Page::Video, 3) => display.vsync = !display.vsync,
+            (Page::Video, 4) => display.filter = cycle(&SCALING_FILTERS, display.filter, step),
+            (Page::Video, _) => display.pixel_perfect = !display.pixel_perfect,
+            (Page::Audio, 1) => {
+                audio.music_volume =
+                    step_number(audio.music_volume as usize, VOLUME_STEPS as usize) as u8
+            }
+            (Page::Audio, _) => {
+                audio.effects_volume =
+                    step_number(audio.effects_volume as usize, VOLUME_STEPS as usize) as u8
+            }
+            (Page::Controls, 1) => self.player = 1 - self.player,
+            (Page::Controls, 2) => {
+                let profiles: Vec<usize> = (0..self.settings.profiles.len()).collect();
+                let profile = self.settings.picked_profiles[self.player];
+                self.settings
+                    .pick_profile(self.player, cycle(&profiles, profile, step));
+            }
+            (Page::Controls, _) => {}
+            (Page::Netplay, 1) => {
+                let netplay = &mut self.settings.netplay;
+                netplay.input_delay = step_number(netplay.input_delay, MAX_INPUT_DELAY);
+            }
+            (Page::Netplay, _) => {
+                let netplay = &mut self.settings.netplay;
+                netplay.max_rollback = step_number(netplay.max_rollback, MAX_ROLLBACK).max(1);
+            }
+            (Page::General, _) => {
+                let languages: Vec<&str> = self.languages.iter().map(String::as_str).collect();
+                let language = cycle(&languages, self.settings.language.as_str(), step);
+                self.settings.language = language.to_string();
+            }
+        }
+    }
+}
+
+/// The option `step` away from `current`, wrapping around, options that aren't offered start on the first
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, step: isize) -> T {
+    let index = options
+        .iter()
+        .position(|option| *option == current)
+        .unwrap_or_default();
+    options[(index as isize + step).rem_euclid(options.len() as isize) as usize]
+}
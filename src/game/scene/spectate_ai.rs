@@ -1,24 +1,34 @@
 use candle_core::Device;
-use candle_nn::VarMap;
+use sdl3::{keyboard::Keycode, pixels::Color};
 
 use crate::game::{
     GameContext, GameState, PlayerInputs,
-    ai::{get_agent_action, load_model, observation_with_inv, take_agent_turn},
+    ai::{ActionRepeat, ObservationBuffer, sample_agent_actions, take_agent_turn},
+    render::text::{draw_text_centered, text_height},
     scene::{
         Scene, Scenes,
         gameplay::{GameplayScene, GameplayScenes},
         main_menu::MainMenu,
+        victory::Victory,
     },
 };
 
+const ATTRACT_LABEL: &str = "DEMO   Press any key";
+
 pub struct SpectateAi {
     scene: GameplayScenes,
-    _var_map1: VarMap,
-    _var_map2: VarMap,
     ai_agent1: candle_nn::Sequential,
-    ai_agent2: candle_nn::Sequential,
+    /// None when both sides use the same model, so both can run in one batch
+    ai_agent2: Option<candle_nn::Sequential>,
+    observations: ObservationBuffer,
+    action_repeat1: ActionRepeat,
+    action_repeat2: ActionRepeat,
     device: Device,
     rng: rand::rngs::ThreadRng,
+    /// Started by the idle main menu, any key goes back to it
+    attract: bool,
+    /// A key was pressed during the attract demo
+    interrupted: bool,
 }
 
 impl Scene for SpectateAi {
@@ -34,24 +44,36 @@ impl Scene for SpectateAi {
         state: &mut GameState,
     ) -> Result<(), String> {
         if let GameplayScenes::DuringRound(during_round) = &self.scene {
-            let timer = during_round.timer();
-            let (obs, obs_inv) = observation_with_inv(context, state, timer, &self.device)
-                .map_err(|err| err.to_string())?;
+            if self.action_repeat1.ready() || self.action_repeat2.ready() {
+                self.observations.fill(context, state, during_round.timer());
+                let actions = self.sample_actions().map_err(|err| err.to_string())?;
+
+                if self.action_repeat1.ready() {
+                    self.action_repeat1.set(actions[0]);
+                }
+                if self.action_repeat2.ready() {
+                    self.action_repeat2.set(actions[1]);
+                }
+            }
 
-            // Agent1
-            let action = get_agent_action(&self.ai_agent1, &obs, &mut self.rng)
-                .map_err(|err| err.to_string())?;
+            let action = self.action_repeat1.tick();
             take_agent_turn(&mut inputs.player1, &mut state.player1_inputs, action);
-            // Agent2
-            let action = get_agent_action(&self.ai_agent2, &obs_inv, &mut self.rng)
-                .map_err(|err| err.to_string())?;
+            let action = self.action_repeat2.tick();
             take_agent_turn(&mut inputs.player2, &mut state.player2_inputs, action);
         }
 
         Ok(())
     }
 
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        if self.interrupted {
+            return Ok(Some(Scenes::MainMenu(MainMenu::new())));
+        }
+
         if let Some(new_gameplay_scene) = self.scene.update(context, state) {
             self.scene.exit(context, state);
             self.scene = new_gameplay_scene;
@@ -59,7 +81,8 @@ impl Scene for SpectateAi {
         }
 
         match self.scene {
-            GameplayScenes::Exit => Ok(Some(Scenes::MainMenu(MainMenu::new()))),
+            GameplayScenes::Exit(_) if self.attract => Ok(Some(Scenes::MainMenu(MainMenu::new()))),
+            GameplayScenes::Exit(results) => Ok(Some(Scenes::Victory(Victory::new(results)))),
             _ => Ok(None),
         }
     }
@@ -71,7 +94,22 @@ impl Scene for SpectateAi {
         context: &GameContext,
         state: &GameState,
     ) -> Result<(), sdl3::Error> {
-        self.scene.render(canvas, global_textures, context, state)
+        self.scene.render(canvas, global_textures, context, state)?;
+        if !self.attract {
+            return Ok(());
+        }
+
+        let (w, h) = canvas.window().size();
+        let (w, h) = (w as f32, h as f32);
+        let scale = h / 250.0;
+        draw_text_centered(
+            canvas,
+            context.strings.get(ATTRACT_LABEL),
+            w / 2.0,
+            h - text_height(scale) * 4.0,
+            scale,
+            Color::WHITE,
+        )
     }
 
     fn exit(&mut self, context: &GameContext, _inputs: &mut PlayerInputs, state: &mut GameState) {
@@ -80,23 +118,59 @@ impl Scene for SpectateAi {
 }
 
 impl SpectateAi {
-    pub fn new(left_agent_path: &str, right_agent_path: &str) -> Result<Self, String> {
-        let device = Device::Cpu;
-        let (_var_map1, ai_agent1) =
-            load_model(left_agent_path, &device)
-            .map_err(|err| err.to_string())?;
-        let (_var_map2, ai_agent2) =
-            load_model(right_agent_path, &device)
-            .map_err(|err| err.to_string())?;
-
-        Ok(Self {
+    /// `ai_agent2` is None when the right side plays the same model as the left
+    pub fn new(
+        ai_agent1: candle_nn::Sequential,
+        ai_agent2: Option<candle_nn::Sequential>,
+        action_repeat: usize,
+    ) -> Self {
+        Self {
             scene: GameplayScenes::new_round_start((0, 0)),
-            _var_map1,
-            _var_map2,
             ai_agent1,
             ai_agent2,
-            device,
+            observations: ObservationBuffer::new(),
+            action_repeat1: ActionRepeat::new(action_repeat),
+            action_repeat2: ActionRepeat::new(action_repeat),
+            device: Device::Cpu,
             rng: rand::rng(),
-        })
+            attract: false,
+            interrupted: false,
+        }
+    }
+
+    /// Runs as the main menu's idle demo
+    pub fn with_attract(mut self) -> Self {
+        self.attract = true;
+        self
+    }
+
+    /// Any key ends the attract demo, false outside of one
+    pub fn capture_key(&mut self, _key: Keycode) -> bool {
+        self.interrupted = self.interrupted || self.attract;
+        self.attract
+    }
+
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.scene.cinematic(state)
+    }
+
+    /// (agent1 action, agent2 action) for the filled observation buffer
+    fn sample_actions(&mut self) -> candle_core::Result<[u32; 2]> {
+        let batch = self.observations.batch(&self.device)?;
+        let mut actions = [0; 2];
+        match &self.ai_agent2 {
+            None => sample_agent_actions(&self.ai_agent1, &batch, &mut self.rng, &mut actions)?,
+            Some(ai_agent2) => {
+                let (action1, action2) = actions.split_at_mut(1);
+                sample_agent_actions(
+                    &self.ai_agent1,
+                    &batch.narrow(0, 0, 1)?,
+                    &mut self.rng,
+                    action1,
+                )?;
+                sample_agent_actions(ai_agent2, &batch.narrow(0, 1, 1)?, &mut self.rng, action2)?;
+            }
+        }
+        Ok(actions)
     }
 }
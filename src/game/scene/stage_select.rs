@@ -0,0 +1,153 @@
+use sdl3::{pixels::Color, render::FRect};
+
+use crate::game::{
+    GameContext, GameState, PlayerInputs,
+    input::{ButtonFlag, Direction},
+    render::text::draw_text_centered,
+    scene::{
+        Scene, Scenes,
+        character_select::{AfterSelect, CharacterSelect},
+        local_play::LocalPlay,
+    },
+};
+
+/// Share of the screen width the stage preview takes up
+const PREVIEW_SIZE: f32 = 0.5;
+
+/// Left and right cycle the stages, L fights on the picked one, M goes back to character select
+pub struct StageSelect {
+    cursor: usize,
+    last_dir: Direction,
+}
+
+impl Scene for StageSelect {
+    fn enter(
+        &mut self,
+        _context: &GameContext,
+        _inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) {
+    }
+
+    fn handle_input(
+        &mut self,
+        _context: &GameContext,
+        inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) -> Result<(), String> {
+        inputs.update_player1();
+        inputs.skip_player2();
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        let just_pressed = state.player1_inputs.just_pressed_buttons();
+
+        if ButtonFlag::M.intersects(just_pressed) {
+            return Ok(Some(Scenes::CharacterSelect(CharacterSelect::new(
+                AfterSelect::LocalPlay,
+            ))));
+        }
+
+        if ButtonFlag::L.intersects(just_pressed) {
+            context.pick_stage(self.cursor)?;
+            return Ok(Some(Scenes::LocalPlay(LocalPlay::new())));
+        }
+
+        let stage_count = context.stages().len();
+        let held_dir = state.player1_inputs.dir();
+        if held_dir != self.last_dir {
+            match held_dir {
+                Direction::Right => self.cursor = (self.cursor + 1) % stage_count,
+                Direction::Left => self.cursor = (self.cursor + stage_count - 1) % stage_count,
+                _ => {}
+            }
+            self.last_dir = held_dir;
+        }
+
+        Ok(None)
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        _state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        let (w, h) = canvas.window().size();
+        let w = w as f32;
+        let h = h as f32;
+
+        let title_scale = h / 100.0;
+        let row_scale = h / 160.0;
+        let strings = &context.strings;
+        draw_text_centered(
+            canvas,
+            strings.get("STAGE SELECT"),
+            w / 2.0,
+            h / 10.0,
+            title_scale,
+            Color::WHITE,
+        )?;
+
+        let stage = &context.stages()[self.cursor];
+        let preview_w = w * PREVIEW_SIZE;
+        let preview_h = preview_w * h / w;
+        let preview = FRect::new((w - preview_w) / 2.0, h / 4.0, preview_w, preview_h);
+        stage.render(canvas, global_textures, preview, 0.0)?;
+        canvas.set_draw_color(Color::WHITE);
+        canvas.draw_rect(preview)?;
+
+        draw_text_centered(
+            canvas,
+            &format!("< {} >", stage.name()),
+            w / 2.0,
+            preview.y + preview.h + row_scale * 4.0,
+            row_scale,
+            Color::WHITE,
+        )?;
+        if let Some(music) = stage.music() {
+            // Just the file name, the directories don't mean anything to players
+            let track = std::path::Path::new(music)
+                .file_stem()
+                .map_or(music.into(), |stem| stem.to_string_lossy());
+            draw_text_centered(
+                canvas,
+                &strings.fill("Music: {}", &[&track]),
+                w / 2.0,
+                preview.y + preview.h + row_scale * 14.0,
+                row_scale / 1.5,
+                Color::GRAY,
+            )?;
+        }
+
+        draw_text_centered(
+            canvas,
+            strings.get("L: Fight   M: Back"),
+            w / 2.0,
+            h * 11.0 / 12.0,
+            row_scale / 2.0,
+            Color::GRAY,
+        )?;
+
+        Ok(())
+    }
+
+    fn exit(&mut self, _context: &GameContext, _inputs: &mut PlayerInputs, _state: &mut GameState) {
+    }
+}
+
+impl StageSelect {
+    /// Starts on the stage last fought on
+    pub fn new(context: &GameContext) -> Self {
+        Self {
+            cursor: context.stage,
+            last_dir: Direction::Neutral,
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use sdl3::pixels::Color;
+
+use crate::game::{
+    GameContext, GameState, PlayerInputs, Side,
+    input::{ButtonFlag, Direction, RelativeDirection, RelativeMotion},
+    render::text::{draw_text_centered, text_height},
+    scene::{
+        Scene, Scenes,
+        gameplay::{GameplayScene, GameplayScenes},
+        main_menu::MainMenu,
+    },
+};
+
+/// Ticks a cleared lesson stays up before the next one
+const CLEARED_FRAMES: usize = 60;
+/// How close the dummy walks in before attacking in the block lesson
+const DUMMY_ATTACK_RANGE: f32 = 120.0;
+/// Ticks the dummy waits between attacks
+const DUMMY_ATTACK_COOLDOWN: usize = 90;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Lesson {
+    WalkForward,
+    WalkBack,
+    Jump,
+    Dash,
+    Special,
+    Block,
+    Done,
+}
+
+const LESSONS: [Lesson; 7] = [
+    Lesson::WalkForward,
+    Lesson::WalkBack,
+    Lesson::Jump,
+    Lesson::Dash,
+    Lesson::Special,
+    Lesson::Block,
+    Lesson::Done,
+];
+
+impl Lesson {
+    fn prompt(self) -> &'static str {
+        match self {
+            Self::WalkForward => "Hold forward (6) to walk in",
+            Self::WalkBack => "Hold back (4) to walk away",
+            Self::Jump => "Press up (8) to jump",
+            Self::Dash => "Tap forward twice (66) to dash",
+            Self::Special => "Roll down to forward (236) and press a button",
+            Self::Block => "Hold back (4) to block the attack",
+            Self::Done => "Tutorial complete!   L: Main menu",
+        }
+    }
+
+    /// Frames held for the walks, times done for the rest
+    fn goal(self) -> usize {
+        match self {
+            Self::WalkForward | Self::WalkBack => 45,
+            Self::Jump | Self::Dash => 2,
+            Self::Special | Self::Block => 3,
+            Self::Done => 0,
+        }
+    }
+
+    /// Whether player1 is doing what the lesson asks on this tick
+    fn performed(self, context: &GameContext, state: &GameState) -> bool {
+        let inputs = &state.player1_inputs;
+        let (motion, buttons) = inputs.move_buf()[0];
+        let (dir, motion) = match state.player1.side() {
+            Side::Left => (inputs.dir().on_left_side(), motion.on_left_side()),
+            Side::Right => (inputs.dir().on_right_side(), motion.on_right_side()),
+        };
+        match self {
+            Self::WalkForward => dir == RelativeDirection::Forward,
+            Self::WalkBack => dir == RelativeDirection::Back,
+            Self::Jump => matches!(
+                dir,
+                RelativeDirection::Up | RelativeDirection::UpBack | RelativeDirection::UpForward
+            ),
+            Self::Dash => motion.contains(RelativeMotion::ForwardForward),
+            Self::Special => motion.contains(RelativeMotion::QcForward) && !buttons.is_empty(),
+            Self::Block => state.player1.in_block_stun(&context.player1),
+            Self::Done => false,
+        }
+    }
+}
+
+/// Walks player1 through movement, a motion input and blocking, player2 is a scripted dummy
+pub struct Tutorial {
+    scene: GameplayScenes,
+    lesson: usize,
+    progress: usize,
+    /// Performed on the last tick, the lessons that count presses only count the first tick
+    performed: bool,
+    /// Ticks left showing the cleared lesson
+    cleared: usize,
+    /// Ticks until the dummy can attack again
+    dummy_cooldown: usize,
+}
+
+impl Scene for Tutorial {
+    fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
+        inputs.local_key_mapping();
+        self.scene.enter(context, state);
+    }
+
+    fn handle_input(
+        &mut self,
+        _context: &GameContext,
+        inputs: &mut PlayerInputs,
+        state: &mut GameState,
+    ) -> Result<(), String> {
+        inputs.update_player1();
+        let (dir, buttons) = self.dummy_inputs(state);
+        inputs.player2.skip();
+        inputs.player2.append_input(0, dir, buttons);
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        let lesson = self.lesson();
+        if lesson == Lesson::Done
+            && ButtonFlag::L.intersects(state.player1_inputs.just_pressed_buttons())
+        {
+            return Ok(Some(Scenes::MainMenu(MainMenu::new())));
+        }
+
+        if let Some(new_gameplay_scene) = self.scene.update(context, state) {
+            self.scene.exit(context, state);
+            self.scene = new_gameplay_scene;
+            self.scene.enter(context, state);
+        }
+        // Nobody is meant to win, a finished match just starts over
+        if let GameplayScenes::Exit(_) = self.scene {
+            self.scene = GameplayScenes::new_round_start((0, 0));
+            self.scene.enter(context, state);
+        }
+
+        if self.cleared > 0 {
+            self.cleared -= 1;
+            if self.cleared == 0 {
+                self.lesson += 1;
+                self.progress = 0;
+            }
+        } else if let GameplayScenes::DuringRound(_) = self.scene {
+            self.check_lesson(context, state);
+        }
+
+        Ok(None)
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        self.scene.render(canvas, global_textures, context, state)?;
+
+        let (w, h) = canvas.window().size();
+        let (w, h) = (w as f32, h as f32);
+        let scale = h / 200.0;
+        let lesson = self.lesson();
+        let y = h / 5.0;
+        let (label, color) = if self.cleared > 0 {
+            ("Nice!", Color::YELLOW)
+        } else {
+            (lesson.prompt(), Color::WHITE)
+        };
+        let label = context.strings.get(label);
+        draw_text_centered(canvas, label, w / 2.0, y, scale, color)?;
+        if lesson != Lesson::Done {
+            draw_text_centered(
+                canvas,
+                &context.strings.fill(
+                    "Lesson {}/{}   {}/{}",
+                    &[
+                        &(self.lesson + 1),
+                        &(LESSONS.len() - 1),
+                        &self.progress.min(lesson.goal()),
+                        &lesson.goal(),
+                    ],
+                ),
+                w / 2.0,
+                y + text_height(scale) * 2.0,
+                scale,
+                Color::GRAY,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn exit(&mut self, context: &GameContext, _inputs: &mut PlayerInputs, state: &mut GameState) {
+        self.scene.exit(context, state);
+    }
+}
+
+impl Tutorial {
+    pub fn new() -> Self {
+        Self {
+            scene: GameplayScenes::new_round_start((0, 0)),
+            lesson: 0,
+            progress: 0,
+            performed: false,
+            cleared: 0,
+            dummy_cooldown: DUMMY_ATTACK_COOLDOWN,
+        }
+    }
+
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.scene.cinematic(state)
+    }
+
+    fn lesson(&self) -> Lesson {
+        LESSONS[self.lesson.min(LESSONS.len() - 1)]
+    }
+
+    fn check_lesson(&mut self, context: &GameContext, state: &GameState) {
+        let lesson = self.lesson();
+        let performed = lesson.performed(context, state);
+        let counts = match lesson {
+            Lesson::WalkForward | Lesson::WalkBack => performed,
+            _ => performed && !self.performed,
+        };
+        self.performed = performed;
+        if counts {
+            self.progress += 1;
+            if self.progress >= lesson.goal() {
+                self.cleared = CLEARED_FRAMES;
+            }
+        }
+    }
+
+    /// Stands still, except in the block lesson where it walks in and jabs every so often
+    fn dummy_inputs(&mut self, state: &GameState) -> (Direction, ButtonFlag) {
+        if self.lesson() != Lesson::Block || self.cleared > 0 {
+            return (Direction::Neutral, ButtonFlag::NONE);
+        }
+        if self.dummy_cooldown > 0 {
+            self.dummy_cooldown -= 1;
+            return (Direction::Neutral, ButtonFlag::NONE);
+        }
+        let distance = (state.player1.pos().x - state.player2.pos().x).abs();
+        if distance > DUMMY_ATTACK_RANGE {
+            let forward = match state.player2.side() {
+                Side::Left => Direction::Right,
+                Side::Right => Direction::Left,
+            };
+            return (forward, ButtonFlag::NONE);
+        }
+        self.dummy_cooldown = DUMMY_ATTACK_COOLDOWN;
+        (Direction::Neutral, ButtonFlag::L)
+    }
+}
@@ -1,20 +1,23 @@
 use candle_core::Device;
-use candle_nn::VarMap;
 
 use crate::game::{
     GameContext, GameState, PlayerInputs,
-    ai::{get_agent_action, load_model, serialize_observation_inv, take_agent_turn},
+    ai::{
+        ActionRepeat, ObservationBuffer, load_inference_model, sample_agent_actions,
+        take_agent_turn,
+    },
     scene::{
         Scene, Scenes,
         gameplay::{GameplayScene, GameplayScenes},
-        main_menu::MainMenu,
+        victory::Victory,
     },
 };
 
 pub struct VersesAi {
     scene: GameplayScenes,
-    _var_map: VarMap,
     ai_agent: candle_nn::Sequential,
+    observations: ObservationBuffer,
+    action_repeat: ActionRepeat,
     device: Device,
     rng: rand::rngs::ThreadRng,
 }
@@ -34,19 +37,32 @@ impl Scene for VersesAi {
         inputs.update_player1();
 
         if let GameplayScenes::DuringRound(during_round) = &self.scene {
-            let timer = during_round.timer();
-            let observation = serialize_observation_inv(context, state, timer, &self.device)
-                .map_err(|err| err.to_string())?;
+            if self.action_repeat.ready() {
+                // Only player2's point of view is needed
+                self.observations
+                    .fill_row(context, state, during_round.timer(), 1);
+                let observation = self
+                    .observations
+                    .row_batch(1, &self.device)
+                    .map_err(|err| err.to_string())?;
 
-            let action = get_agent_action(&self.ai_agent, &observation, &mut self.rng)
-                .map_err(|err| err.to_string())?;
+                let mut action = [0];
+                sample_agent_actions(&self.ai_agent, &observation, &mut self.rng, &mut action)
+                    .map_err(|err| err.to_string())?;
+                self.action_repeat.set(action[0]);
+            }
+            let action = self.action_repeat.tick();
             take_agent_turn(&mut inputs.player2, &mut state.player2_inputs, action);
         }
 
         Ok(())
     }
 
-    fn update(&mut self, context: &GameContext, state: &mut GameState) -> Result<Option<Scenes>, String> {
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
         if let Some(new_gameplay_scene) = self.scene.update(context, state) {
             self.scene.exit(context, state);
             self.scene = new_gameplay_scene;
@@ -54,7 +70,7 @@ impl Scene for VersesAi {
         }
 
         match self.scene {
-            GameplayScenes::Exit => Ok(Some(Scenes::MainMenu(MainMenu::new()))),
+            GameplayScenes::Exit(results) => Ok(Some(Scenes::Victory(Victory::new(results)))),
             _ => Ok(None),
         }
     }
@@ -75,17 +91,30 @@ impl Scene for VersesAi {
 }
 
 impl VersesAi {
-    pub fn new(model_path: &str) -> Result<Self, String> {
-        let device = Device::Cpu;
-        let (_var_map, ai_agent) = load_model(model_path, &device)
-            .map_err(|err| err.to_string())?;
+    pub fn new(model_path: &str, action_repeat: usize) -> Result<Self, String> {
+        let ai_agent =
+            load_inference_model(model_path, &Device::Cpu).map_err(|err| err.to_string())?;
+        Ok(Self::from_agent(ai_agent, action_repeat))
+    }
 
-        Ok(Self {
+    /// Plays against a model that is already loaded
+    pub fn from_agent(ai_agent: candle_nn::Sequential, action_repeat: usize) -> Self {
+        Self {
             scene: GameplayScenes::new_round_start((0, 0)),
-            _var_map,
             ai_agent,
-            device,
+            observations: ObservationBuffer::new(),
+            action_repeat: ActionRepeat::new(action_repeat),
+            device: Device::Cpu,
             rng: rand::rng(),
-        })
+        }
+    }
+
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.scene.cinematic(state)
+    }
+
+    /// True once the match is over and the human on the left won it
+    pub fn player_won(&self) -> bool {
+        matches!(self.scene, GameplayScenes::Exit(results) if results.score.0 > results.score.1)
     }
 }
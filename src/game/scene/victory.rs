@@ -0,0 +1,151 @@
+use sdl3::{pixels::Color, render::FPoint};
+
+use crate::game::{
+    GameContext, GameState, PlayerInputs,
+    input::ButtonFlag,
+    render::text::draw_text_centered,
+    scene::{Scene, Scenes, gameplay::MatchResults, main_menu::MainMenu},
+};
+
+/// Frames before a button press can leave, so mashing out of the last combo doesn't skip it
+const MIN_DISPLAY_FRAMES: usize = 60;
+/// Where the winner stands, left of the stats
+const POSE_POS: FPoint = FPoint { x: -250.0, y: 0.0 };
+
+/// Results of the match over the winner's win pose, L goes back to the main menu
+pub struct Victory {
+    results: MatchResults,
+    frame: usize,
+}
+
+impl Scene for Victory {
+    fn enter(
+        &mut self,
+        _context: &GameContext,
+        _inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) {
+    }
+
+    fn handle_input(
+        &mut self,
+        _context: &GameContext,
+        inputs: &mut PlayerInputs,
+        _state: &mut GameState,
+    ) -> Result<(), String> {
+        inputs.update_player1();
+        inputs.skip_player2();
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        _context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        self.frame += 1;
+        if self.frame >= MIN_DISPLAY_FRAMES
+            && ButtonFlag::L.intersects(state.player1_inputs.just_pressed_buttons())
+        {
+            return Ok(Some(Scenes::MainMenu(MainMenu::new())));
+        }
+        Ok(None)
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        _state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        let (w, h) = canvas.window().size();
+        let w = w as f32;
+        let h = h as f32;
+
+        let strings = &context.strings;
+        let (winner, winner_label) = if self.results.score.0 > self.results.score.1 {
+            (&context.player1, strings.fill("PLAYER {} WINS", &[&1]))
+        } else {
+            (&context.player2, strings.fill("PLAYER {} WINS", &[&2]))
+        };
+        let win_pose = winner.win_pose();
+        context.camera.render_animation(
+            canvas,
+            global_textures,
+            POSE_POS,
+            win_pose,
+            self.frame % win_pose.get_frame_count(),
+        )?;
+
+        let title_scale = h / 100.0;
+        let row_scale = h / 180.0;
+        let stats_x = w * 0.65;
+        draw_text_centered(
+            canvas,
+            &winner_label,
+            w / 2.0,
+            h / 8.0,
+            title_scale,
+            Color::WHITE,
+        )?;
+        draw_text_centered(
+            canvas,
+            winner.name(),
+            w / 2.0,
+            h / 8.0 + title_scale * 10.0,
+            row_scale,
+            Color::GRAY,
+        )?;
+
+        let (score, stats) = (self.results.score, self.results.stats);
+        let reaction = |player| {
+            stats
+                .reaction(player)
+                .map_or(String::from("-"), |frames| format!("{frames:.1}F"))
+        };
+        let rows = [
+            strings.fill("SCORE {} - {}", &[&score.0, &score.1]),
+            strings.fill(
+                "MAX COMBO {} - {}",
+                &[&stats.max_combo[0], &stats.max_combo[1]],
+            ),
+            strings.fill(
+                "DAMAGE {} - {}",
+                &[&stats.damage[0].round(), &stats.damage[1].round()],
+            ),
+            strings.fill("THROWS {} - {}", &[&stats.throws[0], &stats.throws[1]]),
+            strings.fill(
+                "PUNISHES {} - {}",
+                &[&stats.punishes[0], &stats.punishes[1]],
+            ),
+            strings.fill("REACTION {} - {}", &[&reaction(0), &reaction(1)]),
+        ];
+        for (row, label) in rows.iter().enumerate() {
+            let y = h * 0.35 + row as f32 * h / 14.0;
+            draw_text_centered(canvas, label, stats_x, y, row_scale, Color::WHITE)?;
+        }
+
+        if self.frame >= MIN_DISPLAY_FRAMES {
+            draw_text_centered(
+                canvas,
+                strings.get("L: Continue"),
+                w / 2.0,
+                h * 5.0 / 6.0,
+                row_scale / 1.5,
+                Color::GRAY,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn exit(&mut self, _context: &GameContext, _inputs: &mut PlayerInputs, _state: &mut GameState) {
+    }
+}
+
+impl Victory {
+    pub fn new(results: MatchResults) -> Self {
+        Self { results, frame: 0 }
+    }
+}
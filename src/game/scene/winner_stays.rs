@@ -0,0 +1,120 @@
+use sdl3::pixels::Color;
+
+use crate::game::{
+    GameContext, GameState, PlayerInputs,
+    locale::Strings,
+    render::text::{draw_text_centered, text_height},
+    scene::{
+        Scene, Scenes,
+        character_select::{AfterSelect, CharacterSelect},
+        local_play::LocalPlay,
+    },
+};
+
+/// Who holds the cabinet and for how many games
+#[derive(Clone, Copy, Default)]
+pub struct Session {
+    /// Player who won the last game
+    pub champion: Option<usize>,
+    /// Games the champion has won in a row
+    pub streak: u32,
+}
+
+impl Session {
+    fn record(&mut self, winner: usize) {
+        if self.champion == Some(winner) {
+            self.streak += 1;
+        } else {
+            self.champion = Some(winner);
+            self.streak = 1;
+        }
+    }
+
+    /// "P1 STREAK 3" style, None before the first game is won
+    pub fn label(&self, strings: &Strings) -> Option<String> {
+        self.champion
+            .map(|champion| strings.fill("P{} STREAK {}", &[&(champion + 1), &self.streak]))
+    }
+}
+
+/// Local play that goes back to character select after each game, the winner keeps their pick
+pub struct WinnerStays {
+    play: LocalPlay,
+    session: Session,
+}
+
+impl Scene for WinnerStays {
+    fn enter(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
+        self.play.enter(context, inputs, state);
+    }
+
+    fn handle_input(
+        &mut self,
+        context: &GameContext,
+        inputs: &mut PlayerInputs,
+        state: &mut GameState,
+    ) -> Result<(), String> {
+        self.play.handle_input(context, inputs, state)
+    }
+
+    fn update(
+        &mut self,
+        context: &mut GameContext,
+        state: &mut GameState,
+    ) -> Result<Option<Scenes>, String> {
+        let next = self.play.update(context, state)?;
+        // Quitting from the pause menu ends the session without a winner
+        let Some(winner) = self.play.winner() else {
+            return Ok(next);
+        };
+        self.session.record(winner);
+        Ok(Some(Scenes::CharacterSelect(CharacterSelect::new(
+            AfterSelect::WinnerStays(self.session),
+        ))))
+    }
+
+    fn render(
+        &self,
+        canvas: &mut sdl3::render::Canvas<sdl3::video::Window>,
+        global_textures: &[sdl3::render::Texture],
+        context: &GameContext,
+        state: &GameState,
+    ) -> Result<(), sdl3::Error> {
+        self.play.render(canvas, global_textures, context, state)?;
+        if let Some(label) = self.session.label(&context.strings) {
+            let (w, h) = canvas.window().size();
+            let (w, h) = (w as f32, h as f32);
+            let scale = h / 250.0;
+            draw_text_centered(
+                canvas,
+                &label,
+                w / 2.0,
+                h - text_height(scale) * 3.0,
+                scale,
+                Color::YELLOW,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn exit(&mut self, context: &GameContext, inputs: &mut PlayerInputs, state: &mut GameState) {
+        self.play.exit(context, inputs, state);
+    }
+}
+
+impl WinnerStays {
+    pub fn new(session: Session) -> Self {
+        Self {
+            play: LocalPlay::new(),
+            session,
+        }
+    }
+
+    pub fn cinematic(&self, state: &GameState) -> bool {
+        self.play.cinematic(state)
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.play.toggle_pause();
+    }
+}
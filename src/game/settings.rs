@@ -0,0 +1,227 @@
+use sdl3::keyboard::Keycode;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{
+    MAX_ROLLBACK_FRAMES,
+    display::DisplaySettings,
+    input::{KeyBindings, PLAYER1_BINDINGS, PLAYER2_BINDINGS},
+};
+
+/// Steps between silent and full volume
+pub const VOLUME_STEPS: u8 = 10;
+/// Most frames of input delay the netplay settings offer
+pub const MAX_INPUT_DELAY: usize = 8;
+/// Most frames a rollback can rewind, the snapshot history holds the frame it rewinds from too
+pub const MAX_ROLLBACK: usize = MAX_ROLLBACK_FRAMES - 1;
+/// Where the display settings were kept before they moved into the settings file
+const LEGACY_DISPLAY_PATH: &str = "./display.json";
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AudioSettings {
+    /// Out of `VOLUME_STEPS`
+    pub music_volume: u8,
+    /// Out of `VOLUME_STEPS`
+    pub effects_volume: u8,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            music_volume: VOLUME_STEPS,
+            effects_volume: VOLUME_STEPS,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn music(&self) -> f32 {
+        self.music_volume.min(VOLUME_STEPS) as f32 / VOLUME_STEPS as f32
+    }
+
+    pub fn effects(&self) -> f32 {
+        self.effects_volume.min(VOLUME_STEPS) as f32 / VOLUME_STEPS as f32
+    }
+}
+
+/// Preferences traded during the handshake, both peers play with what `agree` settles on
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetplaySettings {
+    /// Frames local inputs are held back online, more delay means shorter rollbacks
+    pub input_delay: usize,
+    /// Frames a rollback may rewind, a peer further behind than that drops the match
+    pub max_rollback: usize,
+}
+
+impl Default for NetplaySettings {
+    fn default() -> Self {
+        Self {
+            input_delay: 3,
+            max_rollback: MAX_ROLLBACK,
+        }
+    }
+}
+
+impl NetplaySettings {
+    /// The longer delay and the shorter rollback, peers' values are clamped to what's offered
+    pub fn agree(self, peer: Self) -> Self {
+        Self {
+            input_delay: self.input_delay.max(peer.input_delay).min(MAX_INPUT_DELAY),
+            max_rollback: self
+                .max_rollback
+                .min(peer.max_rollback)
+                .clamp(1, MAX_ROLLBACK),
+        }
+    }
+}
+
+/// Everything picked in the settings menu, sections missing from the file keep their defaults
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub display: DisplaySettings,
+    #[serde(default)]
+    pub audio: AudioSettings,
+    /// Player1's then player2's keys, online the local player always uses player1's
+    #[serde(default)]
+    pub controls: Controls,
+    /// Named key layouts either player can switch to from the controls menu
+    #[serde(default)]
+    pub profiles: Vec<BindingProfile>,
+    /// Index into `profiles` of the layout each player plays with, rebinding a key edits it
+    #[serde(default)]
+    pub picked_profiles: [usize; 2],
+    #[serde(default)]
+    pub netplay: NetplaySettings,
+    /// String table the UI text is shown in, empty for the built in English
+    #[serde(default)]
+    pub language: String,
+    #[serde(default)]
+    pub last_played: LastPlayed,
+}
+
+/// Characters and stage of the latest match, the next run starts on them
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct LastPlayed {
+    /// Roster indices, None until a match has been played
+    pub characters: Option<[usize; 2]>,
+    pub palettes: [usize; 2],
+    pub stage: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Controls(pub [KeyBindings; 2]);
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self([PLAYER1_BINDINGS, PLAYER2_BINDINGS])
+    }
+}
+
+/// Key layout saved under a name, like "hitbox layout" or "keyboard P2"
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BindingProfile {
+    pub name: String,
+    pub bindings: KeyBindings,
+}
+
+impl Settings {
+    /// Missing or broken settings fall back to the defaults, missing ones are written out.
+    /// Without a display section the old display file is read in once and saved over
+    pub fn load(path: &str) -> Self {
+        let Ok(src) = std::fs::read_to_string(path) else {
+            return Self::default()
+                .with_legacy_display()
+                .with_profiles()
+                .saved(path);
+        };
+        let settings = serde_json::from_str::<serde_json::Value>(&src).and_then(|json| {
+            let has_display = json.get("display").is_some();
+            serde_json::from_value::<Self>(json).map(|settings| (settings, has_display))
+        });
+        match settings {
+            Ok((settings, true)) => settings.with_profiles(),
+            Ok((settings, false)) => settings.with_legacy_display().with_profiles().saved(path),
+            Err(err) => {
+                println!("[WARNING] Ignoring settings '{path}': {err}");
+                Self::default().with_profiles()
+            }
+        }
+    }
+
+    /// Takes the display settings from the file they were kept in before this one
+    fn with_legacy_display(mut self) -> Self {
+        let Ok(src) = std::fs::read_to_string(LEGACY_DISPLAY_PATH) else {
+            return self;
+        };
+        match serde_json::from_str(&src) {
+            Ok(display) => self.display = display,
+            Err(err) => {
+                println!("[WARNING] Ignoring display settings '{LEGACY_DISPLAY_PATH}': {err}")
+            }
+        }
+        self
+    }
+
+    /// Writes out settings that load made up or carried over
+    fn saved(self, path: &str) -> Self {
+        if let Err(err) = self.save(path)
+            && cfg!(feature = "debug")
+        {
+            println!("[WARNING] Failed to save settings: {err}");
+        }
+        self
+    }
+
+    /// Files from before profiles get one per player made from their keys, then each player
+    /// plays with the keys of the profile they picked
+    fn with_profiles(mut self) -> Self {
+        if self.profiles.is_empty() {
+            self.profiles = (0..2)
+                .map(|player| BindingProfile {
+                    name: format!("Keyboard P{}", player + 1),
+                    bindings: self.controls.0[player],
+                })
+                .collect();
+            self.picked_profiles = [0, 1];
+        }
+        for player in 0..2 {
+            let picked = self.picked_profiles[player].min(self.profiles.len() - 1);
+            self.pick_profile(player, picked);
+        }
+        self
+    }
+
+    /// Switches the player onto a saved layout
+    pub fn pick_profile(&mut self, player: usize, profile: usize) {
+        self.picked_profiles[player] = profile;
+        self.controls.0[player] = self.profiles[profile].bindings;
+    }
+
+    /// Rebinds a key for the player and in the profile they play with
+    pub fn rebind(&mut self, player: usize, slot: usize, key: Keycode) {
+        self.controls.0[player].rebind(slot, key);
+        let profile = self.picked_profiles[player];
+        self.profiles[profile].bindings = self.controls.0[player];
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let src = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+        std::fs::write(path, src).map_err(|err| format!("Failed to write '{path}': {err}"))
+    }
+}
+
+#[test]
+fn test_rebind_edits_picked_profile() {
+    let mut settings = Settings::default().with_profiles();
+    settings.pick_profile(0, 1);
+
+    settings.rebind(0, 0, Keycode::F1);
+
+    assert_eq!(settings.controls.0[0], settings.profiles[1].bindings);
+    assert_eq!(
+        settings.profiles[1].bindings.keys().next(),
+        Some(Keycode::F1)
+    );
+    assert_eq!(settings.profiles[0].bindings, PLAYER1_BINDINGS);
+}
@@ -1,44 +1,70 @@
 use sdl3::{
-    render::{Canvas, FPoint, Texture, TextureCreator},
-    video::{Window, WindowContext},
+    render::{Canvas, FPoint, FRect, Texture},
+    video::Window,
 };
 
-use crate::game::render::load_texture;
+use crate::game::render::TextureRegion;
 
-const STATIC_LAYERS: &[&str] = &[
-    "./resources/stage1/1.png",
-    "./resources/stage1/2.png",
-    "./resources/stage1/3.png",
-    "./resources/stage1/4.png",
-    "./resources/stage1/5.png",
-    "./resources/stage1/6.png",
-    "./resources/stage1/7.png",
-    "./resources/stage1/8.png",
-];
+/// Distance from the stage edge that still counts as the corner
+pub const DEFAULT_CORNER_DISTANCE: f32 = 40.0;
+
+/// Background image covering the viewport
+pub struct Layer {
+    pub region: TextureRegion,
+    /// Share of the image's width kept off screen and panned across as the camera goes wall to
+    /// wall, 0 holds it still
+    pub parallax: f32,
+}
 
 pub struct Stage {
-    layers: Vec<usize>,
+    name: String,
+    /// Drawn back to front over the whole viewport
+    layers: Vec<Layer>,
+    /// Players are kept within `width` of the center on either side
     width: f32,
     height: f32,
+    /// Game units the ground is drawn above the default floor line
+    floor_height: f32,
+    music: Option<String>,
+    /// Distance from a wall that counts as cornered
+    corner_distance: f32,
 }
 
 impl Stage {
-    pub fn init<'a>(
-        texture_creator: &'a TextureCreator<WindowContext>,
-        global_textures: &mut Vec<Texture<'a>>,
-    ) -> Result<Stage, String> {
-        let mut layers = Vec::new();
-
-        for layer in STATIC_LAYERS {
-            let texture_index = load_texture(texture_creator, global_textures, layer)?;
-            layers.push(texture_index);
+    pub fn new(
+        name: String,
+        layers: Vec<Layer>,
+        width: f32,
+        height: f32,
+        floor_height: f32,
+        music: Option<String>,
+    ) -> Self {
+        Self {
+            name,
+            layers,
+            width,
+            height,
+            floor_height,
+            music,
+            corner_distance: DEFAULT_CORNER_DISTANCE,
         }
+    }
 
-        Ok(Self {
-            layers,
-            width: 420.0,
-            height: 600.0,
-        })
+    pub fn with_corner_distance(mut self, corner_distance: f32) -> Self {
+        self.corner_distance = corner_distance;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn floor_height(&self) -> f32 {
+        self.floor_height
+    }
+
+    pub fn music(&self) -> Option<&str> {
+        self.music.as_deref()
     }
 
     pub fn width(&self) -> f32 {
@@ -53,14 +79,24 @@ impl Stage {
         &self,
         canvas: &mut Canvas<Window>,
         global_textures: &[Texture],
+        viewport: FRect,
+        pan: f32,
     ) -> Result<(), sdl3::Error> {
-        for &layer in &self.layers {
-            canvas.copy(&global_textures[layer], None, None)?;
+        for layer in &self.layers {
+            let mut src = layer.region.src;
+            let hidden = src.w * layer.parallax.clamp(0.0, 1.0);
+            src.x += hidden * (pan + 1.0) / 2.0;
+            src.w -= hidden;
+            canvas.copy(&global_textures[layer.region.texture], src, viewport)?;
         }
 
         Ok(())
     }
 
+    pub fn in_corner(&self, pos: FPoint) -> bool {
+        self.width - pos.x.abs() <= self.corner_distance
+    }
+
     pub fn bind_pos(&self, pos: FPoint) -> FPoint {
         FPoint::new(pos.x.clamp(-self.width, self.width), pos.y)
     }
@@ -0,0 +1,76 @@
+use sdl3::{
+    pixels::Color,
+    render::{BlendMode, Canvas, FRect},
+    video::Window,
+};
+
+use crate::game::scene::Scenes;
+
+/// Ticks each half of a transition takes
+const FADE_FRAMES: usize = 12;
+
+enum Phase {
+    /// Darkening over the old scene, which holds still, before the new one is swapped in
+    Out(Scenes),
+    /// Lifting off the new scene, which already runs
+    In,
+}
+
+/// Fades through black between scenes instead of cutting
+pub struct Transition {
+    phase: Phase,
+    frame: usize,
+}
+
+impl Transition {
+    /// Fades out before `next` is swapped in, then back in on it
+    pub fn fade_to(next: Scenes) -> Self {
+        Self {
+            phase: Phase::Out(next),
+            frame: 0,
+        }
+    }
+
+    /// Fades in on a scene that was swapped in straight away
+    pub fn fade_in() -> Self {
+        Self {
+            phase: Phase::In,
+            frame: 0,
+        }
+    }
+
+    pub fn fading_out(&self) -> bool {
+        matches!(self.phase, Phase::Out(_))
+    }
+
+    pub fn finished(&self) -> bool {
+        matches!(self.phase, Phase::In) && self.frame >= FADE_FRAMES
+    }
+
+    /// Advances a tick, hands back the next scene once the screen is fully dark
+    pub fn update(&mut self) -> Option<Scenes> {
+        self.frame += 1;
+        if self.frame < FADE_FRAMES || !self.fading_out() {
+            return None;
+        }
+        self.frame = 0;
+        match std::mem::replace(&mut self.phase, Phase::In) {
+            Phase::Out(next) => Some(next),
+            Phase::In => None,
+        }
+    }
+
+    pub fn render(&self, canvas: &mut Canvas<Window>) -> Result<(), sdl3::Error> {
+        let progress = (self.frame as f32 / FADE_FRAMES as f32).min(1.0);
+        let darkness = match self.phase {
+            Phase::Out(_) => progress,
+            Phase::In => 1.0 - progress,
+        };
+        let (w, h) = canvas.window().size();
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, (darkness * 255.0) as u8));
+        canvas.fill_rect(FRect::new(0.0, 0.0, w as f32, h as f32))?;
+        canvas.set_blend_mode(BlendMode::None);
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+use sdl3::{
+    keyboard::Keycode,
+    pixels::Color,
+    render::{Canvas, FRect},
+    video::Window,
+};
+use serde_json::{Value, json};
+
+use crate::game::{GameContext, character};
+
+const DMG_STEP: f32 = 5.0;
+/// Written next to the character's config so hot reloading doesn't pick up half tuned values
+const DUMP_SUFFIX: &str = ".tuned.json";
+const PLAYER_COLORS: [Color; 2] = [Color::RED, Color::BLUE];
+
+#[derive(Clone, Copy)]
+enum TuningField {
+    Dmg,
+    HitStun,
+    BlockStun,
+    CancelStart,
+    CancelEnd,
+}
+
+const FIELDS: [TuningField; 5] = [
+    TuningField::Dmg,
+    TuningField::HitStun,
+    TuningField::BlockStun,
+    TuningField::CancelStart,
+    TuningField::CancelEnd,
+];
+
+impl TuningField {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Dmg => "dmg",
+            Self::HitStun => "hit stun",
+            Self::BlockStun => "block stun",
+            Self::CancelStart => "cancel window start",
+            Self::CancelEnd => "cancel window end",
+        }
+    }
+
+    /// Value drawn as a full bar
+    fn scale(self) -> f32 {
+        match self {
+            Self::Dmg => 200.0,
+            _ => 60.0,
+        }
+    }
+}
+
+/// Debug overlay for balancing moves in training mode, edits go straight into the loaded character
+///
+/// F1 opens it, F2 swaps player, F3/F4 pick the move, F5/F6 pick the value, F7/F8 lower and raise it
+/// and F9 dumps the tuned config
+#[derive(Default)]
+pub struct Tuning {
+    open: bool,
+    player: usize,
+    state: usize,
+    field: usize,
+    /// Move names of the tuned player's config, read when the overlay opens
+    move_names: Vec<String>,
+}
+
+impl Tuning {
+    pub fn handle_keypress(&mut self, keycode: Keycode, context: &mut GameContext) {
+        if keycode == Keycode::F1 {
+            self.open = !self.open;
+            if self.open {
+                self.load_move_names(context);
+                self.print(context);
+            }
+            return;
+        }
+        if !self.open {
+            return;
+        }
+
+        let states_len = self.character(context).states_len();
+        self.state = self.picked_state(self.character(context));
+        match keycode {
+            Keycode::F2 => {
+                self.player = 1 - self.player;
+                self.state = 0;
+                self.load_move_names(context);
+            }
+            Keycode::F3 => self.state = (self.state + states_len - 1) % states_len,
+            Keycode::F4 => self.state = (self.state + 1) % states_len,
+            Keycode::F5 => self.field = (self.field + FIELDS.len() - 1) % FIELDS.len(),
+            Keycode::F6 => self.field = (self.field + 1) % FIELDS.len(),
+            Keycode::F7 => self.adjust(context, -1),
+            Keycode::F8 => self.adjust(context, 1),
+            Keycode::F9 => match self.dump(context) {
+                Ok(path) => println!("Dumped tuned config to '{path}'"),
+                Err(err) => println!("[WARNING] Failed to dump tuned config: {err}"),
+            },
+            _ => return,
+        }
+        self.print(context);
+    }
+
+    /// Panel at the bottom of the screen, one bar per value with the picked one in the player's color
+    pub fn render(
+        &self,
+        canvas: &mut Canvas<Window>,
+        context: &GameContext,
+    ) -> Result<(), sdl3::Error> {
+        if !self.open {
+            return Ok(());
+        }
+
+        let (screen_w, screen_h) = canvas.window().size();
+        let panel = FRect::new(
+            screen_w as f32 * 0.3,
+            screen_h as f32 * 0.65,
+            screen_w as f32 * 0.4,
+            screen_h as f32 * 0.25,
+        );
+        canvas.set_draw_color(Color::BLACK);
+        canvas.fill_rect(panel)?;
+
+        let row_h = panel.h / (FIELDS.len() + 1) as f32;
+        let pad = row_h / 4.0;
+        let bar_w = panel.w - pad * 2.0;
+
+        // Marker along the top row for where the move sits in the movelist
+        let character = self.character(context);
+        let marker_w = bar_w / character.states_len() as f32;
+        canvas.set_draw_color(Color::GRAY);
+        canvas.draw_rect(FRect::new(panel.x + pad, panel.y + pad, bar_w, row_h - pad))?;
+        canvas.set_draw_color(PLAYER_COLORS[self.player]);
+        canvas.fill_rect(FRect::new(
+            panel.x + pad + self.picked_state(character) as f32 * marker_w,
+            panel.y + pad,
+            marker_w,
+            row_h - pad,
+        ))?;
+
+        for (row, (field, value)) in FIELDS.into_iter().zip(self.values(context)).enumerate() {
+            let y = panel.y + (row + 1) as f32 * row_h + pad;
+            let color = if row == self.field {
+                PLAYER_COLORS[self.player]
+            } else {
+                Color::GRAY
+            };
+            canvas.set_draw_color(color);
+            canvas.draw_rect(FRect::new(panel.x + pad, y, bar_w, row_h - pad))?;
+            if let Some(value) = value {
+                let fill = (value / field.scale()).clamp(0.0, 1.0) * bar_w;
+                canvas.fill_rect(FRect::new(panel.x + pad, y, fill, row_h - pad))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn character<'a>(&self, context: &'a GameContext) -> &'a character::Context {
+        match self.player {
+            0 => &context.player1,
+            _ => &context.player2,
+        }
+    }
+
+    fn character_mut<'a>(&self, context: &'a mut GameContext) -> &'a mut character::Context {
+        match self.player {
+            0 => &mut context.player1,
+            _ => &mut context.player2,
+        }
+    }
+
+    /// Hot reloads and new matches can shrink the movelist under the overlay
+    fn picked_state(&self, character: &character::Context) -> usize {
+        self.state.min(character.states_len() - 1)
+    }
+
+    fn load_move_names(&mut self, context: &GameContext) {
+        let config = &context.character_configs[self.player];
+        self.move_names = read_config(config)
+            .ok()
+            .and_then(|json| {
+                json.get("moves")?.as_array().map(|moves| {
+                    moves
+                        .iter()
+                        .map(|mov| mov["name"].as_str().unwrap_or_default().to_string())
+                        .collect()
+                })
+            })
+            .unwrap_or_default();
+    }
+
+    /// Values of the first hit box, `None` when the move has none or no cancel window
+    fn values(&self, context: &GameContext) -> [Option<f32>; 5] {
+        let character = self.character(context);
+        let state = self.picked_state(character);
+        let hit_box = character.state_hit_boxes(state).first();
+        let cancel_window = character.cancel_window(state);
+        let frame = |frame: usize| (frame != usize::MAX).then_some(frame as f32);
+        [
+            hit_box.map(|hit_box| hit_box.dmg()),
+            hit_box
+                .map(|hit_box| hit_box.hit_stun())
+                .filter(|hit_stun| *hit_stun != u32::MAX as usize)
+                .map(|hit_stun| hit_stun as f32),
+            hit_box.map(|hit_box| hit_box.block_stun() as f32),
+            frame(cancel_window.start),
+            frame(cancel_window.end),
+        ]
+    }
+
+    /// Steps the picked value on every hit box of the move, moves without a cancel window keep none
+    fn adjust(&self, context: &mut GameContext, step: i32) {
+        let character = self.character_mut(context);
+        let step_frame = |frame: usize| {
+            if frame == usize::MAX {
+                frame
+            } else {
+                frame.saturating_add_signed(step as isize)
+            }
+        };
+        match FIELDS[self.field] {
+            TuningField::Dmg => {
+                for hit_box in character.state_hit_boxes_mut(self.state) {
+                    hit_box.set_dmg((hit_box.dmg() + step as f32 * DMG_STEP).max(0.0));
+                }
+            }
+            TuningField::HitStun => {
+                for hit_box in character.state_hit_boxes_mut(self.state) {
+                    hit_box.set_hit_stun(step_frame(hit_box.hit_stun()) as u32);
+                }
+            }
+            TuningField::BlockStun => {
+                for hit_box in character.state_hit_boxes_mut(self.state) {
+                    hit_box.set_block_stun(step_frame(hit_box.block_stun()) as u32);
+                }
+            }
+            TuningField::CancelStart => {
+                let cancel_window = character.cancel_window(self.state);
+                character.set_cancel_window(
+                    self.state,
+                    step_frame(cancel_window.start)..cancel_window.end,
+                );
+            }
+            TuningField::CancelEnd => {
+                let cancel_window = character.cancel_window(self.state);
+                character.set_cancel_window(
+                    self.state,
+                    cancel_window.start..step_frame(cancel_window.end),
+                );
+            }
+        }
+    }
+
+    fn print(&self, context: &GameContext) {
+        let character = self.character(context);
+        let move_name = self
+            .move_names
+            .get(self.picked_state(character))
+            .cloned()
+            .unwrap_or_else(|| self.picked_state(character).to_string());
+        let values = self
+            .values(context)
+            .into_iter()
+            .zip(FIELDS)
+            .map(|(value, field)| match value {
+                Some(value) => format!("{} {value}", field.name()),
+                None => format!("{} -", field.name()),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "[TUNING] {} '{move_name}': {values} (editing {})",
+            character.name(),
+            FIELDS[self.field].name()
+        );
+    }
+
+    /// Writes the player's config with the tuned values swapped in, returns where it went
+    fn dump(&self, context: &GameContext) -> Result<String, String> {
+        let config = &context.character_configs[self.player];
+        let mut json = read_config(config)?;
+        let Some(moves) = json.get_mut("moves").and_then(Value::as_array_mut) else {
+            return Err(format!("No moves in '{config}'"));
+        };
+
+        let character = self.character(context);
+        let frame = |frame: usize| {
+            if frame == usize::MAX {
+                Value::Null
+            } else {
+                frame.into()
+            }
+        };
+        for (state, mov) in moves.iter_mut().enumerate().take(character.states_len()) {
+            let json_boxes = mov
+                .get_mut("hit_boxes")
+                .and_then(Value::as_array_mut)
+                .into_iter()
+                .flatten()
+                .filter_map(|group| group.get_mut("boxes").and_then(Value::as_array_mut))
+                .flatten();
+            for (json_box, hit_box) in json_boxes.zip(character.state_hit_boxes(state)) {
+                json_box["dmg"] = (hit_box.dmg().round() as usize).into();
+                json_box["block_stun"] = hit_box.block_stun().into();
+                if hit_box.hit_stun() != u32::MAX as usize {
+                    json_box["hit_stun"] = hit_box.hit_stun().into();
+                }
+            }
+
+            let cancel_window = character.cancel_window(state);
+            mov["cancel_window"] = json!({
+                "start": frame(cancel_window.start),
+                "end": frame(cancel_window.end),
+            });
+        }
+
+        let path = format!("{}{DUMP_SUFFIX}", config.trim_end_matches(".json"));
+        let src = serde_json::to_string_pretty(&json).map_err(|err| err.to_string())?;
+        std::fs::write(&path, src).map_err(|err| format!("Failed to write '{path}': {err}"))?;
+        Ok(path)
+    }
+}
+
+fn read_config(config: &str) -> Result<Value, String> {
+    let src = std::fs::read_to_string(config)
+        .map_err(|err| format!("Failed to open: '{config}': {err}"))?;
+    serde_json::from_str(&src).map_err(|err| format!("Failed to parse: '{config}': {err}"))
+}
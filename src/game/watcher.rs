@@ -0,0 +1,82 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Files edited since the last check, sorted by what reloading them takes
+#[derive(Default)]
+pub struct Changes {
+    pub game_config: bool,
+    /// Players whose character config was edited
+    pub characters: [bool; 2],
+    /// Everything else, images are redrawn in place
+    pub textures: Vec<PathBuf>,
+}
+
+/// Watches the directory the game config sits in for saved edits
+pub struct AssetWatcher {
+    /// Stops watching once dropped
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    game_config: PathBuf,
+    /// Player1 and player2's configs as given, and canonicalized to compare against event paths
+    character_configs: [(String, Option<PathBuf>); 2],
+}
+
+impl AssetWatcher {
+    pub fn new(config: &str, character_configs: &[String; 2]) -> Result<Self, String> {
+        let game_config = std::fs::canonicalize(config)
+            .map_err(|err| format!("Failed to open: '{config}': {err}"))?;
+        let dir = game_config.parent().unwrap_or(Path::new("."));
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(|err| err.to_string())?;
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(|err| format!("Failed to watch '{}': {err}", dir.display()))?;
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            game_config,
+            character_configs: character_configs.clone().map(canonical_pair),
+        })
+    }
+
+    /// Drains the edits so far, `character_configs` are the configs player1 and player2 play
+    pub fn changes(&mut self, character_configs: &[String; 2]) -> Changes {
+        // Only canonicalized again once a new character is picked
+        for (watched, config) in self.character_configs.iter_mut().zip(character_configs) {
+            if watched.0 != *config {
+                *watched = canonical_pair(config.clone());
+            }
+        }
+        let mut changes = Changes::default();
+        let paths = self
+            .events
+            .try_iter()
+            .filter_map(Result::ok)
+            .filter(|event| matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)))
+            .flat_map(|event| event.paths);
+        for path in paths {
+            let characters = self
+                .character_configs
+                .each_ref()
+                .map(|(_, config)| config.as_ref() == Some(&path));
+            if path == self.game_config {
+                changes.game_config = true;
+            } else if characters.contains(&true) {
+                changes.characters[0] |= characters[0];
+                changes.characters[1] |= characters[1];
+            } else if !changes.textures.contains(&path) {
+                changes.textures.push(path);
+            }
+        }
+        changes
+    }
+}
+
+fn canonical_pair(config: String) -> (String, Option<PathBuf>) {
+    let canonical = std::fs::canonicalize(&config).ok();
+    (config, canonical)
+}
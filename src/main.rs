@@ -1,18 +1,128 @@
 mod game;
 mod ring_buf;
 
-use crate::game::Game;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::game::{
+    Game, LaunchOptions,
+    ai::{DQNVariant, TrainingMode},
+};
 
 const DEFAULT_SCREEN_WIDTH: u32 = 960;
 const DEFAULT_SCREEN_HEIGHT: u32 = 540;
-const SCREEN_SCALE_RATIO: f32 = 1.0;
+const DEFAULT_BENCHMARK_ROUNDS: usize = 100;
+
+#[derive(Parser)]
+#[command(name = "fighter", about = "A simple fighting game")]
+struct Args {
+    /// Game config to load characters, stages and menus from
+    #[arg(long, default_value = "./resources/config.json")]
+    config: String,
+    /// Start in fullscreen, whatever the settings say
+    #[arg(long)]
+    fullscreen: bool,
+    /// Window width, the saved resolution's otherwise
+    #[arg(long, requires = "height")]
+    width: Option<u32>,
+    /// Window height, the saved resolution's otherwise
+    #[arg(long, requires = "width")]
+    height: Option<u32>,
+    /// Train the AI agents
+    #[arg(long, requires = "headless")]
+    train: bool,
+    /// Trainer `--train` runs
+    #[arg(long, value_enum, default_value_t, requires = "train")]
+    train_mode: TrainingMode,
+    /// DQN improvements `--train-mode dqn` trains with
+    #[arg(long, value_enum, default_value_t, requires = "train")]
+    dqn_variant: DQNVariant,
+    /// Run without a window, for training and benchmarks
+    #[arg(long)]
+    headless: bool,
+    /// Start playing back a saved replay
+    #[arg(long, conflicts_with = "matchmaking_server")]
+    replay: Option<PathBuf>,
+    /// Load a character config without a window and report its problems, then exit
+    ///
+    /// Exits with 0 when it is clean, 1 when it fails to load and 2 when it loads with warnings
+    #[arg(long, value_name = "CHARACTER_JSON")]
+    check: Option<String>,
+    /// Write a starter character to ./resources/<NAME>/ to build a new one from, then exit
+    #[arg(long, value_name = "NAME", conflicts_with = "check")]
+    new_character: Option<String>,
+    /// Start on online play through the matchmaking server at this address
+    #[arg(long, value_name = "ADDR", alias = "connect")]
+    matchmaking_server: Option<String>,
+    /// Play online through this matchmaking environment of the game config, like "dev"
+    #[arg(long, value_name = "ENV", conflicts_with = "matchmaking_server")]
+    matchmaking: Option<String>,
+    /// Read configs, images and sounds out of this zip archive, files it lacks come from disk
+    #[arg(long, value_name = "FILE")]
+    archive: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play two AI models against each other without a window
+    Bench {
+        model1: String,
+        model2: String,
+        /// At least one
+        #[arg(
+            default_value_t = DEFAULT_BENCHMARK_ROUNDS,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        )]
+        rounds: usize,
+    },
+}
 
 fn main() {
+    let args = Args::parse();
     let screen_dim = (
-        (DEFAULT_SCREEN_WIDTH as f32 * SCREEN_SCALE_RATIO) as u32,
-        (DEFAULT_SCREEN_HEIGHT as f32 * SCREEN_SCALE_RATIO) as u32,
+        args.width.unwrap_or(DEFAULT_SCREEN_WIDTH),
+        args.height.unwrap_or(DEFAULT_SCREEN_HEIGHT),
     );
 
+    if let Some(config) = &args.check {
+        std::process::exit(check(config));
+    }
+
+    if let Some(name) = &args.new_character {
+        match game::new_character(name) {
+            Ok(config) => println!("Created '{config}', add it to the roster to play it"),
+            Err(err) => {
+                eprintln!("error: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::Bench {
+        model1,
+        model2,
+        rounds,
+    }) = &args.command
+    {
+        game::benchmark_headless(&args.config, screen_dim, model1, model2, *rounds)
+            .expect("Failed to benchmark AI");
+        return;
+    }
+
+    if args.headless {
+        if args.train {
+            game::train_headless(&args.config, screen_dim, args.train_mode, args.dqn_variant)
+                .expect("Failed to train AI");
+        } else {
+            eprintln!("Nothing to run headless, pass --train or use the bench command");
+        }
+        return;
+    }
+
     let sdl = sdl3::init().expect("Failed to init sdl");
     let video_subsystem = sdl.video().expect("Failed to init video subsystem");
     let window = video_subsystem
@@ -23,8 +133,25 @@ fn main() {
     let canvas = window.into_canvas();
     let texture_creator = canvas.texture_creator();
     let events = sdl.event_pump().expect("Failed to make event pump");
+    let gamepads = sdl.gamepad().expect("Failed to init gamepad subsystem");
 
-    let game = Game::init(&texture_creator, canvas, events, screen_dim);
+    let options = LaunchOptions {
+        config: args.config,
+        fullscreen: args.fullscreen,
+        resolution: args.width.zip(args.height),
+        replay: args.replay,
+        matchmaking_server: args.matchmaking_server,
+        matchmaking: args.matchmaking,
+        archive: args.archive,
+    };
+    let game = Game::init(
+        &texture_creator,
+        canvas,
+        events,
+        gamepads,
+        screen_dim,
+        options,
+    );
 
     if cfg!(feature = "debug") {
         println!("Game initaliazed");
@@ -34,3 +161,24 @@ fn main() {
 
     game.run();
 }
+
+/// Prints the character's problems, returns the exit code
+fn check(config: &str) -> i32 {
+    match game::check_character(config) {
+        Ok(warnings) if warnings.is_empty() => {
+            println!("'{config}': OK");
+            0
+        }
+        Ok(warnings) => {
+            for warning in &warnings {
+                println!("warning: {warning}");
+            }
+            println!("'{config}': {} warning(s)", warnings.len());
+            2
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            1
+        }
+    }
+}